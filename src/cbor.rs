@@ -0,0 +1,184 @@
+//! Minimal CBOR (RFC 8949) encoder and decoder for JSON values
+//!
+//! Integral numbers are encoded as integers, all others as double precision
+//! floats. Decoded by `ext/cbor.js` in the viewer, and by [decode] to verify
+//! generated documents.
+
+use jzon::JsonValue;
+
+/// Largest integer which can be represented exactly by a double
+const MAX_SAFE_INTEGER: f64 = 9007199254740991.0;
+
+/// Append a data item head (major type and argument)
+fn head(out: &mut Vec<u8>, major: u8, value: u64) {
+  let major = major << 5;
+  match value {
+    0..=23 => out.push(major | value as u8),
+    24..=0xff => out.extend([major | 24, value as u8]),
+    0x100..=0xffff => {
+      out.push(major | 25);
+      out.extend((value as u16).to_be_bytes());
+    }
+    0x10000..=0xffff_ffff => {
+      out.push(major | 26);
+      out.extend((value as u32).to_be_bytes());
+    }
+    _ => {
+      out.push(major | 27);
+      out.extend(value.to_be_bytes());
+    }
+  }
+}
+
+fn text(out: &mut Vec<u8>, value: &str) {
+  head(out, 3, value.len() as u64);
+  out.extend(value.as_bytes());
+}
+
+fn number(out: &mut Vec<u8>, value: f64) {
+  if value.fract() == 0.0 && value.abs() <= MAX_SAFE_INTEGER {
+    match value < 0.0 {
+      true => head(out, 1, (-value - 1.0) as u64),
+      false => head(out, 0, value as u64),
+    }
+  } else {
+    out.push(0xfb);
+    out.extend(value.to_be_bytes());
+  }
+}
+
+fn item(out: &mut Vec<u8>, value: &JsonValue) {
+  match value {
+    JsonValue::Null => out.push(0xf6),
+    JsonValue::Boolean(false) => out.push(0xf4),
+    JsonValue::Boolean(true) => out.push(0xf5),
+    JsonValue::Number(x) => number(out, (*x).into()),
+    JsonValue::Short(x) => text(out, x.as_str()),
+    JsonValue::String(x) => text(out, x),
+    JsonValue::Array(items) => {
+      head(out, 4, items.len() as u64);
+      items.iter().for_each(|x| item(out, x));
+    }
+    JsonValue::Object(object) => {
+      head(out, 5, object.len() as u64);
+      for (key, value) in object.iter() {
+        text(out, key);
+        item(out, value);
+      }
+    }
+  }
+}
+
+/// Encode a JSON value as CBOR
+pub(crate) fn encode(value: &JsonValue) -> Vec<u8> {
+  let mut out = Vec::new();
+  item(&mut out, value);
+  out
+}
+
+/// Take the next `len` bytes
+fn take<'a>(data: &'a [u8], pos: &mut usize, len: usize) -> Option<&'a [u8]> {
+  let bytes = data.get(*pos..pos.checked_add(len)?)?;
+  *pos += len;
+  Some(bytes)
+}
+
+fn decode_item(data: &[u8], pos: &mut usize) -> Option<JsonValue> {
+  let initial = take(data, pos, 1)?[0];
+  let (major, info) = (initial >> 5, initial & 31);
+  if major == 7 {
+    return match info {
+      20 => Some(false.into()),
+      21 => Some(true.into()),
+      22 => Some(JsonValue::Null),
+      27 => {
+        Some(f64::from_be_bytes(take(data, pos, 8)?.try_into().ok()?).into())
+      }
+      _ => None,
+    };
+  }
+  let value = match info {
+    0..=23 => info as u64,
+    24..=27 => take(data, pos, 1 << (info - 24))?
+      .iter()
+      .fold(0, |value, byte| (value << 8) | *byte as u64),
+    _ => return None,
+  };
+  match major {
+    0 => Some(value.into()),
+    1 => Some((-1 - i64::try_from(value).ok()?).into()),
+    3 => {
+      let bytes = take(data, pos, usize::try_from(value).ok()?)?;
+      Some(std::str::from_utf8(bytes).ok()?.into())
+    }
+    4 => {
+      let mut items = Vec::new();
+      for _ in 0..value {
+        items.push(decode_item(data, pos)?);
+      }
+      Some(JsonValue::Array(items))
+    }
+    5 => {
+      let mut object = jzon::object::Object::new();
+      for _ in 0..value {
+        let key = decode_item(data, pos)?;
+        object.insert(key.as_str()?, decode_item(data, pos)?);
+      }
+      Some(JsonValue::Object(object))
+    }
+    _ => None,
+  }
+}
+
+/// Decode CBOR as produced by [encode], returning [None] if it is invalid
+pub(crate) fn decode(data: &[u8]) -> Option<JsonValue> {
+  let mut pos = 0;
+  let value = decode_item(data, &mut pos)?;
+  (pos == data.len()).then_some(value)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_encode() {
+    // Examples from RFC 8949, Appendix A.
+    let hex = |value: JsonValue| {
+      encode(&value)
+        .iter()
+        .map(|x| format!("{:02x}", x))
+        .collect::<String>()
+    };
+    assert_eq!(hex(0.into()), "00");
+    assert_eq!(hex(23.into()), "17");
+    assert_eq!(hex(24.into()), "1818");
+    assert_eq!(hex(1000.into()), "1903e8");
+    assert_eq!(hex(1000000.into()), "1a000f4240");
+    assert_eq!(hex(1000000000000.0.into()), "1b000000e8d4a51000");
+    assert_eq!(hex((-1).into()), "20");
+    assert_eq!(hex((-1000).into()), "3903e7");
+    assert_eq!(hex(1.1.into()), "fb3ff199999999999a");
+    assert_eq!(hex(false.into()), "f4");
+    assert_eq!(hex(JsonValue::Null), "f6");
+    assert_eq!(hex("\u{fc}".into()), "62c3bc");
+    assert_eq!(hex(jzon::array![1, [2, 3]]), "8201820203");
+    assert_eq!(hex(jzon::object! { a: 1 }), "a1616101");
+  }
+
+  #[test]
+  fn test_decode() {
+    let value = jzon::object! {
+      a: [0, -24, 255, -256, 65536, 4294967296.0, 0.5, -1e300],
+      "ü": [true, false, null, "", "x".repeat(300)],
+    };
+    let data = encode(&value);
+    assert_eq!(decode(&data), Some(value));
+    assert_eq!(decode(&data[..data.len() - 1]), None);
+    assert_eq!(decode(&[0x00, 0x00]), None);
+    assert_eq!(
+      decode(&[0x7b, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff]),
+      None
+    );
+  }
+}
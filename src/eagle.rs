@@ -0,0 +1,649 @@
+//! Reader for Eagle XML board files
+//!
+//! Supports board files of Eagle 6 and later, which embed all packages used
+//! on the board. Coordinates are stored in millimeters. The Y axis is
+//! mirrored to point downwards.
+
+use crate::xml::{self, Element};
+use crate::{
+  BoardOutline, Drawing, DrawingKind, DrawingLayer, Footprint, GenerateError,
+  Hole, InputFormat, InteractiveHtmlBom, Layer, NetId, Pad, PadCorner,
+  PadShape, RefMap, SvgPathBuilder, Track, Via,
+};
+use std::collections::BTreeMap;
+
+/// Layer numbers of Eagle's predefined layers
+const TOP: u32 = 1;
+const BOTTOM: u32 = 16;
+const DIMENSION: u32 = 20;
+
+/// Approximate text width per character, relative to the text size
+const CHAR_WIDTH: f64 = 0.8;
+
+/// Maximum angle of the straight segments approximating curved tracks [°]
+const ARC_STEP: f64 = 15.0;
+
+/// Pad with its center and size in package coordinates
+type PackagePad = (Pad, (f64, f64), (f64, f64));
+
+/// Placement of an element
+struct Transform {
+  pos: (f64, f64),
+  /// Rotation angle [°], counterclockwise
+  rotation: f64,
+  mirror: bool,
+}
+
+impl Transform {
+  /// Placement of objects not belonging to an element
+  const BOARD: Transform = Transform {
+    pos: (0.0, 0.0),
+    rotation: 0.0,
+    mirror: false,
+  };
+
+  /// Parse a rotation like "R90" or "MR180"
+  fn new(pos: (f64, f64), rot: Option<&str>) -> Result<Transform, String> {
+    let rot = rot.unwrap_or("R0");
+    let invalid = || format!("invalid rotation \"{}\"", rot);
+    let (flags, angle) = rot.split_once('R').ok_or_else(invalid)?;
+    let angle: f64 = angle.parse().map_err(|_| invalid())?;
+    let mirror = flags.contains('M');
+    Ok(Transform {
+      pos,
+      // Mirrored elements are rotated clockwise as seen from the top.
+      rotation: if mirror { -angle } else { angle },
+      mirror,
+    })
+  }
+
+  /// Map a package coordinate to a board coordinate
+  fn map(&self, p: (f64, f64)) -> (f64, f64) {
+    let x = if self.mirror { -p.0 } else { p.0 };
+    let (sin, cos) = self.rotation.to_radians().sin_cos();
+    let (x, y) = (x * cos - p.1 * sin, x * sin + p.1 * cos);
+    (self.pos.0 + x + 0.0, -(self.pos.1 + y) + 0.0)
+  }
+
+  /// Map a package coordinate to a footprint-local viewer coordinate
+  fn local(&self, p: (f64, f64)) -> (f64, f64) {
+    (if self.mirror { -p.0 } else { p.0 } + 0.0, -p.1 + 0.0)
+  }
+
+  /// Map an arc angle of a package wire
+  fn arc(&self, angle: f64) -> f64 {
+    if self.mirror {
+      angle
+    } else {
+      -angle
+    }
+  }
+
+  /// Map a rotation angle of a package object
+  fn angle(&self, angle: f64) -> f64 {
+    self.rotation + if self.mirror { -angle } else { angle }
+  }
+
+  /// Map a package layer to the board side, if it is a top or bottom layer
+  fn side(&self, layer: u32) -> Option<Layer> {
+    let top = match layer {
+      TOP | 21 | 25 | 27 | 29 | 31 | 51 => true,
+      BOTTOM | 22 | 26 | 28 | 30 | 32 | 52 => false,
+      _ => return None,
+    };
+    Some(if top != self.mirror {
+      Layer::Front
+    } else {
+      Layer::Back
+    })
+  }
+
+  /// Map a package layer to a drawing layer
+  fn drawing_layer(&self, layer: u32) -> Option<DrawingLayer> {
+    let front = self.side(layer)? == Layer::Front;
+    Some(match layer {
+      21 | 22 | 25 | 26 | 27 | 28 if front => DrawingLayer::SilkscreenFront,
+      21 | 22 | 25 | 26 | 27 | 28 => DrawingLayer::SilkscreenBack,
+      51 | 52 if front => DrawingLayer::FabricationFront,
+      51 | 52 => DrawingLayer::FabricationBack,
+      TOP | BOTTOM if front => DrawingLayer::CopperFront,
+      TOP | BOTTOM => DrawingLayer::CopperBack,
+      _ => return None,
+    })
+  }
+}
+
+fn attr<'a>(element: &'a Element, name: &str) -> Result<&'a str, String> {
+  element
+    .attribute(name)
+    .ok_or_else(|| format!("<{}> without attribute \"{}\"", element.name, name))
+}
+
+fn number(element: &Element, name: &str) -> Result<f64, String> {
+  let value = attr(element, name)?;
+  value.parse().map_err(|_| {
+    format!("invalid {} \"{}\" of <{}>", name, value, element.name)
+  })
+}
+
+/// Get an optional number attribute
+fn number_or(
+  element: &Element,
+  name: &str,
+  default: f64,
+) -> Result<f64, String> {
+  match element.attribute(name) {
+    Some(_) => number(element, name),
+    None => Ok(default),
+  }
+}
+
+fn point(element: &Element, x: &str, y: &str) -> Result<(f64, f64), String> {
+  Ok((number(element, x)?, number(element, y)?))
+}
+
+fn layer(element: &Element) -> Result<u32, String> {
+  Ok(number(element, "layer")? as u32)
+}
+
+/// Parse a rotation attribute of a package object
+fn rotation(element: &Element) -> Result<f64, String> {
+  Ok(Transform::new((0.0, 0.0), element.attribute("rot"))?.rotation)
+}
+
+/// Get the shapes of a package or of the board as SVG paths
+///
+/// Wires are chained to polylines if they are connected.
+fn shapes(
+  parent: &Element,
+  transform: &Transform,
+) -> Result<Vec<(u32, String, f64, bool)>, String> {
+  let mut shapes: Vec<(u32, SvgPathBuilder, f64, bool)> = Vec::new();
+  let mut end = None;
+  for child in &parent.children {
+    if child.name != "wire" {
+      end = None;
+    }
+    match child.name.as_str() {
+      "wire" => {
+        let (layer, width) = (layer(child)?, number(child, "width")?);
+        let start = transform.map(point(child, "x1", "y1")?);
+        let stop = transform.map(point(child, "x2", "y2")?);
+        let curve = transform.arc(number_or(child, "curve", 0.0)?);
+        // Continue the previous polyline if connected.
+        match shapes.last_mut() {
+          Some(last) if end == Some((layer, width, start)) => {
+            last.1.arc_to(stop, curve);
+          }
+          _ => {
+            let mut builder = SvgPathBuilder::new();
+            builder.move_to(start).arc_to(stop, curve);
+            shapes.push((layer, builder, width, false));
+          }
+        }
+        end = Some((layer, width, stop));
+      }
+      "circle" => {
+        let center = transform.map(point(child, "x", "y")?);
+        let r = number(child, "radius")?;
+        let width = number(child, "width")?;
+        let mut builder = SvgPathBuilder::new();
+        builder
+          .move_to((center.0 + r, center.1))
+          .arc_to((center.0 - r, center.1), 180.0)
+          .arc_to((center.0 + r, center.1), 180.0)
+          .close();
+        shapes.push((layer(child)?, builder, width, width == 0.0));
+      }
+      "rectangle" => {
+        let (x1, y1) = point(child, "x1", "y1")?;
+        let (x2, y2) = point(child, "x2", "y2")?;
+        let center = ((x1 + x2) / 2.0, (y1 + y2) / 2.0);
+        let (sin, cos) = rotation(child)?.to_radians().sin_cos();
+        let mut builder = SvgPathBuilder::new();
+        for (i, (x, y)) in [(x1, y1), (x2, y1), (x2, y2), (x1, y2)]
+          .into_iter()
+          .enumerate()
+        {
+          let (dx, dy) = (x - center.0, y - center.1);
+          let p = transform.map((
+            center.0 + dx * cos - dy * sin,
+            center.1 + dx * sin + dy * cos,
+          ));
+          match i {
+            0 => builder.move_to(p),
+            _ => builder.line_to(p),
+          };
+        }
+        builder.close();
+        shapes.push((layer(child)?, builder, 0.0, true));
+      }
+      "polygon" => {
+        let vertices = child.children("vertex").collect::<Vec<_>>();
+        let mut builder = SvgPathBuilder::new();
+        for (i, vertex) in vertices.iter().enumerate() {
+          if i == 0 {
+            builder.move_to(transform.map(point(vertex, "x", "y")?));
+          }
+          let next = vertices[(i + 1) % vertices.len()];
+          let curve = transform.arc(number_or(vertex, "curve", 0.0)?);
+          builder.arc_to(transform.map(point(next, "x", "y")?), curve);
+        }
+        builder.close();
+        let width = number(child, "width")?;
+        shapes.push((layer(child)?, builder, width, true));
+      }
+      _ => {}
+    }
+  }
+  Ok(
+    shapes
+      .into_iter()
+      .map(|(layer, builder, width, filled)| {
+        (layer, builder.build(), width, filled)
+      })
+      .collect(),
+  )
+}
+
+/// Build a text drawing, converting Eagle's anchor to the center
+fn text(
+  element: &Element,
+  transform: &Transform,
+  kind: DrawingKind,
+  layer: DrawingLayer,
+  content: &str,
+) -> Result<Drawing, String> {
+  let size = number(element, "size")?;
+  let pos = point(element, "x", "y")?;
+  let lines = content.lines().count().max(1) as f64;
+  let width = content
+    .lines()
+    .map(|x| x.chars().count())
+    .max()
+    .unwrap_or(0);
+  let width = width as f64 * size * CHAR_WIDTH;
+  let align = element.attribute("align").unwrap_or("bottom-left");
+  let (vertical, horizontal) = align.split_once('-').unwrap_or(("", align));
+  let dx = match horizontal {
+    "left" => width / 2.0,
+    "right" => -width / 2.0,
+    _ => 0.0,
+  };
+  let dy = match vertical {
+    "bottom" => size * lines / 2.0,
+    "top" => -size * lines / 2.0,
+    _ => 0.0,
+  };
+  let text_transform = Transform::new(pos, element.attribute("rot"))?;
+  let (sin, cos) = text_transform.rotation.to_radians().sin_cos();
+  let dx = if text_transform.mirror { -dx } else { dx };
+  let center = (pos.0 + dx * cos - dy * sin, pos.1 + dx * sin + dy * cos);
+  let ratio = number_or(element, "ratio", 8.0)? / 100.0;
+  Ok(Drawing::text(
+    kind,
+    layer,
+    content,
+    transform.map(center),
+    (size, size),
+    transform.angle(text_transform.rotation),
+    size * ratio,
+  ))
+}
+
+/// Get the pad of a THT pad or SMD
+fn pad(element: &Element, transform: &Transform) -> Result<PackagePad, String> {
+  let pos = point(element, "x", "y")?;
+  let rotation = rotation(element)?;
+  let (shape, layers, drill, size, offset) = if element.name == "smd" {
+    let (w, h) = point(element, "dx", "dy")?;
+    let layers = transform.side(layer(element)?).into_iter().collect();
+    let radius = number_or(element, "roundness", 0.0)? / 100.0 * w.min(h) / 2.0;
+    let shape = if radius >= w.min(h) / 2.0 && w == h {
+      PadShape::Circle(w)
+    } else if radius >= w.min(h) / 2.0 {
+      PadShape::Oval((w, h))
+    } else if radius > 0.0 {
+      PadShape::RoundRect((w, h), radius)
+    } else {
+      PadShape::Rect((w, h))
+    };
+    (shape, layers, None, (w, h), 0.0)
+  } else {
+    let drill = number(element, "drill")?;
+    // Eagle derives the diameter from the design rules if not specified.
+    let d = number_or(element, "diameter", 0.0)?;
+    let d = if d > 0.0 { d } else { drill * 1.5 };
+    let shape = match element.attribute("shape").unwrap_or("round") {
+      "square" => PadShape::Rect((d, d)),
+      "octagon" => {
+        let corner = PadCorner::Chamfer(d * (1.0 - 0.5f64.sqrt()));
+        PadShape::Corners((d, d), [corner; 4])
+      }
+      "long" | "offset" => PadShape::Oval((d * 2.0, d)),
+      _ => PadShape::Circle(d),
+    };
+    let size = match shape {
+      PadShape::Oval(size) => size,
+      _ => (d, d),
+    };
+    // The drill is located at one end of offset pads.
+    let offset = match element.attribute("shape") {
+      Some("offset") => d / 2.0,
+      _ => 0.0,
+    };
+    let layers = vec![Layer::Front, Layer::Back];
+    (shape, layers, Some((drill, drill)), size, offset)
+  };
+  let name = attr(element, "name")?;
+  let center = rotate_around((pos.0 + offset, pos.1), pos, rotation);
+  let mut pad = Pad::with_shape(
+    layers,
+    transform.map(center),
+    transform.angle(rotation),
+    shape,
+    drill,
+    None,
+    name == "1",
+  );
+  if offset != 0.0 {
+    pad.set_drill_offset(transform.local((-offset, 0.0)));
+  }
+  pad.set_pin(Some(name), None);
+  Ok((pad, center, size))
+}
+
+/// Rotate a point counterclockwise around a center
+fn rotate_around(p: (f64, f64), center: (f64, f64), angle: f64) -> (f64, f64) {
+  let (sin, cos) = angle.to_radians().sin_cos();
+  let (dx, dy) = (p.0 - center.0, p.1 - center.1);
+  (
+    center.0 + dx * cos - dy * sin,
+    center.1 + dx * sin + dy * cos,
+  )
+}
+
+/// Add a footprint for an element of the board
+fn add_element(
+  bom: &mut InteractiveHtmlBom,
+  packages: &BTreeMap<(&str, &str), &Element>,
+  nets: &BTreeMap<(&str, &str), NetId>,
+  element: &Element,
+) -> Result<RefMap, String> {
+  let name = attr(element, "name")?;
+  let value = element.attribute("value").unwrap_or_default();
+  let package_name = attr(element, "package")?;
+  let package = packages
+    .get(&(attr(element, "library")?, package_name))
+    .ok_or_else(|| format!("package \"{}\" not found", package_name))?;
+  let transform =
+    Transform::new(point(element, "x", "y")?, element.attribute("rot"))?;
+
+  let mut pads = Vec::new();
+  let mut bounds: Vec<(f64, f64)> = Vec::new();
+  for child in &package.children {
+    if child.name != "smd" && child.name != "pad" {
+      continue;
+    }
+    let (mut pad, pos, (w, h)) = pad(child, &transform)?;
+    let pad_name = attr(child, "name")?;
+    pad.set_net_id(nets.get(&(name, pad_name)).cloned());
+    pads.push(pad);
+    // Conservative, i.e. large enough for any pad rotation.
+    let r = w.hypot(h) / 2.0;
+    bounds.extend([(pos.0 - r, pos.1 - r), (pos.0 + r, pos.1 + r)]);
+  }
+
+  let mut drawings = Vec::new();
+  for (layer, svgpath, width, filled) in shapes(package, &transform)? {
+    if let Some(layer) = transform.drawing_layer(layer) {
+      drawings.push(Drawing::polygon(layer, &svgpath, width, filled));
+    }
+  }
+  for child in package.children("wire") {
+    if matches!(layer(child)?, 21 | 22 | 51 | 52) {
+      bounds.push(point(child, "x1", "y1")?);
+      bounds.push(point(child, "x2", "y2")?);
+    }
+  }
+
+  // Texts are either placed by the package, or individually placed
+  // ("smashed") by element attributes with absolute coordinates.
+  for (kind, attribute, content) in [
+    (DrawingKind::ReferenceText, "NAME", name),
+    (DrawingKind::ValueText, "VALUE", value),
+  ] {
+    let smashed = element
+      .children("attribute")
+      .find(|x| x.attribute("name") == Some(attribute));
+    let (node, transform) = match smashed {
+      Some(x) if x.attribute("x").is_some() => (x, &Transform::BOARD),
+      Some(x) if x.attribute("display") == Some("off") => continue,
+      _ => {
+        let placeholder = format!(">{}", attribute);
+        let node = package
+          .children("text")
+          .find(|x| x.text.eq_ignore_ascii_case(&placeholder));
+        match node {
+          Some(node) => (node, &transform),
+          None => continue,
+        }
+      }
+    };
+    if node.attribute("display") == Some("off") {
+      continue;
+    }
+    let layer = match transform.drawing_layer(layer(node)?) {
+      Some(layer) => layer,
+      None => continue,
+    };
+    drawings.push(text(node, transform, kind, layer, content)?);
+  }
+  for hole in package.children("hole") {
+    let pos = transform.map(point(hole, "x", "y")?);
+    bom.holes.push(Hole::new(pos, number(hole, "drill")?));
+  }
+
+  let local = bounds.iter().map(|p| transform.local(*p));
+  let (bottom_left, top_right) = match bounds.is_empty() {
+    true => ((0.0, 0.0), (0.0, 0.0)),
+    false => local.fold(
+      (
+        (f64::INFINITY, f64::INFINITY),
+        (f64::NEG_INFINITY, f64::NEG_INFINITY),
+      ),
+      |(a, b), p| ((a.0.min(p.0), a.1.min(p.1)), (b.0.max(p.0), b.1.max(p.1))),
+    ),
+  };
+  let mut fpt = Footprint::new(
+    transform.side(TOP).unwrap_or(Layer::Front),
+    transform.map((0.0, 0.0)),
+    transform.rotation,
+    bottom_left,
+    top_right,
+    [value.to_owned(), package_name.to_owned()],
+    pads,
+    element.attribute("populate") != Some("no"),
+  );
+  for drawing in drawings {
+    fpt.add_drawing(drawing);
+  }
+  Ok(RefMap::new(name, bom.add_footprint(fpt)))
+}
+
+/// Add the wires and vias of a signal
+fn add_signal(
+  bom: &mut InteractiveHtmlBom,
+  signal: &Element,
+  net: &NetId,
+) -> Result<(), String> {
+  for wire in signal.children("wire") {
+    let layer = match layer(wire)? {
+      TOP => Layer::Front,
+      BOTTOM => Layer::Back,
+      _ => continue,
+    };
+    let width = number(wire, "width")?;
+    let (start, end) = (point(wire, "x1", "y1")?, point(wire, "x2", "y2")?);
+    let curve = number_or(wire, "curve", 0.0)?;
+    // Curved tracks are approximated by straight segments.
+    let steps = (curve.abs() / ARC_STEP).ceil().max(1.0) as usize;
+    let mut points = vec![start];
+    if curve != 0.0 {
+      let chord = (end.0 - start.0, end.1 - start.1);
+      let half = (curve.to_radians() / 2.0).tan();
+      let center = (
+        start.0 + chord.0 / 2.0 - chord.1 / (2.0 * half),
+        start.1 + chord.1 / 2.0 + chord.0 / (2.0 * half),
+      );
+      for i in 1..steps {
+        let angle = curve * i as f64 / steps as f64;
+        points.push(rotate_around(start, center, angle));
+      }
+    }
+    points.push(end);
+    for pair in points.windows(2) {
+      let map = |p: (f64, f64)| Transform::BOARD.map(p);
+      let (start, end) = (map(pair[0]), map(pair[1]));
+      let net = Some(net.clone());
+      let track = Track::with_net_id(layer.clone(), start, end, width, net);
+      bom.tracks.push(track);
+    }
+  }
+  for via in signal.children("via") {
+    let pos = Transform::BOARD.map(point(via, "x", "y")?);
+    let drill = number(via, "drill")?;
+    let diameter = number_or(via, "diameter", 0.0)?;
+    let diameter = if diameter > 0.0 {
+      diameter
+    } else {
+      drill * 1.5
+    };
+    let extent = via.attribute("extent").unwrap_or("1-16");
+    let (from, to) = extent.split_once('-').unwrap_or((extent, extent));
+    let layers = [(TOP, Layer::Front), (BOTTOM, Layer::Back)]
+      .into_iter()
+      .filter(|(number, _)| {
+        from == number.to_string() || to == number.to_string()
+      })
+      .map(|(_, layer)| layer);
+    let net = Some(net.clone());
+    let via = Via::with_net_id(layers, pos, diameter, drill, net);
+    if !via.layers().is_empty() {
+      bom.vias.push(via);
+    }
+  }
+  Ok(())
+}
+
+/// Read an Eagle board
+///
+/// # Arguments
+///
+/// * `xml` - Content of the board file.
+///
+/// # Returns
+///
+/// Returns the populated object, or an error if the file is invalid.
+pub(crate) fn import(xml: &str) -> Result<InteractiveHtmlBom, GenerateError> {
+  import_board(xml).map_err(|reason| GenerateError::InvalidInput {
+    format: InputFormat::EagleBoard,
+    reason,
+  })
+}
+
+fn import_board(xml: &str) -> Result<InteractiveHtmlBom, String> {
+  let root = xml::parse(xml)?;
+  if root.name != "eagle" {
+    return Err("root element is not <eagle>".into());
+  }
+  let board = root
+    .child("drawing")
+    .and_then(|x| x.child("board"))
+    .ok_or("file contains no board")?;
+  let mut bom = InteractiveHtmlBom::new("", "", "", "", (0.0, 0.0), (0.0, 0.0));
+  bom.fields = vec!["Value".into(), "Package".into()];
+
+  let mut packages = BTreeMap::new();
+  for library in board
+    .child("libraries")
+    .iter()
+    .flat_map(|x| x.children("library"))
+  {
+    let library_name = attr(library, "name")?;
+    for package in library
+      .children("packages")
+      .flat_map(|x| x.children("package"))
+    {
+      packages.insert((library_name, attr(package, "name")?), package);
+    }
+  }
+
+  // Pad nets by element name and pad name.
+  let mut nets = BTreeMap::new();
+  let mut signals = Vec::new();
+  for signal in board
+    .child("signals")
+    .iter()
+    .flat_map(|x| x.children("signal"))
+  {
+    let net = bom.add_net(attr(signal, "name")?);
+    for contact in signal.children("contactref") {
+      let key = (attr(contact, "element")?, attr(contact, "pad")?);
+      nets.insert(key, net.clone());
+    }
+    signals.push((signal, net));
+  }
+
+  let mut refs = Vec::new();
+  for element in board
+    .child("elements")
+    .iter()
+    .flat_map(|x| x.children("element"))
+  {
+    let name = attr(element, "name")?;
+    let refmap = add_element(&mut bom, &packages, &nets, element)
+      .map_err(|reason| format!("element \"{}\": {}", name, reason))?;
+    refs.push(refmap);
+  }
+  for (signal, net) in signals {
+    add_signal(&mut bom, signal, &net)?;
+  }
+
+  if let Some(plain) = board.child("plain") {
+    let mut outlines = Vec::new();
+    let mut outline_width = 0.0;
+    for (layer, svgpath, width, filled) in shapes(plain, &Transform::BOARD)? {
+      if layer == DIMENSION {
+        outlines.push(svgpath);
+        outline_width = width;
+      } else if let Some(layer) = Transform::BOARD.drawing_layer(layer) {
+        bom
+          .drawings
+          .push(Drawing::polygon(layer, &svgpath, width, filled));
+      }
+    }
+    for node in plain.children("text") {
+      if let Some(layer) = Transform::BOARD.drawing_layer(layer(node)?) {
+        let kind = DrawingKind::Text;
+        let drawing = text(node, &Transform::BOARD, kind, layer, &node.text)?;
+        bom.drawings.push(drawing);
+      }
+    }
+    if !outlines.is_empty() {
+      let outline = outlines.join(" ");
+      bom
+        .outlines
+        .push(BoardOutline::new(&outline, &[], outline_width));
+    }
+    for hole in plain.children("hole") {
+      let pos = Transform::BOARD.map(point(hole, "x", "y")?);
+      bom.holes.push(Hole::new(pos, number(hole, "drill")?));
+    }
+  }
+
+  bom
+    .group_bom(&refs, &["Value", "Package"])
+    .map_err(|err| err.to_string())?;
+  Ok(bom)
+}
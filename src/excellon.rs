@@ -0,0 +1,163 @@
+//! Reader for Excellon drill files
+//!
+//! Supports round drills and slots, either as G85 commands or routed with
+//! straight lines. The Y axis is mirrored to point downwards.
+
+use std::collections::BTreeMap;
+
+/// Drill, or slot if start and end differ \[mm\]
+#[derive(Debug)]
+pub(crate) struct Drill {
+  pub start: (f64, f64),
+  pub end: (f64, f64),
+  pub diameter: f64,
+}
+
+/// Coordinate format
+struct Format {
+  /// Scale from file units to millimeters
+  scale: f64,
+  /// Number of integer and decimal digits
+  digits: (i32, i32),
+  /// Whether leading zeros are included, i.e. trailing zeros are omitted
+  leading_zeros: bool,
+}
+
+impl Format {
+  fn number(&self, value: &str) -> Result<f64, String> {
+    let invalid = || format!("invalid number \"{}\"", value);
+    if value.contains('.') {
+      return Ok(value.parse::<f64>().map_err(|_| invalid())? * self.scale);
+    }
+    let digits = value.trim_start_matches(['+', '-']);
+    let mut number: f64 = digits.parse().map_err(|_| invalid())?;
+    if self.leading_zeros {
+      let total = self.digits.0 + self.digits.1;
+      number *= 10f64.powi(total - digits.len() as i32);
+    }
+    number /= 10f64.powi(self.digits.1);
+    let sign = if value.starts_with('-') { -1.0 } else { 1.0 };
+    Ok(sign * number * self.scale)
+  }
+
+  /// Parse the coordinates of a command, keeping omitted ones from `pos`
+  fn point(&self, text: &str, pos: (f64, f64)) -> Result<(f64, f64), String> {
+    let value = |axis: char| {
+      let start = text.find(axis)? + 1;
+      let len = text[start..]
+        .find(|c: char| c.is_ascii_alphabetic())
+        .unwrap_or(text.len() - start);
+      Some(&text[start..start + len])
+    };
+    Ok((
+      value('X').map_or(Ok(pos.0), |x| self.number(x))?,
+      value('Y').map_or(Ok(pos.1), |x| self.number(x).map(|y| -y))?,
+    ))
+  }
+}
+
+/// Read the drills of an Excellon file
+///
+/// # Arguments
+///
+/// * `excellon` - Content of the drill file.
+///
+/// # Returns
+///
+/// Returns the drills, or a description of the problem if the file is
+/// invalid.
+pub(crate) fn parse(excellon: &str) -> Result<Vec<Drill>, String> {
+  let mut format = Format {
+    scale: 1.0,
+    digits: (3, 3),
+    leading_zeros: false,
+  };
+  let mut tools = BTreeMap::new();
+  let mut diameter = None;
+  let mut pos = (0.0, 0.0);
+  let (mut routing, mut plunged) = (false, false);
+  let mut drills = Vec::new();
+  for line in excellon.lines().map(str::trim) {
+    let mut fields = line.split(',');
+    match fields.next().unwrap_or_default() {
+      "" | "M48" | "%" | "M95" | "G90" => continue,
+      _ if line.starts_with(';') => continue,
+      unit @ ("METRIC" | "INCH") => {
+        let metric = unit == "METRIC";
+        format.scale = if metric { 1.0 } else { 25.4 };
+        format.digits = if metric { (3, 3) } else { (2, 4) };
+        for field in fields {
+          match field {
+            "LZ" => format.leading_zeros = true,
+            "TZ" => format.leading_zeros = false,
+            x => {
+              if let Some((int, dec)) = x.split_once('.') {
+                format.digits = (int.len() as i32, dec.len() as i32);
+              }
+            }
+          }
+        }
+        continue;
+      }
+      "M71" => format.scale = 1.0,
+      "M72" => format.scale = 25.4,
+      "M15" => plunged = true,
+      "M16" | "M17" => plunged = false,
+      "G05" => routing = false,
+      "M30" | "M00" => break,
+      _ => {}
+    }
+    if let Some(tool) = line.strip_prefix('T') {
+      let len = tool
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(tool.len());
+      let number: u32 = tool[..len]
+        .parse()
+        .map_err(|_| format!("invalid tool \"{}\"", line))?;
+      match tool.find('C') {
+        Some(start) => {
+          let value = &tool[start + 1..];
+          let end = value
+            .find(|c: char| c.is_ascii_alphabetic())
+            .unwrap_or(value.len());
+          tools.insert(number, format.number(&value[..end])?);
+        }
+        None => diameter = tools.get(&number).copied(),
+      }
+      continue;
+    }
+    if !line.contains(['X', 'Y']) {
+      continue;
+    }
+    let target = |text: &str| format.point(text, pos);
+    let drill = |start, end| {
+      let diameter = diameter.ok_or("drill without tool")?;
+      Ok::<_, String>(Drill {
+        start,
+        end,
+        diameter,
+      })
+    };
+    if let Some((start, end)) = line.split_once("G85") {
+      let start = target(start)?;
+      let end = format.point(end, start)?;
+      drills.push(drill(start, end)?);
+      pos = end;
+    } else if line.starts_with("G00") {
+      routing = true;
+      pos = target(line)?;
+    } else if line.starts_with("G01") {
+      let end = target(line)?;
+      if plunged {
+        drills.push(drill(pos, end)?);
+      }
+      pos = end;
+    } else if routing {
+      pos = target(line)?;
+    } else {
+      pos = target(line)?;
+      drills.push(drill(pos, pos)?);
+    }
+  }
+  Ok(drills)
+}
@@ -0,0 +1,74 @@
+/* Minimal CBOR decoder, maintained by interactive-html-bom-rs */
+
+var IbomCbor = (function () {
+  function decode(bytes) {
+    var view = new DataView(bytes.buffer, bytes.byteOffset, bytes.length);
+    var decoder = new TextDecoder();
+    var pos = 0;
+
+    function argument(info) {
+      if (info < 24) return info;
+      var value;
+      if (info == 24) {
+        value = view.getUint8(pos);
+        pos += 1;
+      } else if (info == 25) {
+        value = view.getUint16(pos);
+        pos += 2;
+      } else if (info == 26) {
+        value = view.getUint32(pos);
+        pos += 4;
+      } else if (info == 27) {
+        value = view.getUint32(pos) * 4294967296 + view.getUint32(pos + 4);
+        pos += 8;
+      } else {
+        throw new Error("Unsupported CBOR item");
+      }
+      return value;
+    }
+
+    function item() {
+      var initial = view.getUint8(pos++);
+      var major = initial >> 5, info = initial & 31;
+      if (major == 7) {
+        if (info == 20) return false;
+        if (info == 21) return true;
+        if (info == 22) return null;
+        if (info == 27) {
+          var value = view.getFloat64(pos);
+          pos += 8;
+          return value;
+        }
+        throw new Error("Unsupported CBOR item");
+      }
+      var length = argument(info);
+      if (major == 0) return length;
+      if (major == 1) return -1 - length;
+      if (major == 3) {
+        var text = decoder.decode(bytes.subarray(pos, pos + length));
+        pos += length;
+        return text;
+      }
+      if (major == 4) {
+        var array = new Array(length);
+        for (var i = 0; i < length; i++) array[i] = item();
+        return array;
+      }
+      if (major == 5) {
+        var object = {};
+        for (var i = 0; i < length; i++) {
+          var key = item();
+          object[key] = item();
+        }
+        return object;
+      }
+      throw new Error("Unsupported CBOR item");
+    }
+
+    return item();
+  }
+
+  return {
+    decode: decode,
+  };
+})();
@@ -0,0 +1,550 @@
+/* Extensions of the upstream viewer, maintained by interactive-html-bom-rs */
+
+/* Strings in the viewer data are HTML-escaped, but tooltips are plain text */
+function unescapeHtml(html) {
+  var textarea = document.createElement("textarea");
+  textarea.innerHTML = html;
+  return textarea.value;
+}
+
+/* Additional drawing layers */
+(function () {
+  var layers = config.extra_layers || [];
+  if (layers.length == 0) return;
+
+  var origDrawBackground = drawBackground;
+  drawBackground = function (canvasdict, clear = true) {
+    origDrawBackground(canvasdict, clear);
+    for (var layer of layers) {
+      if (!layer.visible) continue;
+      drawBgLayer(
+        layer.name, canvasdict.fab, canvasdict.layer,
+        canvasdict.transform.s * canvasdict.transform.zoom,
+        layer.color, layer.color, layer.color);
+    }
+  };
+
+  document.addEventListener("DOMContentLoaded", function () {
+    var anchor = document.getElementById("referencesCheckbox").parentNode;
+    for (let layer of layers) {
+      var label = document.createElement("label");
+      label.classList.add("menu-label");
+      var checkbox = document.createElement("input");
+      checkbox.type = "checkbox";
+      checkbox.checked = layer.visible;
+      checkbox.onchange = function () {
+        layer.visible = this.checked;
+        redrawIfInitDone();
+      };
+      label.appendChild(checkbox);
+      label.appendChild(document.createTextNode(" " + layer.title));
+      anchor.parentNode.insertBefore(label, anchor);
+    }
+  });
+})();
+
+/* Pin tooltips when hovering pads */
+(function () {
+  var hasPins = pcbdata.footprints.some(
+    (f) => f.pads.some((p) => "pinnumber" in p || "pinname" in p));
+  if (!hasPins) return;
+
+  // Footprint references are only contained in the BOM rows.
+  var refs = {};
+  for (var row of [...pcbdata.bom.F, ...pcbdata.bom.B, ...pcbdata.bom.both]) {
+    for (var [ref, id] of row) refs[id] = ref;
+  }
+
+  function padTooltip(layerdict, x, y) {
+    var t = layerdict.transform;
+    var flip = layerdict.layer === "B";
+    if (flip) {
+      x = (devicePixelRatio * x / t.zoom - t.panx + t.x) / -t.s;
+    } else {
+      x = (devicePixelRatio * x / t.zoom - t.panx - t.x) / t.s;
+    }
+    y = (devicePixelRatio * y / t.zoom - t.y - t.pany) / t.s;
+    var v = rotateVector([x, y], -settings.boardRotation +
+      (flip && settings.offsetBackRotation ? - 180 : 0));
+    for (var i = 0; i < pcbdata.footprints.length; i++) {
+      for (var pad of pcbdata.footprints[i].pads) {
+        if (!pad.layers.includes(layerdict.layer) ||
+          !pointWithinPad(...v, pad)) continue;
+        var text = unescapeHtml(refs[i] || "");
+        if ("pinnumber" in pad) text += " pin " + unescapeHtml(pad.pinnumber);
+        if ("pinname" in pad) text += " (" + unescapeHtml(pad.pinname) + ")";
+        if (pad.net) text += " / " + unescapeHtml(pad.net);
+        return text;
+      }
+    }
+    return "";
+  }
+
+  var origAddMouseHandlers = addMouseHandlers;
+  addMouseHandlers = function (div, layerdict) {
+    origAddMouseHandlers(div, layerdict);
+    div.addEventListener("pointermove", function (e) {
+      if (Object.keys(layerdict.pointerStates).length > 0) return;
+      var rect = div.getBoundingClientRect();
+      div.title = padTooltip(
+        layerdict, e.clientX - rect.left, e.clientY - rect.top);
+    });
+  };
+})();
+
+/* Defaults of settings stored in the browser, unless modified by the user */
+(function () {
+  var defaults = {};
+  for (var checkbox in config.checkbox_defaults || {}) {
+    defaults["checkbox_" + checkbox] = config.checkbox_defaults[checkbox];
+  }
+  if (config.hidden_columns && config.hidden_columns.length > 0) {
+    defaults["hiddenColumns"] = JSON.stringify(config.hidden_columns);
+  }
+  var origReadStorage = readStorage;
+  readStorage = function (key) {
+    var value = origReadStorage(key);
+    if (value === null && key in defaults) return defaults[key];
+    return value;
+  };
+})();
+
+/* Initial sort column and order */
+(function () {
+  if (!config.sort_column) return;
+  var origOnload = window.onload;
+  window.onload = function (e) {
+    origOnload(e);
+    for (var th of document.querySelectorAll("#bomhead th")) {
+      if (th.getAttribute("col_name") !== config.sort_column) continue;
+      var spacer = th.querySelector(".column-spacer");
+      spacer.onclick();
+      if (config.sort_order == "desc") spacer.onclick();
+      break;
+    }
+  };
+})();
+
+/* Initial filter and reference lookup */
+(function () {
+  if (!config.filter && !config.ref_lookup) return;
+  var origOnload = window.onload;
+  window.onload = function (e) {
+    origOnload(e);
+    if (config.filter) {
+      document.getElementById("filter").value = config.filter;
+      updateFilter(config.filter);
+    }
+    if (config.ref_lookup) {
+      document.getElementById("reflookup").value = config.ref_lookup;
+      updateRefLookup(config.ref_lookup);
+    }
+  };
+})();
+
+/* Additional footprint information shown when hovering BOM rows */
+(function () {
+  var tooltips = pcbdata.tooltips || {};
+  if (Object.keys(tooltips).length == 0) return;
+
+  var origCreateRowHighlightHandler = createRowHighlightHandler;
+  createRowHighlightHandler = function (rowid, refs, net) {
+    if (refs) {
+      var lines = [];
+      for (var [ref, id] of refs) {
+        for (var [name, value] of tooltips[id] || []) {
+          var line = unescapeHtml(name) + ": " + unescapeHtml(value);
+          if (!lines.includes(line)) lines.push(line);
+        }
+      }
+      document.getElementById(rowid).title = lines.join("\n");
+    }
+    return origCreateRowHighlightHandler(rowid, refs, net);
+  };
+})();
+
+/* Favicon and logo */
+(function () {
+  if (config.favicon) {
+    var link = document.createElement("link");
+    link.rel = "icon";
+    link.href = config.favicon;
+    document.head.appendChild(link);
+  }
+  if (config.logo) {
+    document.addEventListener("DOMContentLoaded", function () {
+      var img = document.createElement("img");
+      img.id = "logo";
+      img.src = config.logo;
+      img.style.maxHeight = "3em";
+      img.style.padding = "3px 10px";
+      var fileinfo = document.getElementById("fileinfodiv");
+      fileinfo.parentNode.insertBefore(img, fileinfo);
+    });
+  }
+})();
+
+/* Page title */
+(function () {
+  if (!config.page_title) return;
+  document.title = config.page_title;
+  var origOnload = window.onload;
+  window.onload = function (e) {
+    origOnload(e);
+    document.title = config.page_title;
+  };
+})();
+
+/* Floating, resizable panel on top of the viewer, hidden by default */
+function createFloatingPanel(id) {
+  var panel = document.createElement("div");
+  panel.id = id;
+  panel.classList.add("hideonprint");
+  Object.assign(panel.style, {
+    display: "none", position: "fixed", right: "10px", bottom: "10px",
+    width: "40vw", height: "40vh", minWidth: "100px", minHeight: "100px",
+    resize: "both", overflow: "auto", zIndex: "100",
+    backgroundColor: "inherit", border: "1px solid #888",
+    boxShadow: "0 0 8px rgba(0, 0, 0, 0.5)",
+  });
+  document.getElementById("topmostdiv").appendChild(panel);
+  return panel;
+}
+
+/* Toolbar button group appended after the existing ones */
+function createToolbarButtons(titles) {
+  var container = document.createElement("div");
+  container.classList.add("button-container", "hideonprint");
+  var buttons = titles.map(function (title, i) {
+    var button = document.createElement("button");
+    if (titles.length > 1) {
+      button.classList.add(i == 0 ? "left-most-button" :
+        i == titles.length - 1 ? "right-most-button" : "middle-button");
+    }
+    button.textContent = title;
+    button.title = title;
+    Object.assign(button.style, {
+      width: "auto", padding: "0 8px", fontSize: "14px",
+    });
+    container.appendChild(button);
+    return button;
+  });
+  var containers = document.querySelectorAll("#top .button-container");
+  var last = containers[containers.length - 1];
+  last.parentNode.insertBefore(container, last.nextSibling);
+  return buttons;
+}
+
+/* Images shown in a floating panel, selected by toolbar buttons */
+(function () {
+  var images = config.images || [];
+  if (images.length == 0) return;
+
+  document.addEventListener("DOMContentLoaded", function () {
+    var panel = createFloatingPanel("imagepanel");
+    var img = document.createElement("img");
+    img.style.width = "100%";
+    panel.appendChild(img);
+
+    var current = null;
+    var titles = images.map(function (image) { return image.title; });
+    createToolbarButtons(titles).forEach(function (button, i) {
+      button.onclick = function () {
+        if (current) current.classList.remove("depressed");
+        if (current === button) {
+          current = null;
+          panel.style.display = "none";
+          return;
+        }
+        current = button;
+        button.classList.add("depressed");
+        img.src = images[i].src;
+        img.alt = images[i].title;
+        panel.style.display = "block";
+      };
+    });
+  });
+})();
+
+/* Schematic sheets, following the highlighted components */
+(function () {
+  var sheets = config.schematic_sheets || [];
+  if (sheets.length == 0) return;
+  var panel, img, markers, buttons, current = -1;
+
+  function show(index, ids) {
+    current = index;
+    buttons.forEach(function (button, i) {
+      button.classList.toggle("depressed", i == index);
+    });
+    panel.style.display = index < 0 ? "none" : "block";
+    if (index < 0) return;
+    if (img.title != sheets[index].title) {
+      img.src = sheets[index].src;
+      img.alt = img.title = sheets[index].title;
+    }
+    markers.textContent = "";
+    var first = null;
+    sheets[index].locations.forEach(function (location) {
+      if (!ids.includes(location[0])) return;
+      var marker = document.createElement("div");
+      Object.assign(marker.style, {
+        position: "absolute", left: location[1] * 100 + "%",
+        top: location[2] * 100 + "%", width: "24px", height: "24px",
+        margin: "-14px 0 0 -14px", border: "2px solid #d04040",
+        borderRadius: "50%", pointerEvents: "none",
+      });
+      markers.appendChild(marker);
+      first = first || location;
+    });
+    if (first) {
+      panel.scrollTop = first[2] * img.clientHeight - panel.clientHeight / 2;
+    }
+  }
+
+  document.addEventListener("DOMContentLoaded", function () {
+    panel = createFloatingPanel("schematicpanel");
+    img = document.createElement("img");
+    img.style.width = "100%";
+    img.style.display = "block";
+    markers = document.createElement("div");
+    Object.assign(markers.style, {
+      position: "absolute", left: "0", top: "0", width: "100%",
+      height: "100%",
+    });
+    var content = document.createElement("div");
+    content.style.position = "relative";
+    content.appendChild(img);
+    content.appendChild(markers);
+    panel.appendChild(content);
+
+    var titles = sheets.map(function (sheet) { return sheet.title; });
+    buttons = createToolbarButtons(titles);
+    buttons.forEach(function (button, i) {
+      button.onclick = function () {
+        show(current == i ? -1 : i, highlightedFootprints);
+      };
+    });
+  });
+
+  EventHandler.registerCallback(IBOM_EVENT_TYPES.HIGHLIGHT_EVENT,
+    function () {
+      if (current < 0) return;
+      var ids = highlightedFootprints;
+      var index = sheets.findIndex(function (sheet) {
+        return sheet.locations.some(function (location) {
+          return ids.includes(location[0]);
+        });
+      });
+      show(index < 0 ? current : index, ids);
+    });
+})();
+
+/* Simple 3D view of the board outline and component bounding boxes */
+(function () {
+  var view = config.view_3d;
+  if (!view) return;
+  var COMPONENT_HEIGHT = 1;
+  var yaw = 0.5, pitch = 0.9, zoom = 1;
+  var panel, canvas;
+
+  var bbox = pcbdata.edges_bbox;
+  var center = [(bbox.minx + bbox.maxx) / 2, (bbox.miny + bbox.maxy) / 2,
+    view.thickness / 2];
+  var size = Math.max(bbox.maxx - bbox.minx, bbox.maxy - bbox.miny, 1);
+
+  /* Rotate a vector given in board coordinates into view coordinates */
+  function rotate(v) {
+    var x = v[0], y = -v[1], z = v[2];
+    var x1 = x * Math.cos(yaw) - y * Math.sin(yaw);
+    var y1 = x * Math.sin(yaw) + y * Math.cos(yaw);
+    return [x1, y1 * Math.cos(pitch) - z * Math.sin(pitch),
+      y1 * Math.sin(pitch) + z * Math.cos(pitch)];
+  }
+
+  /* Polygon of [x, y] points lifted to the height z */
+  function face(points, z) {
+    return points.map(function (p) { return [p[0], p[1], z]; });
+  }
+
+  /* Faces of a footprint bounding box, with their outward normals */
+  function boxFaces(footprint, z0, z1) {
+    var bb = footprint.bbox;
+    var angle = -deg2rad(bb.angle);
+    var corners = [[0, 0], [bb.size[0], 0], bb.size, [0, bb.size[1]]];
+    corners = corners.map(function (c) {
+      var x = c[0] + bb.relpos[0], y = c[1] + bb.relpos[1];
+      return [bb.pos[0] + x * Math.cos(angle) - y * Math.sin(angle),
+        bb.pos[1] + x * Math.sin(angle) + y * Math.cos(angle)];
+    });
+    var faces = [
+      { points: face(corners, z1), normal: [0, 0, z1 - z0] },
+      { points: face(corners, z0), normal: [0, 0, z0 - z1] },
+    ];
+    var cx = (corners[0][0] + corners[2][0]) / 2;
+    var cy = (corners[0][1] + corners[2][1]) / 2;
+    for (var i = 0; i < 4; i++) {
+      var a = corners[i], b = corners[(i + 1) % 4];
+      faces.push({
+        points: [[a[0], a[1], z0], [b[0], b[1], z0], [b[0], b[1], z1],
+          [a[0], a[1], z1]],
+        normal: [(a[0] + b[0]) / 2 - cx, (a[1] + b[1]) / 2 - cy, 0],
+      });
+    }
+    return faces;
+  }
+
+  /* Whether a point lies inside a polygon (even-odd rule) */
+  function inside(point, polygon) {
+    var result = false;
+    for (var i = 0, j = polygon.length - 1; i < polygon.length; j = i++) {
+      var a = polygon[i], b = polygon[j];
+      if ((a[1] > point[1]) != (b[1] > point[1]) && point[0] <
+        (b[0] - a[0]) * (point[1] - a[1]) / (b[1] - a[1]) + a[0]) {
+        result = !result;
+      }
+    }
+    return result;
+  }
+
+  /* Board edge faces, with normals pointing away from the board material */
+  var walls = [];
+  view.outlines.forEach(function (outline) {
+    var area = 0;
+    outline.forEach(function (a, i) {
+      var b = outline[(i + 1) % outline.length];
+      area += a[0] * b[1] - b[0] * a[1];
+    });
+    var hole = view.outlines.filter(function (other) {
+      return other !== outline && inside(outline[0], other);
+    }).length % 2 == 1;
+    var sign = (area > 0) != hole ? 1 : -1;
+    outline.forEach(function (a, i) {
+      var b = outline[(i + 1) % outline.length];
+      walls.push({
+        points: [[a[0], a[1], 0], [b[0], b[1], 0],
+          [b[0], b[1], view.thickness], [a[0], a[1], view.thickness]],
+        normal: [(b[1] - a[1]) * sign, (a[0] - b[0]) * sign, 0],
+      });
+    });
+  });
+
+  function draw() {
+    if (!panel || panel.style.display == "none") return;
+    var width = canvas.width = panel.clientWidth;
+    var height = canvas.height = panel.clientHeight;
+    var scale = Math.min(width, height) / size * 0.7 * zoom;
+    var ctx = canvas.getContext("2d");
+    ctx.clearRect(0, 0, width, height);
+
+    function project(p) {
+      var r = rotate([p[0] - center[0], p[1] - center[1], p[2] - center[2]]);
+      return [width / 2 + r[0] * scale, height / 2 - r[1] * scale, r[2]];
+    }
+    function path(points) {
+      points.forEach(function (p, i) {
+        if (i == 0) ctx.moveTo(p[0], p[1]); else ctx.lineTo(p[0], p[1]);
+      });
+      ctx.closePath();
+    }
+    /* Paint faces back to front, skipping those facing away */
+    function paint(faces, color) {
+      faces.map(function (f) {
+        var n = rotate(f.normal);
+        var len = Math.hypot(n[0], n[1], n[2]) || 1;
+        var points = f.points.map(project);
+        var depth = points.reduce(function (sum, p) {
+          return sum + p[2];
+        }, 0) / points.length;
+        return {
+          points: points, depth: depth, shade: 0.6 + 0.4 * n[2] / len,
+          color: f.color || color, visible: n[2] > 0,
+        };
+      }).filter(function (f) {
+        return f.visible;
+      }).sort(function (a, b) {
+        return a.depth - b.depth;
+      }).forEach(function (f) {
+        ctx.beginPath();
+        path(f.points);
+        ctx.fillStyle = "rgb(" + f.color.map(function (x) {
+          return Math.round(x * f.shade);
+        }).join(",") + ")";
+        ctx.fill();
+        ctx.stroke();
+      });
+    }
+    ctx.strokeStyle = "rgba(0, 0, 0, 0.4)";
+    ctx.lineWidth = 0.5;
+
+    // Board edges, then the visible board side with holes cut out.
+    var top = rotate([0, 0, 1])[2] > 0;
+    var z = top ? view.thickness : 0;
+    paint(walls, [30, 80, 40]);
+    ctx.beginPath();
+    view.outlines.forEach(function (outline) {
+      path(outline.map(function (p) { return project([p[0], p[1], z]); }));
+    });
+    ctx.fillStyle = "rgb(40, 110, 55)";
+    ctx.fill("evenodd");
+    ctx.stroke();
+
+    // Components on the visible side only, the others are hidden by the board.
+    var boxes = [];
+    pcbdata.footprints.forEach(function (footprint, i) {
+      if ((footprint.layer == "F") != top) return;
+      var color = highlightedFootprints.includes(i) ?
+        [208, 64, 64] : [150, 150, 150];
+      var dz = top ? COMPONENT_HEIGHT : -COMPONENT_HEIGHT;
+      boxFaces(footprint, z, z + dz).forEach(function (f) {
+        f.color = color;
+        boxes.push(f);
+      });
+    });
+    paint(boxes);
+  }
+
+  var origDrawHighlights = drawHighlights;
+  drawHighlights = function () {
+    origDrawHighlights();
+    draw();
+  };
+
+  document.addEventListener("DOMContentLoaded", function () {
+    panel = createFloatingPanel("view3dpanel");
+    panel.style.overflow = "hidden";
+    canvas = document.createElement("canvas");
+    canvas.style.display = "block";
+    canvas.style.cursor = "grab";
+    panel.appendChild(canvas);
+
+    var drag = null;
+    canvas.onpointerdown = function (e) {
+      drag = [e.clientX, e.clientY];
+      canvas.setPointerCapture(e.pointerId);
+    };
+    canvas.onpointermove = function (e) {
+      if (!drag) return;
+      yaw += (e.clientX - drag[0]) * 0.01;
+      pitch = Math.max(-Math.PI, Math.min(Math.PI,
+        pitch - (e.clientY - drag[1]) * 0.01));
+      drag = [e.clientX, e.clientY];
+      draw();
+    };
+    canvas.onpointerup = function () { drag = null; };
+    canvas.onwheel = function (e) {
+      e.preventDefault();
+      zoom = Math.max(0.2, Math.min(20, zoom * Math.exp(-e.deltaY * 0.002)));
+      draw();
+    };
+    new ResizeObserver(draw).observe(panel);
+
+    var button = createToolbarButtons(["3D"])[0];
+    button.onclick = function () {
+      var show = panel.style.display == "none";
+      button.classList.toggle("depressed", show);
+      panel.style.display = show ? "block" : "none";
+      draw();
+    };
+  });
+})();
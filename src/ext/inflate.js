@@ -0,0 +1,158 @@
+/* Minimal raw DEFLATE decoder, maintained by interactive-html-bom-rs */
+
+var IbomInflate = (function () {
+  var LEN_BASE = [3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31,
+    35, 43, 51, 59, 67, 83, 99, 115, 131, 163, 195, 227, 258];
+  var LEN_EXTRA = [0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2,
+    3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 0];
+  var DIST_BASE = [1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129,
+    193, 257, 385, 513, 769, 1025, 1537, 2049, 3073, 4097, 6145, 8193,
+    12289, 16385, 24577];
+  var DIST_EXTRA = [0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6,
+    7, 7, 8, 8, 9, 9, 10, 10, 11, 11, 12, 12, 13, 13];
+  var CL_ORDER = [16, 17, 18, 0, 8, 7, 9, 6, 10, 5, 11, 4, 12, 3, 13, 2,
+    14, 1, 15];
+
+  /* Canonical Huffman table: symbol counts per length and sorted symbols */
+  function buildTable(lengths) {
+    var counts = new Uint16Array(16);
+    var offsets = new Uint16Array(16);
+    var symbols = new Uint16Array(lengths.length);
+    for (var i = 0; i < lengths.length; i++) counts[lengths[i]]++;
+    counts[0] = 0;
+    for (var i = 1; i < 16; i++) offsets[i] = offsets[i - 1] + counts[i - 1];
+    for (var i = 0; i < lengths.length; i++) {
+      if (lengths[i]) symbols[offsets[lengths[i]]++] = i;
+    }
+    return { counts: counts, symbols: symbols };
+  }
+
+  function inflate(input) {
+    var out = new Uint8Array(input.length * 4 + 1024);
+    var outPos = 0;
+    var pos = 0, bitBuf = 0, bitCnt = 0;
+
+    function ensure(n) {
+      if (outPos + n <= out.length) return;
+      var grown = new Uint8Array(Math.max(out.length * 2, outPos + n));
+      grown.set(out);
+      out = grown;
+    }
+    function bits(n) {
+      while (bitCnt < n) {
+        if (pos >= input.length) throw new Error("Unexpected end of data");
+        bitBuf |= input[pos++] << bitCnt;
+        bitCnt += 8;
+      }
+      var value = bitBuf & ((1 << n) - 1);
+      bitBuf >>>= n;
+      bitCnt -= n;
+      return value;
+    }
+    function decode(table) {
+      var code = 0, first = 0, index = 0;
+      for (var len = 1; len < 16; len++) {
+        code |= bits(1);
+        var count = table.counts[len];
+        if (code - first < count) return table.symbols[index + code - first];
+        index += count;
+        first = (first + count) << 1;
+        code <<= 1;
+      }
+      throw new Error("Invalid Huffman code");
+    }
+
+    var fixedLit, fixedDist;
+    var last;
+    do {
+      last = bits(1);
+      var type = bits(2);
+      if (type == 0) {
+        bitBuf = 0;
+        bitCnt = 0;
+        var len = input[pos] | (input[pos + 1] << 8);
+        pos += 4;
+        ensure(len);
+        out.set(input.subarray(pos, pos + len), outPos);
+        outPos += len;
+        pos += len;
+        continue;
+      }
+      var lit, dist;
+      if (type == 1) {
+        if (!fixedLit) {
+          var lengths = new Uint8Array(288);
+          for (var i = 0; i < 288; i++) {
+            lengths[i] = i < 144 ? 8 : i < 256 ? 9 : i < 280 ? 7 : 8;
+          }
+          fixedLit = buildTable(lengths);
+          fixedDist = buildTable(new Uint8Array(30).fill(5));
+        }
+        lit = fixedLit;
+        dist = fixedDist;
+      } else if (type == 2) {
+        var nlen = bits(5) + 257, ndist = bits(5) + 1, ncode = bits(4) + 4;
+        var clLengths = new Uint8Array(19);
+        for (var i = 0; i < ncode; i++) clLengths[CL_ORDER[i]] = bits(3);
+        var clTable = buildTable(clLengths);
+        var lengths = new Uint8Array(nlen + ndist);
+        for (var i = 0; i < nlen + ndist;) {
+          var sym = decode(clTable);
+          if (sym < 16) {
+            lengths[i++] = sym;
+          } else {
+            var value = 0, repeat;
+            if (sym == 16) {
+              value = lengths[i - 1];
+              repeat = 3 + bits(2);
+            } else if (sym == 17) {
+              repeat = 3 + bits(3);
+            } else {
+              repeat = 11 + bits(7);
+            }
+            while (repeat--) lengths[i++] = value;
+          }
+        }
+        lit = buildTable(lengths.subarray(0, nlen));
+        dist = buildTable(lengths.subarray(nlen));
+      } else {
+        throw new Error("Invalid block type");
+      }
+      for (;;) {
+        var sym = decode(lit);
+        if (sym < 256) {
+          ensure(1);
+          out[outPos++] = sym;
+        } else if (sym == 256) {
+          break;
+        } else {
+          sym -= 257;
+          var len = LEN_BASE[sym] + bits(LEN_EXTRA[sym]);
+          var d = decode(dist);
+          var offset = DIST_BASE[d] + bits(DIST_EXTRA[d]);
+          ensure(len);
+          for (var i = 0; i < len; i++, outPos++) {
+            out[outPos] = out[outPos - offset];
+          }
+        }
+      }
+    } while (!last);
+    return out.subarray(0, outPos);
+  }
+
+  /* Decode base64 and inflate the result */
+  function inflateBase64Bytes(data) {
+    var raw = atob(data);
+    var bytes = new Uint8Array(raw.length);
+    for (var i = 0; i < raw.length; i++) bytes[i] = raw.charCodeAt(i);
+    return inflate(bytes);
+  }
+
+  return {
+    inflateBase64Bytes: inflateBase64Bytes,
+    /* Decode base64, inflate and decode the result as UTF-8 */
+    inflateBase64: function (data) {
+      return new TextDecoder().decode(inflateBase64Bytes(data));
+    },
+  };
+})();
@@ -0,0 +1,166 @@
+//! Embedded stroke font used to render text drawings
+
+use jzon::{array, object, JsonValue};
+
+/// Glyph definitions
+///
+/// Each glyph consists of space-separated polylines, each polyline being a
+/// sequence of hexadecimal (x, y) digit pairs. The Y axis points downwards,
+/// with 0 being the cap height, 4 the x-height, 12 the baseline and 15 the
+/// descender line.
+const GLYPHS: &[(char, &str)] = &[
+  ('!', "1018 1b1c"),
+  ('"', "1013 3033"),
+  ('#', "302c 605c 0484 0878"),
+  ('$', "8361210304266688896b2b09 404c"),
+  ('%', "0c80 102031322313020110 69798a8b7c6c5b5a69"),
+  ('&', "8c131120405153080a2c4c87"),
+  ('\'', "1013"),
+  ('(', "2003092c"),
+  (')', "0023290c"),
+  ('*', "3238 0367 6307"),
+  ('+', "424a 0686"),
+  (',', "1b1c0e"),
+  ('-', "0666"),
+  ('.', "0b0c"),
+  ('/', "0c60"),
+  ('0', "2060828a6c2c0a0220 820a"),
+  ('1', "22404c 2c6c"),
+  ('2', "02206082840c8c"),
+  ('3', "022060828466888a6c2c0a 3666"),
+  ('4', "6c600888"),
+  ('5', "80000565878a6c2c0a"),
+  ('6', "7030030a2c6c8a87652507"),
+  ('7', "00803c"),
+  ('8', "26040220608284662608 0a2c6c8a8866"),
+  ('9', "1c5c898260200205276785"),
+  (':', "0405 0b0c"),
+  (';', "1415 1b1c0e"),
+  ('<', "81068b"),
+  ('=', "0484 0888"),
+  ('>', "01860b"),
+  ('?', "02206082844648 4b4c"),
+  ('@', "6864342527386886826020020a2c7c"),
+  ('A', "0c408c 1979"),
+  ('B', "0c006082846606 66888a6c0c"),
+  ('C', "826020020a2c6c8a"),
+  ('D', "005083895c0c00"),
+  ('E', "80000c8c 0666"),
+  ('F', "80000c 0666"),
+  ('G', "826020020a2c6c8a8656"),
+  ('H', "000c 808c 0686"),
+  ('I', "404c 2060 2c6c"),
+  ('J', "606a4c2c0a"),
+  ('K', "000c 8008 268c"),
+  ('L', "000c8c"),
+  ('M', "0c0046808c"),
+  ('N', "0c008c80"),
+  ('O', "2060828a6c2c0a0220"),
+  ('P', "0c006082846606"),
+  ('Q', "2060828a6c2c0a0220 598c"),
+  ('R', "0c006082846606 468c"),
+  ('S', "82602002042666888a6c2c0a"),
+  ('T', "0080 404c"),
+  ('U', "000a2c6c8a80"),
+  ('V', "004c80"),
+  ('W', "002c466c80"),
+  ('X', "008c 800c"),
+  ('Y', "004680 464c"),
+  ('Z', "00800c8c"),
+  ('[', "30000c3c"),
+  ('\\', "006c"),
+  (']', "00303c0c"),
+  ('^', "044084"),
+  ('_', "0e8e"),
+  ('`', "0022"),
+  ('a', "051454656c 6717080b1c5c6b"),
+  ('b', "000c 062454656b5c2c0a"),
+  ('c', "655414050b1c5c6b"),
+  ('d', "606c 664414050b1c4c6a"),
+  ('e', "0868655414050b1c5c6b"),
+  ('f', "5030212c 0444"),
+  ('g', "646e5f1f0e 664414050a1b4b69"),
+  ('h', "000c 062454656c"),
+  ('i', "141c 1112"),
+  ('j', "242e1f0f 2122"),
+  ('k', "000c 5409 276c"),
+  ('l', "101b2c"),
+  ('m', "040c 051434454c 455474858c"),
+  ('n', "040c 062454656c"),
+  ('o', "1454656b5c1c0b0514"),
+  ('p', "040f 062454656b5c2c0a"),
+  ('q', "646f 664414050b1c4c6a"),
+  ('r', "040c 073454"),
+  ('s', "65541405071858696b5c1c0b"),
+  ('t', "212b3c5c 0444"),
+  ('u', "040b1c4c6a 646c"),
+  ('v', "043c64"),
+  ('w', "042c466c84"),
+  ('x', "046c 640c"),
+  ('y', "043c 643c2e1f0f"),
+  ('z', "04640c6c"),
+  ('{', "3020111506171b2c3c"),
+  ('|', "000e"),
+  ('}', "0010212536272b1c0c"),
+  ('~', "071636587887"),
+];
+
+/// Cap height in glyph units
+const CAP_HEIGHT: f64 = 12.0;
+
+/// Horizontal spacing on each side of a glyph in glyph units
+const SPACING: f64 = 1.5;
+
+/// Width of the space character in glyph units
+const SPACE_WIDTH: f64 = 8.0;
+
+/// Get glyph in the format expected by the viewer's `font_data`
+///
+/// Characters not contained in the font are rendered as '?'.
+pub(crate) fn glyph_json(c: char) -> JsonValue {
+  if c == ' ' || c == '\t' {
+    return object! { w: SPACE_WIDTH / CAP_HEIGHT, l: array![] };
+  }
+  let find = |c: char| GLYPHS.iter().find(|(g, _)| *g == c).map(|g| g.1);
+  let strokes = find(c).or_else(|| find('?')).unwrap_or_default();
+  let polylines = strokes
+    .split(' ')
+    .map(|stroke| {
+      stroke
+        .as_bytes()
+        .chunks(2)
+        .map(|xy| {
+          let digit = |d: u8| (d as char).to_digit(16).unwrap_or(0) as f64;
+          (digit(xy[0]), digit(xy[1]))
+        })
+        .collect::<Vec<_>>()
+    })
+    .collect::<Vec<_>>();
+  let min_x = polylines
+    .iter()
+    .flatten()
+    .map(|p| p.0)
+    .fold(f64::INFINITY, f64::min);
+  let max_x = polylines
+    .iter()
+    .flatten()
+    .map(|p| p.0)
+    .fold(f64::NEG_INFINITY, f64::max);
+  let mut lines = array![];
+  for polyline in &polylines {
+    let mut line = array![];
+    for (x, y) in polyline {
+      line
+        .push(array![
+          (x - min_x + SPACING) / CAP_HEIGHT,
+          (y - CAP_HEIGHT) / CAP_HEIGHT
+        ])
+        .unwrap();
+    }
+    lines.push(line).unwrap();
+  }
+  object! {
+    w: (max_x - min_x + 2.0 * SPACING) / CAP_HEIGHT,
+    l: lines,
+  }
+}
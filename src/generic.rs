@@ -0,0 +1,508 @@
+//! Reader for the generic JSON format of the upstream InteractiveHtmlBom
+//!
+//! The format is the input of the Python tool's generic JSON parser and the
+//! output of [InteractiveHtmlBom::generate_generic_json]. It contains the
+//! board data as drawn by the viewer, thus coordinates are already in
+//! millimeters with the Y axis pointing downwards.
+
+use crate::{
+  rotate, Drawing, DrawingKind, DrawingLayer, Footprint, GenerateError,
+  InputFormat, InteractiveHtmlBom, Layer, NetId, Pad, PadCorner, PadShape,
+  RefMap, SvgPathBuilder, Track, Via, Zone,
+};
+use jzon::JsonValue;
+
+/// Supported value of the "spec_version" key
+const SPEC_VERSION: u32 = 1;
+
+/// Approximate text width per character, relative to the character width
+const CHAR_WIDTH: f64 = 0.8;
+
+/// Maximum angle of the straight segments approximating arc tracks [°]
+const ARC_STEP: f64 = 15.0;
+
+fn number(value: &JsonValue, key: &str) -> Result<f64, String> {
+  value[key]
+    .as_f64()
+    .ok_or_else(|| format!("missing or invalid number \"{}\"", key))
+}
+
+/// Get an optional number
+fn number_or(
+  value: &JsonValue,
+  key: &str,
+  default: f64,
+) -> Result<f64, String> {
+  match value[key].is_null() {
+    true => Ok(default),
+    false => number(value, key),
+  }
+}
+
+/// Get a point stored as `[x, y]`
+fn point(value: &JsonValue, key: &str) -> Result<(f64, f64), String> {
+  let array = &value[key];
+  match (array.len(), array[0].as_f64(), array[1].as_f64()) {
+    (2, Some(x), Some(y)) => Ok((x, y)),
+    _ => Err(format!("missing or invalid point \"{}\"", key)),
+  }
+}
+
+/// Get a flag, which is either a boolean or a number
+fn flag(value: &JsonValue, key: &str, default: bool) -> bool {
+  let value = &value[key];
+  value
+    .as_bool()
+    .or_else(|| value.as_f64().map(|x| x != 0.0))
+    .unwrap_or(default)
+}
+
+fn layer(value: &JsonValue) -> Result<Layer, String> {
+  match value.as_str() {
+    Some("F") => Ok(Layer::Front),
+    Some("B") => Ok(Layer::Back),
+    _ => Err(format!("invalid layer {}", value)),
+  }
+}
+
+fn net(bom: &mut InteractiveHtmlBom, value: &JsonValue) -> Option<NetId> {
+  value["net"].as_str().map(|name| bom.add_net(name))
+}
+
+/// Build the SVG path of polygons given by their vertices
+fn polygons_svgpath(
+  polygons: &JsonValue,
+  pos: (f64, f64),
+  angle: f64,
+) -> Result<String, String> {
+  let mut path = SvgPathBuilder::new();
+  for polygon in polygons.members() {
+    for (index, vertex) in polygon.members().enumerate() {
+      let p = match (vertex[0].as_f64(), vertex[1].as_f64()) {
+        (Some(x), Some(y)) => rotate((x, y), angle),
+        _ => return Err(format!("invalid polygon vertex {}", vertex)),
+      };
+      let p = (pos.0 + p.0, pos.1 + p.1);
+      match index {
+        0 => path.move_to(p),
+        _ => path.line_to(p),
+      };
+    }
+    path.close();
+  }
+  Ok(path.build())
+}
+
+/// Get the SVG path of an object, either given directly or as polygons
+fn svgpath(value: &JsonValue) -> Result<String, String> {
+  match value["svgpath"].as_str() {
+    Some(svgpath) => Ok(svgpath.to_owned()),
+    None => polygons_svgpath(
+      &value["polygons"],
+      point(value, "pos").unwrap_or((0.0, 0.0)),
+      number_or(value, "angle", 0.0)?,
+    ),
+  }
+}
+
+/// Convert a text drawing, whose position is its anchor point
+fn text(
+  value: &JsonValue,
+  kind: DrawingKind,
+  layer: DrawingLayer,
+) -> Result<Drawing, String> {
+  let content = value["text"].as_str().unwrap_or_default();
+  let pos = point(value, "pos")?;
+  let size = (number(value, "width")?, number(value, "height")?);
+  let angle = number_or(value, "angle", 0.0)?;
+  let lines = content.lines().count().max(1) as f64;
+  let width = content
+    .lines()
+    .map(|x| x.chars().count())
+    .max()
+    .unwrap_or(0);
+  let width = width as f64 * size.0 * CHAR_WIDTH;
+  // Justification is -1 (left/top), 0 (center) or 1 (right/bottom).
+  let justify = &value["justify"];
+  let jx = justify[0].as_f64().unwrap_or(0.0);
+  let jy = justify[1].as_f64().unwrap_or(0.0);
+  let mirrored = value["attr"]
+    .members()
+    .any(|x| x.as_str() == Some("mirrored"));
+  let dx = -jx * width / 2.0;
+  let dx = if mirrored { -dx } else { dx };
+  let (dx, dy) = rotate((dx, -jy * size.1 * lines / 2.0), angle);
+  Ok(Drawing::text(
+    kind,
+    layer,
+    content,
+    (pos.0 + dx, pos.1 + dy),
+    size,
+    angle,
+    number_or(value, "thickness", 0.0)?,
+  ))
+}
+
+/// Convert a drawing of any type
+fn drawing(value: &JsonValue, layer: DrawingLayer) -> Result<Drawing, String> {
+  let kind = if flag(value, "ref", false) {
+    DrawingKind::ReferenceText
+  } else if flag(value, "val", false) {
+    DrawingKind::ValueText
+  } else {
+    DrawingKind::Text
+  };
+  let width = || number_or(value, "width", 0.0);
+  let drawing = match value["type"].as_str() {
+    None if value.has_key("text") => text(value, kind, layer)?,
+    None => {
+      let thickness = number_or(value, "thickness", 0.0)?;
+      Drawing::new(kind, layer, &svgpath(value)?, thickness, false)
+    }
+    Some("segment") => {
+      let svgpath = SvgPathBuilder::new()
+        .move_to(point(value, "start")?)
+        .line_to(point(value, "end")?)
+        .build();
+      Drawing::polygon(layer, &svgpath, width()?, false)
+    }
+    Some("rect") => {
+      let (a, b) = (point(value, "start")?, point(value, "end")?);
+      let points = [a, (b.0, a.1), b, (a.0, b.1)];
+      let filled = flag(value, "filled", false);
+      Drawing::polygon_from_points(layer, points, width()?, filled)
+    }
+    Some("circle") => {
+      let center = point(value, "start")?;
+      let radius = number(value, "radius")?;
+      let arc = Drawing::arc(layer.clone(), center, radius, 0.0, 360.0, 0.0);
+      let filled = flag(value, "filled", false);
+      Drawing::polygon(layer, arc.svgpath(), width()?, filled)
+    }
+    Some("arc") if value.has_key("svgpath") => {
+      Drawing::polygon(layer, &svgpath(value)?, width()?, false)
+    }
+    Some("arc") => {
+      // Drawn like a canvas arc, i.e. always in positive direction.
+      let start = number(value, "startangle")?;
+      let sweep = (number(value, "endangle")? - start).rem_euclid(360.0);
+      Drawing::arc(
+        layer,
+        point(value, "start")?,
+        number(value, "radius")?,
+        start,
+        start + sweep,
+        width()?,
+      )
+    }
+    Some("curve") => {
+      let svgpath = SvgPathBuilder::new()
+        .move_to(point(value, "start")?)
+        .cubic_to(
+          point(value, "cpa")?,
+          point(value, "cpb")?,
+          point(value, "end")?,
+        )
+        .build();
+      Drawing::polygon(layer, &svgpath, width()?, false)
+    }
+    Some("polygon") => {
+      let filled = flag(value, "filled", true);
+      Drawing::polygon(layer, &svgpath(value)?, width()?, filled)
+    }
+    Some(other) => return Err(format!("unknown drawing type \"{}\"", other)),
+  };
+  Ok(drawing)
+}
+
+/// Convert a footprint pad
+fn pad(bom: &mut InteractiveHtmlBom, value: &JsonValue) -> Result<Pad, String> {
+  let layers = value["layers"]
+    .members()
+    .map(layer)
+    .collect::<Result<Vec<_>, _>>()?;
+  let mut pos = point(value, "pos")?;
+  let angle = number_or(value, "angle", 0.0)?;
+  let size = || point(value, "size");
+  let shape = match value["shape"].as_str().unwrap_or_default() {
+    "rect" => PadShape::Rect(size()?),
+    "circle" => PadShape::Circle(size()?.0),
+    "oval" => PadShape::Oval(size()?),
+    "roundrect" => PadShape::RoundRect(size()?, number(value, "radius")?),
+    "chamfrect" => {
+      let size = size()?;
+      let radius = number_or(value, "radius", 0.0)?;
+      let chamfer = number(value, "chamfratio")? * size.0.min(size.1);
+      let chamfpos = number(value, "chamfpos")? as u32;
+      let corners = [0, 1, 2, 3].map(|i| match chamfpos & (1 << i) {
+        0 if radius > 0.0 => PadCorner::Round(radius),
+        0 => PadCorner::Sharp,
+        _ => PadCorner::Chamfer(chamfer),
+      });
+      PadShape::Corners(size, corners)
+    }
+    "custom" => PadShape::Custom(match value["svgpath"].as_str() {
+      Some(svgpath) => svgpath.to_owned(),
+      None => polygons_svgpath(&value["polygons"], (0.0, 0.0), 0.0)?,
+    }),
+    other => return Err(format!("unknown pad shape \"{}\"", other)),
+  };
+  let drill = match value["type"].as_str() {
+    Some("th") => Some(point(value, "drillsize")?),
+    _ => None,
+  };
+  // The position is the drill position, with the copper shape offset.
+  let offset = match value["offset"].is_null() {
+    true => (0.0, 0.0),
+    false => point(value, "offset")?,
+  };
+  let drill_offset = (-offset.0 + 0.0, -offset.1 + 0.0);
+  let (dx, dy) = rotate(drill_offset, angle);
+  pos = (pos.0 - dx, pos.1 - dy);
+  let pin1 = flag(value, "pin1", false);
+  let mut pad = Pad::with_shape(layers, pos, angle, shape, drill, None, pin1);
+  if drill_offset != (0.0, 0.0) {
+    pad.set_drill_offset(drill_offset);
+  }
+  pad.set_net_id(net(bom, value));
+  let (number, name) = (value["pinnumber"].as_str(), value["pinname"].as_str());
+  if number.is_some() || name.is_some() {
+    pad.set_pin(number, name);
+  }
+  Ok(pad)
+}
+
+/// Add a footprint with its component
+fn add_footprint(
+  bom: &mut InteractiveHtmlBom,
+  value: &JsonValue,
+  component: &JsonValue,
+) -> Result<RefMap, String> {
+  let bbox = &value["bbox"];
+  let relpos = point(bbox, "relpos")?;
+  let size = point(bbox, "size")?;
+  let pads = value["pads"]
+    .members()
+    .map(|x| pad(bom, x))
+    .collect::<Result<Vec<_>, _>>()?;
+  let text = |key: &str| component[key].as_str().unwrap_or("").to_string();
+  let fields = bom.fields.iter().map(|name| match name.as_str() {
+    "Value" => text("val"),
+    "Footprint" => text("footprint"),
+    name => component["extra_fields"]
+      .entries()
+      .find(|(key, _)| *key == name)
+      .and_then(|(_, value)| value.as_str())
+      .map(str::to_string)
+      .unwrap_or_default(),
+  });
+  let mut fpt = Footprint::new(
+    layer(&value["layer"])?,
+    point(bbox, "pos")?,
+    number_or(bbox, "angle", 0.0)?,
+    relpos,
+    (relpos.0 + size.0, relpos.1 + size.1),
+    fields.collect::<Vec<_>>(),
+    pads,
+    component["attr"].as_str() != Some("Virtual"),
+  );
+  // The drawing layers within footprints are not distinguished.
+  for drawing_value in value["drawings"].members() {
+    let layer = match layer(&drawing_value["layer"])? {
+      Layer::Front => DrawingLayer::SilkscreenFront,
+      Layer::Back => DrawingLayer::SilkscreenBack,
+    };
+    fpt.add_drawing(drawing(&drawing_value["drawing"], layer)?);
+  }
+  Ok(RefMap::new(&text("ref"), bom.add_footprint(fpt)))
+}
+
+/// Add the tracks and vias of both layers
+fn add_tracks(
+  bom: &mut InteractiveHtmlBom,
+  tracks: &JsonValue,
+) -> Result<(), String> {
+  // Vias are listed on each layer they exist, thus merge identical ones.
+  let mut vias: Vec<(&JsonValue, Vec<Layer>)> = Vec::new();
+  for (key, layer) in [("F", Layer::Front), ("B", Layer::Back)] {
+    for track in tracks[key].members() {
+      if track.has_key("drillsize") {
+        match vias.iter_mut().find(|(x, _)| *x == track) {
+          Some((_, layers)) => layers.push(layer.clone()),
+          None => vias.push((track, vec![layer.clone()])),
+        }
+        continue;
+      }
+      let width = number(track, "width")?;
+      let mut points = Vec::new();
+      if track.has_key("center") {
+        // Arcs are approximated by straight segments.
+        let center = point(track, "center")?;
+        let radius = number(track, "radius")?;
+        let start = number(track, "startangle")?;
+        let sweep = (number(track, "endangle")? - start).rem_euclid(360.0);
+        let steps = (sweep / ARC_STEP).ceil().max(1.0) as usize;
+        for i in 0..=steps {
+          let angle = (start + sweep * i as f64 / steps as f64).to_radians();
+          points.push((
+            center.0 + radius * angle.cos(),
+            center.1 + radius * angle.sin(),
+          ));
+        }
+      } else {
+        points.extend([point(track, "start")?, point(track, "end")?]);
+      }
+      let net = net(bom, track);
+      for pair in points.windows(2) {
+        let track = Track::with_net_id(
+          layer.clone(),
+          pair[0],
+          pair[1],
+          width,
+          net.clone(),
+        );
+        bom.tracks.push(track);
+      }
+    }
+  }
+  for (value, layers) in vias {
+    let pos = point(value, "start")?;
+    let diameter = number(value, "width")?;
+    let drill = number(value, "drillsize")?;
+    let net = net(bom, value);
+    let via = Via::with_net_id(layers, pos, diameter, drill, net);
+    bom.vias.push(via);
+  }
+  Ok(())
+}
+
+/// Add the zones of both layers
+fn add_zones(
+  bom: &mut InteractiveHtmlBom,
+  zones: &JsonValue,
+) -> Result<(), String> {
+  for (key, layer) in [("F", Layer::Front), ("B", Layer::Back)] {
+    for value in zones[key].members() {
+      let svgpath = svgpath(value)?;
+      // With even-odd filling, all contours after the first are holes.
+      // Malformed paths are kept as-is and reported when generating.
+      let subpaths = match value["fillrule"].as_str() {
+        Some("evenodd") => {
+          crate::svgpath::subpaths(&svgpath).unwrap_or_default()
+        }
+        _ => Vec::new(),
+      };
+      let mut zone = match subpaths.split_first() {
+        Some((outline, holes)) if !holes.is_empty() => {
+          let holes: Vec<&str> = holes.iter().map(String::as_str).collect();
+          Zone::with_holes(layer.clone(), outline, &holes, None)
+        }
+        _ => Zone::new(layer.clone(), &svgpath, None),
+      };
+      zone.set_net_id(net(bom, value));
+      bom.zones.push(zone);
+    }
+  }
+  Ok(())
+}
+
+/// Read a generic JSON file
+///
+/// # Arguments
+///
+/// * `json` - Content of the JSON file.
+///
+/// # Returns
+///
+/// Returns the populated object, or an error if the file is invalid.
+pub(crate) fn import(json: &str) -> Result<InteractiveHtmlBom, GenerateError> {
+  import_json(json).map_err(|reason| GenerateError::InvalidInput {
+    format: InputFormat::GenericJson,
+    reason,
+  })
+}
+
+fn import_json(json: &str) -> Result<InteractiveHtmlBom, String> {
+  let json = jzon::parse(json).map_err(|err| err.to_string())?;
+  let version = &json["spec_version"];
+  if version.as_u32() != Some(SPEC_VERSION) {
+    return Err(format!("unsupported spec_version {}", version));
+  }
+  let pcbdata = &json["pcbdata"];
+  let metadata = |key: &str| {
+    pcbdata["metadata"][key]
+      .as_str()
+      .unwrap_or_default()
+      .to_string()
+  };
+  let bbox = &pcbdata["edges_bbox"];
+  let mut bom = InteractiveHtmlBom::new(
+    &metadata("title"),
+    &metadata("company"),
+    &metadata("revision"),
+    &metadata("date"),
+    (number(bbox, "minx")?, number(bbox, "miny")?),
+    (number(bbox, "maxx")?, number(bbox, "maxy")?),
+  );
+  for name in pcbdata["nets"].members().filter_map(JsonValue::as_str) {
+    bom.add_net(name);
+  }
+
+  for value in pcbdata["edges"].members() {
+    bom.drawings.push(drawing(value, DrawingLayer::Edge)?);
+  }
+  for (kind, front, back) in [
+    (
+      "silkscreen",
+      DrawingLayer::SilkscreenFront,
+      DrawingLayer::SilkscreenBack,
+    ),
+    (
+      "fabrication",
+      DrawingLayer::FabricationFront,
+      DrawingLayer::FabricationBack,
+    ),
+  ] {
+    for (key, layer) in [("F", front), ("B", back)] {
+      for value in pcbdata["drawings"][kind][key].members() {
+        bom.drawings.push(drawing(value, layer.clone())?);
+      }
+    }
+  }
+  add_tracks(&mut bom, &pcbdata["tracks"])?;
+  add_zones(&mut bom, &pcbdata["zones"])?;
+
+  // Fields are the value, the footprint and all extra fields in the order
+  // of their first occurrence.
+  let footprints = &pcbdata["footprints"];
+  let components = &json["components"];
+  if components.len() != footprints.len() {
+    return Err(format!(
+      "{} footprints but {} components",
+      footprints.len(),
+      components.len()
+    ));
+  }
+  bom.fields = vec!["Value".into(), "Footprint".into()];
+  for component in components.members() {
+    for (name, _) in component["extra_fields"].entries() {
+      let name = name.to_string();
+      if !bom.fields.contains(&name) {
+        bom.fields.push(name);
+      }
+    }
+  }
+  let mut refs = Vec::new();
+  for (index, (value, component)) in
+    footprints.members().zip(components.members()).enumerate()
+  {
+    let refmap = add_footprint(&mut bom, value, component)
+      .map_err(|reason| format!("footprint {}: {}", index, reason))?;
+    refs.push(refmap);
+  }
+
+  bom
+    .group_bom(&refs, &["Value", "Footprint"])
+    .map_err(|err| err.to_string())?;
+  Ok(bom)
+}
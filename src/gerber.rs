@@ -0,0 +1,658 @@
+//! Reader for Gerber (RS-274X) files
+//!
+//! Converts the image of a Gerber file into SVG paths. Draws become stroked
+//! paths, flashes and regions become filled paths. Objects with clear
+//! polarity, step and repeat blocks as well as thermal macro primitives are
+//! not supported and ignored. The Y axis is mirrored to point downwards.
+
+use std::collections::BTreeMap;
+use std::f64::consts::PI;
+use std::fmt::Write;
+
+/// Object of a Gerber file
+#[derive(Debug)]
+pub(crate) struct Shape {
+  /// Outline as SVG path \[mm\]
+  pub path: String,
+  /// Line width \[mm\]
+  pub width: f64,
+  /// Whether the outline is filled or stroked
+  pub filled: bool,
+}
+
+/// Part of an aperture, relative to its origin \[file units\]
+#[derive(Clone, Debug)]
+enum Part {
+  Circle((f64, f64), f64),
+  Polygon(Vec<(f64, f64)>),
+  Obround(f64, f64),
+}
+
+/// Rotate a point counterclockwise around the origin
+fn rotate(p: (f64, f64), angle: f64) -> (f64, f64) {
+  let (sin, cos) = angle.to_radians().sin_cos();
+  (p.0 * cos - p.1 * sin, p.0 * sin + p.1 * cos)
+}
+
+/// Evaluate an aperture macro arithmetic expression
+fn evaluate(expr: &str, vars: &BTreeMap<usize, f64>) -> Result<f64, String> {
+  struct Eval<'a> {
+    chars: Vec<char>,
+    pos: usize,
+    vars: &'a BTreeMap<usize, f64>,
+  }
+  impl Eval<'_> {
+    fn peek(&self) -> Option<char> {
+      self.chars.get(self.pos).copied()
+    }
+    fn sum(&mut self) -> Option<f64> {
+      let mut value = self.product()?;
+      while let Some(op @ ('+' | '-')) = self.peek() {
+        self.pos += 1;
+        let rhs = self.product()?;
+        value = if op == '+' { value + rhs } else { value - rhs };
+      }
+      Some(value)
+    }
+    fn product(&mut self) -> Option<f64> {
+      let mut value = self.factor()?;
+      while let Some(op @ ('x' | 'X' | '/')) = self.peek() {
+        self.pos += 1;
+        let rhs = self.factor()?;
+        value = if op == '/' { value / rhs } else { value * rhs };
+      }
+      Some(value)
+    }
+    fn factor(&mut self) -> Option<f64> {
+      match self.peek()? {
+        '+' => {
+          self.pos += 1;
+          self.factor()
+        }
+        '-' => {
+          self.pos += 1;
+          Some(-self.factor()?)
+        }
+        '(' => {
+          self.pos += 1;
+          let value = self.sum()?;
+          (self.peek()? == ')').then_some(())?;
+          self.pos += 1;
+          Some(value)
+        }
+        '$' => {
+          self.pos += 1;
+          let start = self.pos;
+          while self.peek().is_some_and(|c| c.is_ascii_digit()) {
+            self.pos += 1;
+          }
+          let index: String = self.chars[start..self.pos].iter().collect();
+          Some(self.vars.get(&index.parse().ok()?).copied().unwrap_or(0.0))
+        }
+        _ => {
+          let start = self.pos;
+          while self.peek().is_some_and(|c| c.is_ascii_digit() || c == '.') {
+            self.pos += 1;
+          }
+          let number: String = self.chars[start..self.pos].iter().collect();
+          number.parse().ok()
+        }
+      }
+    }
+  }
+  let mut eval = Eval {
+    chars: expr.chars().filter(|c| !c.is_whitespace()).collect(),
+    pos: 0,
+    vars,
+  };
+  match eval.sum() {
+    Some(value) if eval.pos == eval.chars.len() => Ok(value),
+    _ => Err(format!("invalid expression \"{}\"", expr)),
+  }
+}
+
+/// Instantiate an aperture macro with the given parameters
+fn instantiate(body: &[String], params: &[f64]) -> Result<Vec<Part>, String> {
+  let mut vars: BTreeMap<usize, f64> = params
+    .iter()
+    .enumerate()
+    .map(|(i, x)| (i + 1, *x))
+    .collect();
+  let mut parts = Vec::new();
+  for statement in body {
+    if let Some(definition) = statement.strip_prefix('$') {
+      let (index, expr) = definition
+        .split_once('=')
+        .ok_or_else(|| format!("invalid macro statement \"{}\"", statement))?;
+      let index = index
+        .trim()
+        .parse()
+        .map_err(|_| format!("invalid macro variable \"{}\"", statement))?;
+      vars.insert(index, evaluate(expr, &vars)?);
+      continue;
+    }
+    let mut fields = statement.split(',');
+    let code = fields.next().unwrap_or_default().trim();
+    let values = fields
+      .map(|x| evaluate(x, &vars))
+      .collect::<Result<Vec<_>, _>>()?;
+    let get = |i: usize| values.get(i).copied().unwrap_or(0.0);
+    // Clear exposure is not supported.
+    if code != "0" && get(0) == 0.0 {
+      continue;
+    }
+    let rotated = |points: Vec<(f64, f64)>, angle: f64| {
+      Part::Polygon(points.into_iter().map(|p| rotate(p, angle)).collect())
+    };
+    match code {
+      "1" => {
+        let center = rotate((get(2), get(3)), get(4));
+        parts.push(Part::Circle(center, get(1) / 2.0));
+      }
+      "20" => {
+        let (start, end) = ((get(2), get(3)), (get(4), get(5)));
+        let len = (end.0 - start.0).hypot(end.1 - start.1);
+        if len > 0.0 {
+          let w = get(1) / 2.0;
+          let n = ((start.1 - end.1) / len * w, (end.0 - start.0) / len * w);
+          let points = vec![
+            (start.0 + n.0, start.1 + n.1),
+            (end.0 + n.0, end.1 + n.1),
+            (end.0 - n.0, end.1 - n.1),
+            (start.0 - n.0, start.1 - n.1),
+          ];
+          parts.push(rotated(points, get(6)));
+        }
+      }
+      "21" => {
+        let (w, h, x, y) = (get(1) / 2.0, get(2) / 2.0, get(3), get(4));
+        let points = vec![
+          (x - w, y - h),
+          (x + w, y - h),
+          (x + w, y + h),
+          (x - w, y + h),
+        ];
+        parts.push(rotated(points, get(5)));
+      }
+      "4" => {
+        let count = get(1) as usize + 1;
+        if values.len() < 2 * count + 3 {
+          return Err("invalid outline primitive".into());
+        }
+        let points = (0..count)
+          .map(|i| (get(2 + 2 * i), get(3 + 2 * i)))
+          .collect();
+        parts.push(rotated(points, get(2 + 2 * count)));
+      }
+      "5" => {
+        let (count, center, r) =
+          (get(1) as usize, (get(2), get(3)), get(4) / 2.0);
+        let points = (0..count)
+          .map(|i| {
+            let angle = 2.0 * PI * i as f64 / count as f64;
+            (center.0 + r * angle.cos(), center.1 + r * angle.sin())
+          })
+          .collect();
+        parts.push(rotated(points, get(5)));
+      }
+      // Comments, and thermals which are not supported.
+      _ => {}
+    }
+  }
+  Ok(parts)
+}
+
+/// Aperture, i.e. the shape of flashes and the width of draws
+#[derive(Clone, Debug)]
+struct Aperture {
+  parts: Vec<Part>,
+  width: f64,
+}
+
+/// Parser state
+struct Parser {
+  /// Scale from file units to millimeters
+  scale: f64,
+  /// Number of integer and decimal digits of coordinates
+  format: (i32, i32),
+  /// Whether trailing instead of leading zeros are omitted
+  trailing: bool,
+  macros: BTreeMap<String, Vec<String>>,
+  apertures: BTreeMap<u32, Aperture>,
+  aperture: Option<u32>,
+  pos: (f64, f64),
+  interpolation: u32,
+  operation: u32,
+  single_quadrant: bool,
+  dark: bool,
+  /// Path of the current region and whether a contour is open
+  region: Option<(String, bool)>,
+  /// Path, aperture and end point of the current draw
+  stroke: Option<(String, u32, (f64, f64))>,
+  shapes: Vec<Shape>,
+}
+
+impl Parser {
+  /// Convert to millimeters, rounded to get rid of floating point noise
+  fn mm(&self, value: f64) -> f64 {
+    (value * self.scale * 1e6).round() / 1e6 + 0.0
+  }
+
+  fn point(&self, p: (f64, f64)) -> String {
+    format!("{} {}", self.mm(p.0), self.mm(-p.1))
+  }
+
+  fn coordinate(&self, value: &str) -> Result<f64, String> {
+    let invalid = || format!("invalid coordinate \"{}\"", value);
+    if value.contains('.') {
+      return value.parse().map_err(|_| invalid());
+    }
+    let digits = value.trim_start_matches(['+', '-']);
+    let mut number: f64 = digits.parse().map_err(|_| invalid())?;
+    if self.trailing {
+      let total = self.format.0 + self.format.1;
+      number *= 10f64.powi(total - digits.len() as i32);
+    }
+    number /= 10f64.powi(self.format.1);
+    Ok(if value.starts_with('-') {
+      -number
+    } else {
+      number
+    })
+  }
+
+  fn flush_stroke(&mut self) {
+    if let Some((path, aperture, _)) = self.stroke.take() {
+      let width = self.apertures.get(&aperture).map_or(0.0, |x| x.width);
+      self.shapes.push(Shape {
+        path,
+        width: width * self.scale,
+        filled: false,
+      });
+    }
+  }
+
+  /// Get the SVG path segment from the current position to `end`
+  fn segment(&self, end: (f64, f64), offset: (f64, f64)) -> String {
+    if self.interpolation == 1 {
+      return format!(" L {}", self.point(end));
+    }
+    let start = self.pos;
+    let ccw = self.interpolation == 3;
+    let angle = |p: (f64, f64), c: (f64, f64)| (p.1 - c.1).atan2(p.0 - c.0);
+    let sweep = |c: (f64, f64)| {
+      let delta = match ccw {
+        true => angle(end, c) - angle(start, c),
+        false => angle(start, c) - angle(end, c),
+      };
+      delta.rem_euclid(2.0 * PI)
+    };
+    let center = if self.single_quadrant {
+      // Offsets are unsigned, pick the center resulting in a quarter arc.
+      let error = |c: &(f64, f64)| {
+        let r0 = (start.0 - c.0).hypot(start.1 - c.1);
+        let r1 = (end.0 - c.0).hypot(end.1 - c.1);
+        let quarter = sweep(*c) <= PI / 2.0 + 1e-6;
+        (r0 - r1).abs() + if quarter { 0.0 } else { f64::MAX / 2.0 }
+      };
+      [(1.0, 1.0), (1.0, -1.0), (-1.0, 1.0), (-1.0, -1.0)]
+        .map(|(sx, sy)| (start.0 + sx * offset.0, start.1 + sy * offset.1))
+        .into_iter()
+        .min_by(|a, b| error(a).total_cmp(&error(b)))
+        .unwrap_or(start)
+    } else {
+      (start.0 + offset.0, start.1 + offset.1)
+    };
+    let r = (start.0 - center.0).hypot(start.1 - center.1);
+    let s = self.mm(r);
+    // Mirroring the Y axis reverses the direction.
+    let flag = if ccw { 0 } else { 1 };
+    let mut delta = sweep(center);
+    if delta == 0.0 && !self.single_quadrant {
+      delta = 2.0 * PI;
+    }
+    if delta > PI {
+      // Split into two arcs, e.g. for full circles.
+      let a = angle(start, center) + if ccw { delta } else { -delta } / 2.0;
+      let mid = (center.0 + r * a.cos(), center.1 + r * a.sin());
+      return format!(
+        " A {s} {s} 0 0 {flag} {} A {s} {s} 0 0 {flag} {}",
+        self.point(mid),
+        self.point(end),
+      );
+    }
+    format!(" A {s} {s} 0 0 {flag} {}", self.point(end))
+  }
+
+  /// Add a flash of the current aperture at the given position
+  fn flash(&mut self, at: (f64, f64)) -> Result<(), String> {
+    let aperture = self
+      .aperture
+      .and_then(|x| self.apertures.get(&x))
+      .ok_or("flash without aperture")?;
+    let p = |x: f64, y: f64| self.point((at.0 + x, at.1 + y));
+    let mut path = String::new();
+    for part in &aperture.parts {
+      match part {
+        Part::Circle((x, y), r) => {
+          let s = self.mm(*r);
+          let _ = write!(
+            path,
+            "M {} A {s} {s} 0 0 0 {} A {s} {s} 0 0 0 {} Z ",
+            p(x - r, *y),
+            p(x + r, *y),
+            p(x - r, *y),
+          );
+        }
+        Part::Polygon(points) => {
+          for (i, (x, y)) in points.iter().enumerate() {
+            let cmd = if i == 0 { "M" } else { "L" };
+            let _ = write!(path, "{} {} ", cmd, p(*x, *y));
+          }
+          path += "Z ";
+        }
+        Part::Obround(w, h) => {
+          let r = w.min(*h) / 2.0;
+          let (dx, dy) = (w / 2.0 - r, h / 2.0 - r);
+          let points = match dx > 0.0 {
+            true => [p(-dx, -r), p(dx, -r), p(dx, r), p(-dx, r)],
+            false => [p(r, -dy), p(r, dy), p(-r, dy), p(-r, -dy)],
+          };
+          let s = self.mm(r);
+          let _ = write!(
+            path,
+            "M {} L {} A {s} {s} 0 0 0 {} L {} A {s} {s} 0 0 0 {} Z ",
+            points[0], points[1], points[2], points[3], points[0],
+          );
+        }
+      }
+    }
+    if !path.is_empty() {
+      self.shapes.push(Shape {
+        path: path.trim_end().to_owned(),
+        width: 0.0,
+        filled: true,
+      });
+    }
+    Ok(())
+  }
+
+  /// Process an extended command block (without the `%` delimiters)
+  fn extended(&mut self, block: &str) -> Result<(), String> {
+    let statements: Vec<&str> = block
+      .split('*')
+      .map(str::trim)
+      .filter(|x| !x.is_empty())
+      .collect();
+    let Some(first) = statements.first() else {
+      return Ok(());
+    };
+    let Some((code, args)) = first.split_at_checked(first.len().min(2)) else {
+      return Err(format!("invalid format \"{}\"", first));
+    };
+    match code {
+      "FS" => {
+        self.trailing = args.starts_with('T');
+        let digits = args
+          .split_once('X')
+          .map(|(_, x)| x.chars().take(2).filter_map(|c| c.to_digit(10)))
+          .ok_or_else(|| format!("invalid format \"{}\"", first))?
+          .collect::<Vec<_>>();
+        if digits.len() != 2 {
+          return Err(format!("invalid format \"{}\"", first));
+        }
+        self.format = (digits[0] as i32, digits[1] as i32);
+      }
+      "MO" => {
+        self.scale = match args {
+          "MM" => 1.0,
+          "IN" => 25.4,
+          _ => return Err(format!("invalid unit \"{}\"", args)),
+        };
+      }
+      "AM" => {
+        let body = statements[1..]
+          .iter()
+          .filter(|x| !x.starts_with('0'))
+          .map(|x| x.chars().filter(|c| !c.is_whitespace()).collect())
+          .collect();
+        self.macros.insert(args.to_owned(), body);
+      }
+      "AD" => {
+        let invalid = || format!("invalid aperture \"{}\"", first);
+        let args = args.strip_prefix('D').ok_or_else(invalid)?;
+        let len = args
+          .find(|c: char| !c.is_ascii_digit())
+          .unwrap_or(args.len());
+        let number = args[..len].parse().map_err(|_| invalid())?;
+        let (name, params) =
+          args[len..].split_once(',').unwrap_or((&args[len..], ""));
+        let params = params
+          .split('X')
+          .filter(|x| !x.is_empty())
+          .map(|x| x.trim().parse().map_err(|_| invalid()))
+          .collect::<Result<Vec<f64>, _>>()?;
+        let p = |i: usize| params.get(i).copied().unwrap_or(0.0);
+        let (w, h) = (p(0) / 2.0, p(1) / 2.0);
+        let aperture = match name {
+          "C" => Aperture {
+            parts: vec![Part::Circle((0.0, 0.0), w)],
+            width: p(0),
+          },
+          "R" => Aperture {
+            parts: vec![Part::Polygon(vec![
+              (-w, -h),
+              (w, -h),
+              (w, h),
+              (-w, h),
+            ])],
+            width: p(0).min(p(1)),
+          },
+          "O" => Aperture {
+            parts: vec![Part::Obround(p(0), p(1))],
+            width: p(0).min(p(1)),
+          },
+          "P" => {
+            let count = (p(1) as usize).max(3);
+            let points = (0..count)
+              .map(|i| {
+                let angle = 360.0 * i as f64 / count as f64 + p(2);
+                rotate((w, 0.0), angle)
+              })
+              .collect();
+            Aperture {
+              parts: vec![Part::Polygon(points)],
+              width: p(0),
+            }
+          }
+          name => {
+            let body = self
+              .macros
+              .get(name)
+              .ok_or_else(|| format!("unknown aperture macro \"{}\"", name))?;
+            Aperture {
+              parts: instantiate(body, &params)?,
+              width: 0.0,
+            }
+          }
+        };
+        self.apertures.insert(number, aperture);
+      }
+      "LP" => {
+        self.flush_stroke();
+        self.dark = args != "C";
+      }
+      // Attributes, step and repeat and deprecated commands are ignored.
+      _ => {}
+    }
+    Ok(())
+  }
+
+  /// Process a word command (without the `*` terminator)
+  ///
+  /// Returns whether the end of the file has been reached.
+  fn word(&mut self, word: &str) -> Result<bool, String> {
+    if word.starts_with("G04") || word.starts_with("G4 ") {
+      return Ok(false);
+    }
+    let word: String = word.chars().filter(|c| !c.is_whitespace()).collect();
+    let starts: Vec<usize> = word
+      .char_indices()
+      .filter(|(_, c)| c.is_ascii_alphabetic())
+      .map(|(i, _)| i)
+      .collect();
+    if starts.first() != Some(&0) {
+      return Err(format!("invalid command \"{}\"", word));
+    }
+    let (mut x, mut y, mut i, mut j) = (None, None, None, None);
+    let mut operation = None;
+    for (k, start) in starts.iter().enumerate() {
+      let value =
+        &word[start + 1..starts.get(k + 1).copied().unwrap_or(word.len())];
+      let number = || {
+        value
+          .parse::<u32>()
+          .map_err(|_| format!("invalid command \"{}\"", word))
+      };
+      match &word[*start..start + 1] {
+        "G" => match number()? {
+          code @ 1..=3 => self.interpolation = code,
+          36 => {
+            self.flush_stroke();
+            self.region = Some((String::new(), false));
+          }
+          37 => {
+            if let Some((mut path, open)) = self.region.take() {
+              if open {
+                path += " Z";
+              }
+              if !path.is_empty() && self.dark {
+                self.shapes.push(Shape {
+                  path: path.trim_start().to_owned(),
+                  width: 0.0,
+                  filled: true,
+                });
+              }
+            }
+          }
+          70 => self.scale = 25.4,
+          71 => self.scale = 1.0,
+          74 => self.single_quadrant = true,
+          75 => self.single_quadrant = false,
+          _ => {}
+        },
+        "X" => x = Some(self.coordinate(value)?),
+        "Y" => y = Some(self.coordinate(value)?),
+        "I" => i = Some(self.coordinate(value)?),
+        "J" => j = Some(self.coordinate(value)?),
+        "D" => match number()? {
+          code @ 1..=3 => operation = Some(code),
+          code if self.apertures.contains_key(&code) => {
+            self.flush_stroke();
+            self.aperture = Some(code);
+          }
+          code => return Err(format!("undefined aperture D{}", code)),
+        },
+        "M" => return Ok(matches!(number()?, 0 | 2)),
+        _ => return Err(format!("invalid command \"{}\"", word)),
+      }
+    }
+    let has_coordinates = [x, y, i, j].iter().any(Option::is_some);
+    let operation = match operation {
+      Some(code) => code,
+      // Deprecated modal operation codes.
+      None if has_coordinates => self.operation,
+      None => return Ok(false),
+    };
+    self.operation = operation;
+    let end = (x.unwrap_or(self.pos.0), y.unwrap_or(self.pos.1));
+    let offset = (i.unwrap_or(0.0), j.unwrap_or(0.0));
+    match operation {
+      1 => {
+        let segment = self.segment(end, offset);
+        let start = self.point(self.pos);
+        if let Some((path, open)) = &mut self.region {
+          if !*open {
+            *path += &format!(" M {}", start);
+            *open = true;
+          }
+          *path += &segment;
+        } else if self.dark {
+          let aperture = self.aperture.ok_or("draw without aperture")?;
+          match &mut self.stroke {
+            Some((path, a, last)) if *a == aperture && *last == self.pos => {
+              *path += &segment;
+              *last = end;
+            }
+            _ => {
+              self.flush_stroke();
+              let path = format!("M {}{}", start, segment);
+              self.stroke = Some((path, aperture, end));
+            }
+          }
+        }
+      }
+      2 => {
+        if let Some((path, open)) = &mut self.region {
+          if *open {
+            *path += " Z";
+            *open = false;
+          }
+        }
+      }
+      _ => {
+        if self.dark && self.region.is_none() {
+          self.flash(end)?;
+        }
+      }
+    }
+    self.pos = end;
+    Ok(false)
+  }
+}
+
+/// Read the objects of a Gerber file
+///
+/// # Arguments
+///
+/// * `gerber` - Content of the Gerber file.
+///
+/// # Returns
+///
+/// Returns the objects, or a description of the problem if the file is
+/// invalid.
+pub(crate) fn parse(gerber: &str) -> Result<Vec<Shape>, String> {
+  let mut parser = Parser {
+    scale: 1.0,
+    format: (3, 6),
+    trailing: false,
+    macros: BTreeMap::new(),
+    apertures: BTreeMap::new(),
+    aperture: None,
+    pos: (0.0, 0.0),
+    interpolation: 1,
+    operation: 2,
+    single_quadrant: false,
+    dark: true,
+    region: None,
+    stroke: None,
+    shapes: Vec::new(),
+  };
+  let mut rest = gerber.trim_start();
+  while !rest.is_empty() {
+    if let Some(extended) = rest.strip_prefix('%') {
+      let end = extended.find('%').ok_or("unterminated extended command")?;
+      parser.extended(&extended[..end])?;
+      rest = &extended[end + 1..];
+    } else {
+      let end = rest.find('*').ok_or("unterminated command")?;
+      if parser.word(&rest[..end])? {
+        break;
+      }
+      rest = &rest[end + 1..];
+    }
+    rest = rest.trim_start();
+  }
+  parser.flush_stroke();
+  Ok(parser.shapes)
+}
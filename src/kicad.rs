@@ -0,0 +1,75 @@
+//! Reader for the XML netlist exported by KiCad's schematic editor
+
+use crate::xml;
+
+/// Fields stored as dedicated elements of a component, with their names
+const FIELD_ELEMENTS: [(&str, &str); 4] = [
+  ("Value", "value"),
+  ("Footprint", "footprint"),
+  ("Datasheet", "datasheet"),
+  ("Description", "description"),
+];
+
+/// Component reference and (name, value) pairs of its fields
+pub(crate) type Component = (String, Vec<(String, String)>);
+
+/// Read the component fields from a netlist
+///
+/// # Arguments
+///
+/// * `xml` - Content of the XML netlist.
+///
+/// # Returns
+///
+/// Returns the components, or a description of the problem if the netlist
+/// is invalid.
+pub(crate) fn components(xml: &str) -> Result<Vec<Component>, String> {
+  let root = xml::parse(xml)?;
+  if root.name != "export" {
+    return Err("root element is not <export>".into());
+  }
+
+  let mut components = Vec::new();
+  for comp in root
+    .child("components")
+    .iter()
+    .flat_map(|x| x.children("comp"))
+  {
+    let reference =
+      comp.attribute("ref").ok_or("component without reference")?;
+    let mut fields: Vec<(String, String)> = Vec::new();
+    for (name, element) in FIELD_ELEMENTS {
+      if let Some(element) = comp.child(element) {
+        fields.push((name.into(), element.text.clone()));
+      }
+    }
+    for field in comp
+      .child("fields")
+      .iter()
+      .flat_map(|x| x.children("field"))
+    {
+      if let Some(name) = field.attribute("name") {
+        fields.retain(|(n, _)| n != name);
+        fields.push((name.into(), field.text.clone()));
+      }
+    }
+    // Newer KiCad versions store fields as properties too.
+    for property in comp.children("property") {
+      if let (Some(name), Some(value)) =
+        (property.attribute("name"), property.attribute("value"))
+      {
+        if !fields.iter().any(|(n, _)| n == name) {
+          fields.push((name.into(), value.into()));
+        }
+      }
+    }
+    // KiCad uses "~" for empty fields.
+    for (_, value) in &mut fields {
+      if value == "~" {
+        value.clear();
+      }
+    }
+    components.push((reference.to_owned(), fields));
+  }
+  Ok(components)
+}
@@ -5,6 +5,38 @@
 #![warn(missing_docs)]
 
 use jzon::{array, object, JsonValue};
+use std::borrow::Cow;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::ffi::OsString;
+use std::fs::{self, OpenOptions};
+use std::io::BufWriter;
+use std::ops::{Add, Range, Sub};
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+mod cbor;
+#[cfg(feature = "eagle")]
+mod eagle;
+#[cfg(feature = "gerber")]
+mod excellon;
+mod font;
+#[cfg(feature = "generic")]
+mod generic;
+#[cfg(feature = "gerber")]
+mod gerber;
+#[cfg(feature = "kicad")]
+mod kicad;
+#[cfg(feature = "librepcb")]
+mod librepcb;
+mod minify;
+#[cfg(feature = "pdf")]
+mod pdf;
+#[cfg(feature = "pnp")]
+mod pnp;
+mod svgpath;
+#[cfg(any(feature = "eagle", feature = "kicad"))]
+mod xml;
 
 trait ToJson {
   fn to_json(&self) -> JsonValue;
@@ -22,12 +54,18 @@ impl ToJson for String {
   }
 }
 
-impl ToJson for (f32, f32) {
+impl ToJson for (f64, f64) {
   fn to_json(&self) -> JsonValue {
     array![self.0, self.1]
   }
 }
 
+impl<T: ToJson> ToJson for &T {
+  fn to_json(&self) -> JsonValue {
+    (*self).to_json()
+  }
+}
+
 impl<T: ToJson> ToJson for Vec<T> {
   fn to_json(&self) -> JsonValue {
     let mut arr = array![];
@@ -38,706 +76,4592 @@ impl<T: ToJson> ToJson for Vec<T> {
   }
 }
 
-/// View modes
-#[derive(Clone, PartialEq)]
-pub enum ViewMode {
-  /// BOM only
-  BomOnly,
-  /// BOM left, drawings right
-  LeftRight,
-  /// BOM top, drawings bottom
-  TopBottom,
-}
+/// Default number of decimal places of numbers, i.e. 1 nm
+const DECIMALS: u8 = 6;
 
-impl ToJson for ViewMode {
-  fn to_json(&self) -> JsonValue {
-    match self {
-      ViewMode::BomOnly => "bom-only".into(),
-      ViewMode::LeftRight => "left-right".into(),
-      ViewMode::TopBottom => "top-bottom".into(),
-    }
+/// Board thickness shown in the 3D view [mm]
+const BOARD_THICKNESS: f64 = 1.6;
+
+/// Round a value to the given number of decimal places
+fn round(value: f64, decimals: u8) -> f64 {
+  // More decimals are beyond the precision of f64 anyway.
+  let factor = 10f64.powi(decimals.min(15).into());
+  let value = (value * factor).round() / factor;
+  // Avoid emitting "-0".
+  if value == 0.0 {
+    0.0
+  } else {
+    value
   }
 }
 
-/// Highlight pin-1 modes
-#[derive(Clone, PartialEq)]
-pub enum HighlightPin1Mode {
-  /// No pins
-  None,
-  /// Selected pins
-  Selected,
-  /// All pins
-  All,
-}
+/// Bundled viewer assets, by file name
+const ASSETS: [(&str, &[u8]); 9] = [
+  ("ibom.css", include_bytes!("web/ibom.css")),
+  ("split.js", include_bytes!("web/split.js")),
+  ("lz-string.js", include_bytes!("web/lz-string.js")),
+  ("pep.js", include_bytes!("web/pep.js")),
+  ("util.js", include_bytes!("web/util.js")),
+  ("render.js", include_bytes!("web/render.js")),
+  ("table-util.js", include_bytes!("web/table-util.js")),
+  ("ibom.js", include_bytes!("web/ibom.js")),
+  ("ibom-ext.js", include_bytes!("ext/ibom-ext.js")),
+];
 
-impl ToJson for HighlightPin1Mode {
-  fn to_json(&self) -> JsonValue {
-    match self {
-      HighlightPin1Mode::None => "none".into(),
-      HighlightPin1Mode::Selected => "selected".into(),
-      HighlightPin1Mode::All => "all".into(),
-    }
+/// Placeholders of the HTML template, in the order of replacement
+const TEMPLATE_PLACEHOLDERS: [&str; 14] = [
+  "///CSS///",
+  "///USERCSS///",
+  "///SPLITJS///",
+  "///LZ-STRING///",
+  "///POINTER_EVENTS_POLYFILL///",
+  "///UTILJS///",
+  "///RENDERJS///",
+  "///TABLEUTILJS///",
+  "///IBOMJS///",
+  "///CONFIG///",
+  "///PCBDATA///",
+  "///USERJS///",
+  "///USERHEADER///",
+  "///USERFOOTER///",
+];
+
+/// Split a template at its placeholders
+///
+/// # Arguments
+///
+/// * `template` - The template to split.
+/// * `placeholders` - Placeholders to split at.
+///
+/// # Returns
+///
+/// Returns the texts preceding each placeholder together with the index of
+/// the placeholder, and the text after the last placeholder.
+fn split_template<'a>(
+  template: &'a str,
+  placeholders: &[&str],
+) -> (Vec<(&'a str, usize)>, &'a str) {
+  let mut parts = Vec::new();
+  let mut rest = template;
+  while let Some((pos, index)) = placeholders
+    .iter()
+    .enumerate()
+    .filter_map(|(index, x)| rest.find(x).map(|pos| (pos, index)))
+    .min_by_key(|x| x.0)
+  {
+    parts.push((&rest[..pos], index));
+    rest = &rest[pos + placeholders[index].len()..];
   }
+  (parts, rest)
 }
 
-/// Layer enum
-#[derive(Clone, PartialEq)]
-pub enum Layer {
-  /// Front layer
-  Front,
-  /// Back layer
-  Back,
+/// Build HTML to load a script from within an inline script element
+///
+/// # Arguments
+///
+/// * `url` - URL of the script.
+/// * `nonce` - Nonce attribute of the elements, or an empty string.
+fn external_script(url: &str, nonce: &str) -> String {
+  // Close the inline element to load the script in between.
+  format!(
+    "</script>\n  \
+     <script{1} type=\"text/javascript\" src=\"{0}\"></script>\n  \
+     <script{1} type=\"text/javascript\">",
+    escape_html(url),
+    nonce
+  )
 }
 
-impl ToJson for Layer {
-  fn to_json(&self) -> JsonValue {
-    match self {
-      Layer::Front => "F".into(),
-      Layer::Back => "B".into(),
+/// Build HTML to load a stylesheet from within an inline style element
+///
+/// # Arguments
+///
+/// * `url` - URL of the stylesheet.
+/// * `nonce` - Nonce attribute of the elements, or an empty string.
+fn external_style(url: &str, nonce: &str) -> String {
+  format!(
+    "</style>\n  \
+     <link{1} rel=\"stylesheet\" href=\"{0}\">\n  \
+     <style{1} type=\"text/css\">",
+    escape_html(url),
+    nonce
+  )
+}
+
+/// Build the JavaScript statement defining the viewer data
+///
+/// Returned in pieces to avoid copying the (possibly huge) data around.
+fn pcbdata_js(
+  data: &JsonValue,
+  encoding: &DataEncoding,
+) -> Vec<Cow<'static, str>> {
+  match encoding {
+    DataEncoding::LzString => vec![
+      "var pcbdata = JSON.parse(LZString.decompressFromBase64(\"".into(),
+      lz_str::compress_to_base64(&data.dump()).into(),
+      "\"))".into(),
+    ],
+    DataEncoding::Json => {
+      let json = data.dump();
+      vec![
+        "var pcbdata = ".into(),
+        // Avoid terminating the script element with "</script>" in strings.
+        match json.contains('<') {
+          true => json.replace('<', "\\u003c").into(),
+          false => json.into(),
+        },
+      ]
     }
+    DataEncoding::Deflate => vec![
+      String::from_utf8_lossy(include_bytes!("ext/inflate.js")),
+      "\nvar pcbdata = JSON.parse(IbomInflate.inflateBase64(\"".into(),
+      base64(&miniz_oxide::deflate::compress_to_vec(
+        data.dump().as_bytes(),
+        9,
+      ))
+      .into(),
+      "\"))".into(),
+    ],
+    DataEncoding::Cbor => vec![
+      String::from_utf8_lossy(include_bytes!("ext/inflate.js")),
+      "\n".into(),
+      String::from_utf8_lossy(include_bytes!("ext/cbor.js")),
+      "\nvar pcbdata = IbomCbor.decode(IbomInflate.inflateBase64Bytes(\""
+        .into(),
+      base64(&miniz_oxide::deflate::compress_to_vec(
+        &cbor::encode(data),
+        9,
+      ))
+      .into(),
+      "\"))".into(),
+    ],
   }
 }
 
-/// Drawing kind
-#[derive(PartialEq)]
-pub enum DrawingKind {
-  /// Polygon
-  Polygon,
-  /// Component reference designator text
-  ReferenceText,
-  /// Component value text
-  ValueText,
+/// Round all numbers contained in a JSON value, including SVG paths
+fn round_json(value: &mut JsonValue, decimals: u8) {
+  match value {
+    JsonValue::Number(number) => {
+      *value = round((*number).into(), decimals).into()
+    }
+    JsonValue::Array(items) => {
+      items.iter_mut().for_each(|x| round_json(x, decimals))
+    }
+    JsonValue::Object(object) => {
+      for (key, item) in object.iter_mut() {
+        match item.as_str() {
+          Some(path) if key == "svgpath" => {
+            *item = svgpath::map_numbers(path, |x| round(x, decimals)).into();
+          }
+          _ => round_json(item, decimals),
+        }
+      }
+    }
+    _ => {}
+  }
 }
 
-/// Drawing layer
-#[derive(PartialEq)]
-pub enum DrawingLayer {
-  /// PCB edge
-  Edge,
-  /// Silkscreen front
-  SilkscreenFront,
-  /// Silkscreen back
-  SilkscreenBack,
-  /// Fabrication front
-  FabricationFront,
-  /// Fabrication back
-  FabricationBack,
+/// Find a NaN or infinite number contained in a JSON value, including SVG
+/// paths
+///
+/// Returns the path to the first such number, e.g. `tracks.F[0].width`.
+fn find_non_finite(value: &JsonValue, path: &str) -> Option<String> {
+  match value {
+    JsonValue::Number(number) if number.is_nan() => Some(path.to_owned()),
+    JsonValue::Array(items) => items
+      .iter()
+      .enumerate()
+      .find_map(|(i, x)| find_non_finite(x, &format!("{}[{}]", path, i))),
+    JsonValue::Object(object) => object.iter().find_map(|(key, x)| {
+      let path = if path.is_empty() {
+        key.to_owned()
+      } else {
+        format!("{}.{}", path, key)
+      };
+      match x.as_str() {
+        Some(svgpath) if key == "svgpath" => {
+          svgpath::has_non_finite(svgpath).then_some(path)
+        }
+        _ => find_non_finite(x, &path),
+      }
+    }),
+    _ => None,
+  }
 }
 
-/// Drawing structure (SVG polygon)
-#[non_exhaustive]
-pub struct Drawing {
-  kind: DrawingKind,
-  layer: DrawingLayer,
-  svgpath: String,
-  width: f32,
-  filled: bool,
+/// Rotate a vector the same way as the viewer rotates pads & footprints
+fn rotate(v: (f64, f64), angle: f64) -> (f64, f64) {
+  let (sin, cos) = (-angle).to_radians().sin_cos();
+  (v.0 * cos - v.1 * sin, v.0 * sin + v.1 * cos)
 }
 
-impl Drawing {
-  /// Construct drawing
-  ///
-  /// # Arguments
-  ///
-  /// * `kind` - Drawing kind.
-  /// * `layer` - Drawing layer.
-  /// * `svgpath` - Outline as an SVG path \[mm\].
-  /// * `width` - Line width \[mm\].
-  /// * `filled` - Whether to fill the shape or not.
-  ///
-  /// # Returns
-  ///
-  /// Returns the new object.
-  pub fn new(
-    kind: DrawingKind,
-    layer: DrawingLayer,
-    svgpath: &str,
-    width: f32,
-    filled: bool,
-  ) -> Drawing {
-    Drawing {
-      kind,
-      layer,
-      svgpath: svgpath.to_owned(),
-      width,
-      filled,
+/// Escape text to be inserted into HTML by the viewer
+///
+/// The viewer assigns many strings (e.g. metadata, references, field values
+/// and net names) to `innerHTML`, so they must not contain markup.
+fn escape_html(text: &str) -> String {
+  let mut escaped = String::with_capacity(text.len());
+  for c in text.chars() {
+    match c {
+      '&' => escaped += "&amp;",
+      '<' => escaped += "&lt;",
+      '>' => escaped += "&gt;",
+      '"' => escaped += "&quot;",
+      '\'' => escaped += "&#39;",
+      _ => escaped.push(c),
     }
   }
+  escaped
 }
 
-impl ToJson for Drawing {
-  fn to_json(&self) -> JsonValue {
-    let mut obj = object! {
-      svgpath: self.svgpath.clone(),
-      filled: self.filled,
-    };
-    match self.kind {
-      DrawingKind::Polygon => {
-        obj["type"] = "polygon".into();
-        obj["width"] = self.width.into();
+/// Unescape the entities produced by [escape_html]
+fn unescape_html(text: &str) -> String {
+  text
+    .replace("&lt;", "<")
+    .replace("&gt;", ">")
+    .replace("&quot;", "\"")
+    .replace("&#39;", "'")
+    .replace("&amp;", "&")
+}
+
+/// Convert a generated HTML document to a string
+///
+/// Only strings are written to the document, thus it is valid UTF-8.
+fn html_string(html: Vec<u8>) -> Result<String, GenerateError> {
+  String::from_utf8(html).map_err(|err| GenerateError::InvalidOutput {
+    reason: err.to_string(),
+  })
+}
+
+/// Prepare an HTML template for a Content-Security-Policy with nonce
+///
+/// Adds the nonce to all script and style elements and moves inline event
+/// handlers (e.g. `onclick="..."`) into a script since they are not
+/// allowed by such a policy.
+///
+/// # Arguments
+///
+/// * `html` - HTML template.
+/// * `nonce` - Nonce attribute to add, e.g. ` nonce="abc"`.
+///
+/// # Returns
+///
+/// Returns the modified template and the script registering the event
+/// handlers.
+fn csp_template(html: &str, nonce: &str) -> (String, String) {
+  let mut out = String::with_capacity(html.len());
+  let mut js = String::new();
+  let mut count = 0;
+  let mut rest = html;
+  while let Some(start) = rest.find('<') {
+    out += &rest[..start];
+    rest = &rest[start..];
+
+    // Copy comments and the content of script and style elements as-is.
+    let raw_end = [
+      ("<!--", "-->"),
+      ("<script", "</script>"),
+      ("<style", "</style>"),
+    ]
+    .into_iter()
+    .find(|(start, _)| rest.starts_with(start));
+    if let Some((start, end)) = raw_end {
+      let end = rest.find(end).map_or(rest.len(), |x| x + end.len());
+      out += start;
+      if start != "<!--" {
+        out += nonce;
       }
-      DrawingKind::ReferenceText => {
-        obj["thickness"] = self.width.into();
-        obj["ref"] = 1.into();
+      out += &rest[start.len()..end];
+      rest = &rest[end..];
+      continue;
+    }
+
+    // Find the end of the tag, skipping quoted attribute values.
+    let mut quote = None;
+    let end = rest
+      .char_indices()
+      .find(|(_, c)| match quote {
+        Some(q) if q == *c => {
+          quote = None;
+          false
+        }
+        Some(_) => false,
+        None if *c == '"' || *c == '\'' => {
+          quote = Some(*c);
+          false
+        }
+        None => *c == '>',
+      })
+      .map_or(rest.len(), |(i, _)| i);
+    let name_len = rest.find(char::is_whitespace).unwrap_or(end).min(end);
+    let (name, mut attrs) = (&rest[..name_len], &rest[name_len..end]);
+    rest = &rest[end..];
+
+    // Copy attributes, except event handlers.
+    out += name;
+    let mut handlers = Vec::new();
+    loop {
+      let attr = attrs.trim_start();
+      let whitespace = &attrs[..attrs.len() - attr.len()];
+      let name_len = attr
+        .find(|c: char| c == '=' || c.is_whitespace())
+        .unwrap_or(attr.len())
+        .max(attr.chars().next().map_or(0, char::len_utf8));
+      if name_len == 0 {
+        out += attrs;
+        break;
       }
-      DrawingKind::ValueText => {
-        obj["thickness"] = self.width.into();
-        obj["val"] = 1.into();
+      let mut len = name_len;
+      let mut value = None;
+      if let Some(v) = attr[name_len..].strip_prefix('=') {
+        let v_len = match v.chars().next() {
+          Some(q @ ('"' | '\'')) => {
+            let value_len = v[1..].find(q).unwrap_or(v.len() - 1);
+            value = Some(&v[1..1 + value_len]);
+            (2 + value_len).min(v.len())
+          }
+          _ => {
+            let value_len = v.find(char::is_whitespace).unwrap_or(v.len());
+            value = Some(&v[..value_len]);
+            value_len
+          }
+        };
+        len += 1 + v_len;
+      }
+      match (attr[..name_len].strip_prefix("on"), value) {
+        (Some(event), Some(code)) if !event.is_empty() => {
+          handlers.push((event, code));
+        }
+        _ => {
+          out += whitespace;
+          out += &attr[..len];
+        }
       }
+      attrs = &attr[len..];
+    }
+    if !handlers.is_empty() {
+      out += &format!(" data-ibom-handler=\"{}\"", count);
+      for (event, code) in handlers {
+        js += &format!(
+          "  element({}).on{} = function (event) {{ {} }};\n",
+          count,
+          event,
+          unescape_html(code)
+        );
+      }
+      count += 1;
     }
-    obj
   }
+  out += rest;
+  if !js.is_empty() {
+    js = format!(
+      "document.addEventListener(\"DOMContentLoaded\", function () {{\n  \
+       var element = function (id) {{\n    \
+       return document.querySelector(\"[data-ibom-handler='\" + \
+       id + \"']\");\n  \
+       }};\n{}}});\n",
+      js
+    );
+  }
+  (out, js)
 }
 
-/// Track structure
-#[non_exhaustive]
-pub struct Track {
-  layer: Layer,
-  start: (f32, f32),
-  end: (f32, f32),
-  width: f32,
-  net: Option<String>,
+/// Escape a list of texts, see [escape_html]
+fn escape_html_json(texts: &[String]) -> JsonValue {
+  texts
+    .iter()
+    .map(|x| escape_html(x))
+    .collect::<Vec<_>>()
+    .to_json()
 }
 
-impl Track {
-  /// Construct track
-  ///
-  /// # Arguments
-  ///
-  /// * `layer` - Layer.
-  /// * `start` - Start position (x, y) \[mm\].
-  /// * `end` - End position (x, y) \[mm\].
-  /// * `width` - Track width \[mm\].
-  /// * `net` - Net name (optional).
-  ///
-  /// # Returns
-  ///
-  /// Returns the new object.
-  pub fn new(
-    layer: Layer,
-    start: (f32, f32),
-    end: (f32, f32),
-    width: f32,
-    net: Option<&str>,
-  ) -> Track {
-    Track {
-      layer,
-      start,
-      end,
-      width,
-      net: net.map(|s| s.to_owned()),
+/// Escape the texts of the viewer data assigned to `innerHTML`, see
+/// [escape_html]
+///
+/// The data is built with raw texts for other tools, only the copy embedded
+/// into the HTML is escaped.
+fn escape_data(data: &mut JsonValue) {
+  fn escape(value: &mut JsonValue) {
+    if let Some(text) = value.as_str() {
+      *value = escape_html(text).into();
+    }
+  }
+  fn escape_keys(obj: &mut JsonValue, keys: &[&str]) {
+    if let JsonValue::Object(obj) = obj {
+      for key in keys {
+        if let Some(value) = obj.get_mut(key) {
+          escape(value);
+        }
+      }
+    }
+  }
+
+  for (_, value) in data["metadata"].entries_mut() {
+    escape(value);
+  }
+  data["nets"].members_mut().for_each(escape);
+  for layer in ["F", "B"] {
+    for obj in data["tracks"][layer].members_mut() {
+      escape_keys(obj, &["net"]);
+    }
+    for obj in data["zones"][layer].members_mut() {
+      escape_keys(obj, &["net"]);
+    }
+  }
+  for fpt in data["footprints"].members_mut() {
+    for pad in fpt["pads"].members_mut() {
+      escape_keys(pad, &["net", "pinnumber", "pinname"]);
     }
   }
+  for view in ["F", "B", "both"] {
+    for row in data["bom"][view].members_mut() {
+      row.members_mut().for_each(|x| escape(&mut x[0]));
+    }
+  }
+  for (_, fields) in data["bom"]["fields"].entries_mut() {
+    fields.members_mut().for_each(escape);
+  }
+  for (_, tooltips) in data["tooltips"].entries_mut() {
+    tooltips
+      .members_mut()
+      .flat_map(|x| x.members_mut())
+      .for_each(escape);
+  }
 }
 
-impl ToJson for Track {
-  fn to_json(&self) -> JsonValue {
-    let mut obj = object! {
-      start: self.start.to_json(),
-      end: self.end.to_json(),
-      width: self.width,
+/// Encode data as base64
+fn base64(data: &[u8]) -> String {
+  const CHARS: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+  let mut out = String::with_capacity(data.len().div_ceil(3) * 4);
+  for chunk in data.chunks(3) {
+    let b = [
+      chunk[0],
+      *chunk.get(1).unwrap_or(&0),
+      *chunk.get(2).unwrap_or(&0),
+    ];
+    let n = ((b[0] as u32) << 16) | ((b[1] as u32) << 8) | (b[2] as u32);
+    for i in 0..4 {
+      if i <= chunk.len() {
+        out.push(CHARS[((n >> (18 - 6 * i)) & 0x3f) as usize] as char);
+      } else {
+        out.push('=');
+      }
+    }
+  }
+  out
+}
+
+/// Decode base64 data, returning [None] if it is invalid
+fn base64_decode(text: &str) -> Option<Vec<u8>> {
+  let mut out = Vec::with_capacity(text.len() / 4 * 3);
+  let (mut n, mut bits) = (0u32, 0);
+  for c in text.trim_end_matches('=').bytes() {
+    let value = match c {
+      b'A'..=b'Z' => c - b'A',
+      b'a'..=b'z' => c - b'a' + 26,
+      b'0'..=b'9' => c - b'0' + 52,
+      b'+' => 62,
+      b'/' => 63,
+      _ => return None,
     };
-    if let Some(net) = &self.net {
-      obj["net"] = net.clone().into();
+    n = (n << 6) | value as u32;
+    bits += 6;
+    if bits >= 8 {
+      bits -= 8;
+      out.push((n >> bits) as u8);
+      n &= (1 << bits) - 1;
     }
-    obj
   }
+  Some(out)
 }
 
-/// Via structure
-#[non_exhaustive]
-pub struct Via {
-  layers: Vec<Layer>,
-  pos: (f32, f32),
-  diameter: f32,
-  drill_diameter: f32,
-  net: Option<String>,
+/// 2D point
+///
+/// All constructors accept anything convertible into a point, in particular
+/// (x, y) tuples.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Point {
+  /// X coordinate \[mm\]
+  pub x: f64,
+  /// Y coordinate \[mm\]
+  pub y: f64,
 }
 
-impl Via {
-  /// Construct via
+impl Point {
+  /// Construct object
   ///
   /// # Arguments
   ///
-  /// * `layers` - Layers.
-  /// * `pos` - Position (x, y) \[mm\].
-  /// * `diameter` - Outer diameter \[mm\].
-  /// * `drill_diameter` - Drill diameter \[mm\].
-  /// * `net` - Net name (optional).
+  /// * `x` - X coordinate \[mm\].
+  /// * `y` - Y coordinate \[mm\].
   ///
   /// # Returns
   ///
   /// Returns the new object.
-  pub fn new(
-    layers: &[Layer],
-    pos: (f32, f32),
-    diameter: f32,
-    drill_diameter: f32,
-    net: Option<&str>,
-  ) -> Via {
-    Via {
-      layers: layers.to_vec(),
-      pos,
-      diameter,
-      drill_diameter,
-      net: net.map(|s| s.to_owned()),
-    }
+  pub fn new(x: f64, y: f64) -> Point {
+    Point { x, y }
   }
 }
 
-impl ToJson for Via {
-  fn to_json(&self) -> JsonValue {
-    let mut obj = object! {
-      start: self.pos.to_json(),
-      end: self.pos.to_json(),
-      width: self.diameter,
-      drillsize: self.drill_diameter,
-    };
-    if let Some(net) = &self.net {
-      obj["net"] = net.clone().into();
-    }
-    obj
+impl From<(f64, f64)> for Point {
+  fn from(xy: (f64, f64)) -> Self {
+    Point::new(xy.0, xy.1)
   }
 }
 
-/// Zone structure
-#[non_exhaustive]
-pub struct Zone {
-  layer: Layer,
-  svgpath: String,
-  net: Option<String>,
+impl From<Point> for (f64, f64) {
+  fn from(p: Point) -> Self {
+    (p.x, p.y)
+  }
 }
 
-impl Zone {
-  /// Construct object
+impl Add for Point {
+  type Output = Point;
+
+  fn add(self, rhs: Point) -> Point {
+    Point::new(self.x + rhs.x, self.y + rhs.y)
+  }
+}
+
+impl Sub for Point {
+  type Output = Point;
+
+  fn sub(self, rhs: Point) -> Point {
+    Point::new(self.x - rhs.x, self.y - rhs.y)
+  }
+}
+
+/// Convert a point into the tuple representation used internally
+fn xy(p: impl Into<Point>) -> (f64, f64) {
+  p.into().into()
+}
+
+/// Rotation angle
+///
+/// Angles are given in degrees and normalized to the range (-180°, 180°], so
+/// equivalent angles like -270° and 90° result in identical output. Positive
+/// values rotate counter-clockwise as seen in the viewer.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Angle(f64);
+
+impl Angle {
+  /// Construct object from degrees
   ///
   /// # Arguments
   ///
-  /// * `layer` - Layer.
-  /// * `svgpath` - Zone outline as SVG path \[mm\].
-  /// * `net` - Net name (optional).
+  /// * `degrees` - Angle [°], will be normalized.
   ///
   /// # Returns
   ///
   /// Returns the new object.
-  pub fn new(layer: Layer, svgpath: &str, net: Option<&str>) -> Zone {
-    Zone {
-      layer,
-      svgpath: svgpath.to_owned(),
-      net: net.map(|s| s.to_owned()),
+  pub fn from_degrees(degrees: f64) -> Angle {
+    let mut degrees = degrees % 360.0;
+    if degrees <= -180.0 {
+      degrees += 360.0;
+    } else if degrees > 180.0 {
+      degrees -= 360.0;
     }
+    Angle(degrees)
+  }
+
+  /// Get angle in degrees
+  ///
+  /// # Returns
+  ///
+  /// Returns the normalized angle [°] in the range (-180°, 180°].
+  pub fn degrees(&self) -> f64 {
+    self.0
   }
 }
 
-impl ToJson for Zone {
-  fn to_json(&self) -> JsonValue {
-    let mut obj = object! {
-      svgpath: self.svgpath.clone(),
-    };
-    if let Some(net) = &self.net {
-      obj["net"] = net.clone().into();
-    }
-    obj
+impl From<f64> for Angle {
+  fn from(degrees: f64) -> Self {
+    Angle::from_degrees(degrees)
   }
 }
 
-/// Footprint pad structure
-#[derive(Clone)]
-#[non_exhaustive]
-pub struct Pad {
-  layers: Vec<Layer>,
-  pos: (f32, f32),
-  angle: f32,
-  svgpath: String,
-  drill_size: Option<(f32, f32)>,
-  net: Option<String>,
-  pin1: bool,
+/// SVG path builder
+///
+/// Helper to build the SVG paths consumed by [Drawing], [Zone] and [Pad]
+/// with consistent number formatting.
+///
+/// # Examples
+///
+/// ```
+/// use interactive_html_bom::SvgPathBuilder;
+///
+/// let path = SvgPathBuilder::new()
+///   .move_to((0.0, 0.0))
+///   .line_to((10.0, 0.0))
+///   .arc_to((10.0, 10.0), 180.0)
+///   .close()
+///   .build();
+/// assert_eq!(path, "M 0 0 L 10 0 A 5 5 0 0 1 10 10 Z");
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SvgPathBuilder {
+  path: String,
+  start: (f64, f64),
+  pos: (f64, f64),
 }
 
-impl Pad {
-  /// Construct object
+impl SvgPathBuilder {
+  /// Construct empty path
+  ///
+  /// # Returns
+  ///
+  /// Returns the new object.
+  pub fn new() -> SvgPathBuilder {
+    SvgPathBuilder::default()
+  }
+
+  /// Start a new sub-path
   ///
   /// # Arguments
   ///
-  /// * `layers` - Layers on which the pad exists.
-  /// * `pos` - Position (x, y) \[mm\].
-  /// * `angle` - Rotation angle [°].
-  /// * `svgpath` - Pad shape as SVG path \[mm\].
-  /// * `drill_size` - Drill size (w, h) \[mm\] (only for THT pads).
-  /// * `net` - Net name (optional).
-  /// * `pin1` - Whether this is considered as the pin-1 or not.
+  /// * `pos` - Start position (x, y) \[mm\].
+  pub fn move_to(&mut self, pos: impl Into<Point>) -> &mut Self {
+    let pos = xy(pos);
+    self.push("M", &[pos.0, pos.1]);
+    self.start = pos;
+    self.pos = pos;
+    self
+  }
+
+  /// Add a straight line
+  ///
+  /// # Arguments
+  ///
+  /// * `pos` - End position (x, y) \[mm\].
+  pub fn line_to(&mut self, pos: impl Into<Point>) -> &mut Self {
+    let pos = xy(pos);
+    self.push("L", &[pos.0, pos.1]);
+    self.pos = pos;
+    self
+  }
+
+  /// Add a circular arc
+  ///
+  /// The radius is derived from the current position, the end position and
+  /// the angle. An angle of zero results in a straight line.
+  ///
+  /// # Arguments
+  ///
+  /// * `pos` - End position (x, y) \[mm\].
+  /// * `angle` - Arc angle [°], positive values rotate from the positive X
+  ///   axis towards the positive Y axis. Must be greater than -360° and less
+  ///   than 360°, since a full circle can't be described by a single arc
+  ///   (use two half arcs instead). In debug builds, other values panic. In
+  ///   release builds, they are clamped to ±359.999° and a non-finite angle
+  ///   results in a straight line.
+  pub fn arc_to(&mut self, pos: impl Into<Point>, angle: f64) -> &mut Self {
+    debug_assert!(angle.abs() < 360.0, "Invalid arc angle: {angle}");
+    let pos = xy(pos);
+    if angle == 0.0 || !angle.is_finite() {
+      return self.line_to(pos);
+    }
+    let angle = angle.clamp(-359.999, 359.999);
+    let chord = (pos.0 - self.pos.0).hypot(pos.1 - self.pos.1);
+    let radius = chord / (2.0 * (angle.to_radians() / 2.0).sin().abs());
+    let large_arc = if angle.abs() > 180.0 { 1.0 } else { 0.0 };
+    let sweep = if angle > 0.0 { 1.0 } else { 0.0 };
+    self.push("A", &[radius, radius, 0.0, large_arc, sweep, pos.0, pos.1]);
+    self.pos = pos;
+    self
+  }
+
+  /// Add a cubic bézier curve
+  ///
+  /// # Arguments
+  ///
+  /// * `ctrl1` - First control point (x, y) \[mm\].
+  /// * `ctrl2` - Second control point (x, y) \[mm\].
+  /// * `pos` - End position (x, y) \[mm\].
+  pub fn cubic_to(
+    &mut self,
+    ctrl1: impl Into<Point>,
+    ctrl2: impl Into<Point>,
+    pos: impl Into<Point>,
+  ) -> &mut Self {
+    let ctrl1 = xy(ctrl1);
+    let ctrl2 = xy(ctrl2);
+    let pos = xy(pos);
+    self.push("C", &[ctrl1.0, ctrl1.1, ctrl2.0, ctrl2.1, pos.0, pos.1]);
+    self.pos = pos;
+    self
+  }
+
+  /// Close the current sub-path
+  pub fn close(&mut self) -> &mut Self {
+    self.push("Z", &[]);
+    self.pos = self.start;
+    self
+  }
+
+  /// Get the built path
   ///
   /// # Returns
   ///
-  /// Returns the new object.
-  pub fn new(
-    layers: &[Layer],
-    pos: (f32, f32),
-    angle: f32,
-    svgpath: &str,
-    drill_size: Option<(f32, f32)>,
-    net: Option<&str>,
-    pin1: bool,
-  ) -> Pad {
-    Pad {
-      layers: layers.into(),
-      pos,
-      angle,
-      svgpath: svgpath.to_owned(),
-      drill_size,
-      net: net.map(|s| s.to_owned()),
-      pin1,
+  /// Returns the SVG path string.
+  pub fn build(&self) -> String {
+    self.path.clone()
+  }
+
+  fn push(&mut self, cmd: &str, values: &[f64]) {
+    if !self.path.is_empty() {
+      self.path.push(' ');
+    }
+    self.path.push_str(cmd);
+    for value in values {
+      self.path += &format!(" {}", round(*value, DECIMALS));
     }
   }
 }
 
-impl ToJson for Pad {
+/// View modes
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ViewMode {
+  /// BOM only
+  BomOnly,
+  /// BOM left, drawings right
+  #[default]
+  LeftRight,
+  /// BOM top, drawings bottom
+  TopBottom,
+}
+
+impl ToJson for ViewMode {
   fn to_json(&self) -> JsonValue {
-    let mut obj = object! {
-      layers: self.layers.to_json(),
-      pos: self.pos.to_json(),
-      angle: self.angle,
-      shape: "custom",
-      svgpath: self.svgpath.clone(),
-    };
-    if let Some(drill) = &self.drill_size {
-      obj["type"] = "th".into();
-      obj["drillsize"] = array![drill.0, drill.1];
-      obj["drillshape"] = if drill.0 != drill.1 {
-        "oblong".into()
-      } else {
-        "circle".into()
-      };
-    } else {
-      obj["type"] = "smd".into();
+    match self {
+      ViewMode::BomOnly => "bom-only".into(),
+      ViewMode::LeftRight => "left-right".into(),
+      ViewMode::TopBottom => "top-bottom".into(),
     }
-    if let Some(net) = &self.net {
-      obj["net"] = net.clone().into();
+  }
+}
+
+/// Layer views
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum LayerView {
+  /// Front side only
+  Front,
+  /// Back side only
+  Back,
+  /// Front and back side
+  FrontAndBack,
+}
+
+impl ToJson for LayerView {
+  fn to_json(&self) -> JsonValue {
+    match self {
+      LayerView::Front => "F".into(),
+      LayerView::Back => "B".into(),
+      LayerView::FrontAndBack => "FB".into(),
     }
-    if self.pin1 {
-      obj["pin1"] = 1.into();
+  }
+}
+
+/// Sort orders
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SortOrder {
+  /// Ascending
+  #[default]
+  Ascending,
+  /// Descending
+  Descending,
+}
+
+impl ToJson for SortOrder {
+  fn to_json(&self) -> JsonValue {
+    match self {
+      SortOrder::Ascending => "asc".into(),
+      SortOrder::Descending => "desc".into(),
     }
-    obj
   }
 }
 
-/// Footprint structure
+/// Image formats
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ImageFormat {
+  /// PNG image
+  Png,
+  /// SVG image
+  Svg,
+}
+
+/// Image structure
+///
+/// Images are embedded as data URLs into the generated HTML.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[non_exhaustive]
-pub struct Footprint {
-  layer: Layer,
-  pos: (f32, f32),
-  angle: f32,
-  bottom_left: (f32, f32),
-  top_right: (f32, f32),
-  fields: Vec<String>,
-  pads: Vec<Pad>,
-  mount: bool,
+pub struct Image {
+  format: ImageFormat,
+  data: Vec<u8>,
 }
 
-impl Footprint {
+impl Image {
   /// Construct object
   ///
   /// # Arguments
   ///
-  /// * `layer` - Placement layer.
-  /// * `pos` - Position (x, y) \[mm\].
-  /// * `angle` - Rotation angle [°].
-  /// * `bottom_left` - Bottom left corner of bounding box (x, y) \[mm\].
-  /// * `top_right` - Top right corner of bounding box (x, y) \[mm\].
-  /// * `fields` - Custom fields, corresponding to [InteractiveHtmlBom::fields].
-  /// * `pads` - Footprint pads.
-  /// * `mount` - Whether the footprint is mounted or not.
+  /// * `format` - Image format.
+  /// * `data` - File content.
   ///
   /// # Returns
   ///
   /// Returns the new object.
-  #[allow(clippy::too_many_arguments)]
-  pub fn new(
-    layer: Layer,
-    pos: (f32, f32),
-    angle: f32,
-    bottom_left: (f32, f32),
-    top_right: (f32, f32),
-    fields: &[String],
-    pads: &[Pad],
-    mount: bool,
-  ) -> Footprint {
-    Footprint {
-      layer,
-      pos,
-      angle,
-      bottom_left,
-      top_right,
-      fields: fields.to_vec(),
-      pads: pads.to_vec(),
-      mount,
+  pub fn new(format: ImageFormat, data: &[u8]) -> Image {
+    Image {
+      format,
+      data: data.to_vec(),
     }
   }
-}
 
-impl ToJson for Footprint {
-  fn to_json(&self) -> JsonValue {
-    object! {
-      bbox: object!{
-        pos: self.pos.to_json(),
-        angle: self.angle,
-        relpos: self.bottom_left.to_json(),
-        size: array![
-          self.top_right.0 - self.bottom_left.0,
-          self.top_right.1 - self.bottom_left.1],
-      },
-      drawings: array![],  // Not supported yet.
-      layer: self.layer.to_json(),
-      pads: self.pads.to_json(),
-    }
+  fn data_url(&self) -> String {
+    let mime = match self.format {
+      ImageFormat::Png => "image/png",
+      ImageFormat::Svg => "image/svg+xml",
+    };
+    format!("data:{};base64,{}", mime, base64(&self.data))
   }
 }
 
-/// Reference-FootprintID map
-#[derive(Clone)]
+/// Schematic sheet structure
+///
+/// A schematic page shown in the viewer, together with the locations of
+/// the components on it.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[non_exhaustive]
-pub struct RefMap {
-  reference: String,
-  footprint_id: usize,
+pub struct SchematicSheet {
+  title: String,
+  image: Image,
+  locations: Vec<(String, (f64, f64))>,
 }
 
-impl RefMap {
+impl SchematicSheet {
   /// Construct object
   ///
   /// # Arguments
   ///
-  /// * `reference` - Component reference (e.g. "R1").
-  /// * `footprint_id` - ID of footprint as returned by
-  ///                    [InteractiveHtmlBom::add_footprint].
+  /// * `title` - Sheet title, shown on its toolbar button.
+  /// * `image` - Rendered sheet.
+  /// * `locations` - Pairs of component reference and position (x, y) on
+  ///   the sheet, relative to the image size (0..1, from the top left).
   ///
   /// # Returns
   ///
   /// Returns the new object.
-  pub fn new(reference: &str, footprint_id: usize) -> RefMap {
-    RefMap {
-      reference: reference.to_owned(),
-      footprint_id,
+  pub fn new(
+    title: &str,
+    image: Image,
+    locations: &[(&str, (f64, f64))],
+  ) -> SchematicSheet {
+    SchematicSheet {
+      title: title.to_owned(),
+      image,
+      locations: locations
+        .iter()
+        .map(|(reference, pos)| (reference.to_string(), *pos))
+        .collect(),
     }
   }
 }
 
-impl ToJson for RefMap {
-  fn to_json(&self) -> JsonValue {
-    array! {
-      self.reference.clone(),
-      self.footprint_id,
+/// Theme structure
+///
+/// Colors used to render the board, applied in both light and dark mode.
+/// Colors are specified as CSS colors (e.g. `"#d04040"`, `"red"` or
+/// `"rgb(208 64 64 / 50%)"`), colors set to [None] keep their default value.
+/// Other values are rejected with [GenerateError::InvalidColor] since they
+/// are inserted into the stylesheet.
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub struct Theme {
+  /// Background color
+  pub background: Option<String>,
+
+  /// Board edge color
+  pub edge: Option<String>,
+
+  /// Pad color
+  pub pad: Option<String>,
+
+  /// Pad hole color
+  pub pad_hole: Option<String>,
+
+  /// Highlighted pad color
+  pub pad_highlight: Option<String>,
+
+  /// Copper (tracks & zones) color
+  pub copper: Option<String>,
+
+  /// Highlighted copper (tracks & zones) color
+  pub copper_highlight: Option<String>,
+
+  /// Silkscreen color
+  pub silkscreen: Option<String>,
+
+  /// Fabrication layer color
+  pub fabrication: Option<String>,
+}
+
+impl Theme {
+  /// Check that all colors are plain CSS color values
+  ///
+  /// Only names, hex colors and color functions with numeric arguments are
+  /// accepted, so a color can't terminate the declaration or the stylesheet.
+  fn validate(&self) -> Result<(), GenerateError> {
+    for (color, _) in self.colors() {
+      if let Some(color) = color.as_ref().filter(|x| !is_css_color(x)) {
+        return Err(GenerateError::InvalidColor {
+          color: color.clone(),
+        });
+      }
+    }
+    Ok(())
+  }
+
+  /// Get the colors with the CSS properties they are applied to
+  fn colors(&self) -> [(&Option<String>, &[&str]); 9] {
+    [
+      (&self.background, &["background-color"][..]),
+      (&self.edge, &["--pcb-edge-color"]),
+      (&self.pad, &["--pad-color"]),
+      (&self.pad_hole, &["--pad-hole-color"]),
+      (&self.pad_highlight, &["--pad-color-highlight"]),
+      (&self.copper, &["--track-color", "--zone-color"]),
+      (
+        &self.copper_highlight,
+        &["--track-color-highlight", "--zone-color-highlight"],
+      ),
+      (
+        &self.silkscreen,
+        &[
+          "--silkscreen-edge-color",
+          "--silkscreen-polygon-color",
+          "--silkscreen-text-color",
+        ],
+      ),
+      (
+        &self.fabrication,
+        &[
+          "--fabrication-edge-color",
+          "--fabrication-polygon-color",
+          "--fabrication-text-color",
+        ],
+      ),
+    ]
+  }
+
+  fn css(&self) -> String {
+    let mut properties = String::new();
+    for (color, names) in self.colors() {
+      if let Some(color) = color {
+        for name in names {
+          properties += &format!("  {}: {};\n", name, color);
+        }
+      }
     }
+    if properties.is_empty() {
+      return String::new();
+    }
+    format!(".topmostdiv,\n.dark.topmostdiv {{\n{}}}\n", properties)
   }
 }
 
-/// Interactive HTML BOM structure
+/// Check if a string is a CSS color value
 ///
-/// The top-level structure to build & generate a HTML BOM.
-///
-/// <div class="warning">
-/// Please note that this struct is not completely fool-proof as it does not
-/// validate lots of the added data. So make sure you add only valid BOM data.
-/// Only the most important things are validated to avoid generating completely
-/// broken HTML pages: Footprint IDs in BOM rows, and number of fields in
-/// footprints.
-/// </div>
-///
-/// # Examples
-///
-/// ```
-/// use interactive_html_bom::*;
-///
-/// let mut ibom = InteractiveHtmlBom::new(
-///   "My Project",   // Title
-///   "My Company",   // Company
-///   "Rev. 1",       // Revision
-///   "1970-01-01",   // Date
-///   (0.0, 0.0),     // Bottom left
-///   (100.0, 80.0),  // Top right
-/// );
-///
-/// // Set configuration.
-/// ibom.fields = vec!["Value".into(), "Footprint".into()];
-///
-/// // Draw PCB.
-/// ibom.drawings.push(Drawing::new(
-///   DrawingKind::Polygon,             // Kind of drawing
-///   DrawingLayer::Edge,               // Layer
-///   "M 0 0 H 100 V 80 H -100 V -80",  // SVG path
-///   0.1,                              // Line width
-///   false,                            // Filled
-/// ));
-/// ibom.drawings.push(Drawing::new(
-///   DrawingKind::ReferenceText,
-///   DrawingLayer::SilkscreenFront,
-///   "M 10 10 H 80 V 60 H -80 V -60",
-///   0.1,
-///   false,
-/// ));
-///
-/// // Add footprints.
-/// let id = ibom.add_footprint(
-///   Footprint::new(
-///     Layer::Front,                       // Layer
-///     (50.0, 40.0),                       // Position
-///     45.0,                               // Rotation
-///     (-2.0, -1.0),                       // Bottom left
-///     (2.0, 1.0),                         // Top right
-///     &["100R".into(), "0603".into()],    // Fields
-///     &[Pad::new(
-///         &[Layer::Front],                // Pad layers
-///         (-2.0, 0.0),                    // Pad position
-///         0.0,                            // Pad rotation
-///         "M -1 -1 H 2 V 2 H -2 V -2",    // Pad shape (SVG)
-///         None,                           // Pad drill
-///         None,                           // Pad net
-///         true,                           // Pin 1
-///       ),
-///       // [...]
-///     ],
-///     true,                               // Mount or not
-///   ),
-/// );
-///
-/// // Add BOM rows (designators and their footprint IDs).
-/// ibom.bom_front.push(vec![RefMap::new("R1", id)]);
-/// ```
-#[non_exhaustive]
-pub struct InteractiveHtmlBom {
-  // Metadata
-  title: String,
-  company: String,
-  revision: String,
-  date: String,
-  bottom_left: (f32, f32),
-  top_right: (f32, f32),
-
-  /// Initial view mode
-  pub view_mode: ViewMode,
+/// Accepts a name or hex color like `red` or `#d04040`, optionally followed
+/// by one level of parentheses with numeric arguments like
+/// `rgb(208 64 64 / 50%)`.
+fn is_css_color(color: &str) -> bool {
+  let (name, args) = match color.strip_suffix(')') {
+    Some(function) => match function.split_once('(') {
+      Some((name, args)) => (name, Some(args)),
+      None => return false,
+    },
+    None => (color, None),
+  };
+  let name = name.strip_prefix('#').unwrap_or(name);
+  !name.is_empty()
+    && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+    && args.is_none_or(|args| {
+      args
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || " .,+-%/".contains(c))
+        && !args.contains("/*")
+    })
+}
 
-  /// Hightlight pin-1 mode
-  pub highlight_pin1: HighlightPin1Mode,
+/// Highlight pin-1 modes
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum HighlightPin1Mode {
+  /// No pins
+  #[default]
+  None,
+  /// Selected pins
+  Selected,
+  /// All pins
+  All,
+}
 
-  /// Dark mode on/off
-  pub dark_mode: bool,
+impl ToJson for HighlightPin1Mode {
+  fn to_json(&self) -> JsonValue {
+    match self {
+      HighlightPin1Mode::None => "none".into(),
+      HighlightPin1Mode::Selected => "selected".into(),
+      HighlightPin1Mode::All => "all".into(),
+    }
+  }
+}
 
-  /// Board drawings rotation \[°\]
-  pub board_rotation: f32,
+/// Layer enum
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Layer {
+  /// Front layer
+  Front,
+  /// Back layer
+  Back,
+}
 
-  /// Whether to offset the back side rotation or not
-  pub offset_back_rotation: bool,
+impl ToJson for Layer {
+  fn to_json(&self) -> JsonValue {
+    match self {
+      Layer::Front => "F".into(),
+      Layer::Back => "B".into(),
+    }
+  }
+}
 
-  /// Silkscreen visibility
-  pub show_silkscreen: bool,
+/// Drawing kind
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum DrawingKind {
+  /// Polygon
+  Polygon,
+  /// Component reference designator text
+  ReferenceText,
+  /// Component value text
+  ValueText,
+  /// Arbitrary text
+  Text,
+}
 
-  /// Fabrication layer visibility
-  pub show_fabrication: bool,
+/// Drawing layer
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum DrawingLayer {
+  /// PCB edge
+  Edge,
+  /// Silkscreen front
+  SilkscreenFront,
+  /// Silkscreen back
+  SilkscreenBack,
+  /// Fabrication front
+  FabricationFront,
+  /// Fabrication back
+  FabricationBack,
+  /// Solder paste front
+  PasteFront,
+  /// Solder paste back
+  PasteBack,
+  /// Solder mask front
+  MaskFront,
+  /// Solder mask back
+  MaskBack,
+  /// Copper front
+  CopperFront,
+  /// Copper back
+  CopperBack,
+  /// Auxiliary layer front, index into [InteractiveHtmlBom::aux_layers]
+  AuxiliaryFront(usize),
+  /// Auxiliary layer back, index into [InteractiveHtmlBom::aux_layers]
+  AuxiliaryBack(usize),
+}
 
-  /// Pads visibility
-  pub show_pads: bool,
+impl DrawingLayer {
+  fn is_back(&self) -> bool {
+    matches!(
+      self,
+      DrawingLayer::SilkscreenBack
+        | DrawingLayer::FabricationBack
+        | DrawingLayer::PasteBack
+        | DrawingLayer::MaskBack
+        | DrawingLayer::CopperBack
+        | DrawingLayer::AuxiliaryBack(_)
+    )
+  }
+}
 
-  /// Checkbox column names
-  pub checkboxes: Vec<String>,
+/// Auxiliary drawing layer
+///
+/// Additional layer for drawings like keepout regions or assembly notes,
+/// which can be toggled in the viewer's settings menu. Drawings are added
+/// with [DrawingLayer::AuxiliaryFront] and [DrawingLayer::AuxiliaryBack].
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub struct AuxiliaryLayer {
+  title: String,
+  color: String,
+  visible: bool,
+}
 
-  /// Custom field names, listed as columns
-  pub fields: Vec<String>,
+impl AuxiliaryLayer {
+  /// Construct object
+  ///
+  /// # Arguments
+  ///
+  /// * `title` - Title shown in the viewer's settings menu.
+  /// * `color` - CSS color of the drawings, e.g. `"rgba(255, 0, 0, 0.5)"`.
+  /// * `visible` - Initial visibility.
+  ///
+  /// # Returns
+  ///
+  /// Returns the new object.
+  pub fn new(title: &str, color: &str, visible: bool) -> AuxiliaryLayer {
+    AuxiliaryLayer {
+      title: title.to_owned(),
+      color: color.to_owned(),
+      visible,
+    }
+  }
 
-  /// User-defined HTML header
+  /// Get title
   ///
-  /// <div class="warning">
-  /// This should be used carefully as we neither guarantee forward- nor
-  /// backward-compatibility.
-  /// </div>
-  pub user_header: String,
+  /// # Returns
+  ///
+  /// Returns the layer title.
+  pub fn title(&self) -> &str {
+    &self.title
+  }
 
-  /// User-defined HTML footer
+  /// Get color
   ///
-  /// <div class="warning">
-  /// This should be used carefully as we neither guarantee forward- nor
-  /// backward-compatibility.
-  /// </div>
-  pub user_footer: String,
+  /// # Returns
+  ///
+  /// Returns the CSS color.
+  pub fn color(&self) -> &str {
+    &self.color
+  }
 
-  /// User-defined JavaScript
+  /// Get visibility
   ///
-  /// <div class="warning">
-  /// This should be used carefully as we neither guarantee forward- nor
-  /// backward-compatibility.
-  /// </div>
-  pub user_js: String,
+  /// # Returns
+  ///
+  /// Returns whether the layer is initially visible or not.
+  pub fn visible(&self) -> bool {
+    self.visible
+  }
+}
 
-  /// Drawings (PCB edges, silkscreen, fabrication)
-  pub drawings: Vec<Drawing>,
+/// Text content of a drawing
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+struct DrawingText {
+  text: String,
+  pos: (f64, f64),
+  size: (f64, f64),
+  angle: f64,
+}
 
-  /// PCB tracks
-  pub tracks: Vec<Track>,
+/// Net handle
+///
+/// Returned by [InteractiveHtmlBom::add_net]. The net name is shared between
+/// all clones, so assigning it to many objects is cheap.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct NetId(Arc<str>);
 
-  /// PCB vias
-  pub vias: Vec<Via>,
+impl ToJson for NetId {
+  fn to_json(&self) -> JsonValue {
+    self.name().into()
+  }
+}
 
-  /// PCB zones
-  pub zones: Vec<Zone>,
+impl NetId {
+  fn new(name: &str) -> NetId {
+    NetId(name.into())
+  }
 
-  /// Footprints
-  pub footprints: Vec<Footprint>,
+  /// Get net name
+  ///
+  /// # Returns
+  ///
+  /// Returns the net name.
+  pub fn name(&self) -> &str {
+    &self.0
+  }
+}
 
-  /// BOM rows front side
-  pub bom_front: Vec<Vec<RefMap>>,
+/// Registered nets by name, for fast lookup in [InteractiveHtmlBom::add_net]
+///
+/// Derived from the list of registered nets, thus not serialized and ignored
+/// when comparing. After deserializing, it is rebuilt on first use.
+#[derive(Clone, Debug, Default)]
+struct NetIndex(HashMap<Arc<str>, NetId>);
 
-  /// BOM rows back side
-  pub bom_back: Vec<Vec<RefMap>>,
+impl PartialEq for NetIndex {
+  fn eq(&self, _other: &Self) -> bool {
+    true
+  }
+}
 
-  /// BOM rows front+back
-  pub bom_both: Vec<Vec<RefMap>>,
+/// Drawing structure (SVG polygon or text)
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub struct Drawing {
+  kind: DrawingKind,
+  layer: DrawingLayer,
+  svgpath: String,
+  width: f64,
+  filled: bool,
+  text: Option<DrawingText>,
+  net: Option<NetId>,
 }
 
-impl InteractiveHtmlBom {
-  /// Construct object
+impl Drawing {
+  /// Construct drawing
   ///
   /// # Arguments
   ///
-  /// * `title` - Project title.
-  /// * `company` - Company/author name.
-  /// * `revision` - Project revision.
-  /// * `date` - Date/time as desired.
-  /// * `bottom_left` - Bottom left corner of bounding box (x, y) \[mm\].
-  /// * `top_right` - Top right corner of bounding box (x, y) \[mm\].
+  /// * `kind` - Drawing kind.
+  /// * `layer` - Drawing layer.
+  /// * `svgpath` - Outline as an SVG path \[mm\].
+  /// * `width` - Line width \[mm\].
+  /// * `filled` - Whether to fill the shape or not.
   ///
   /// # Returns
   ///
   /// Returns the new object.
   pub fn new(
-    title: &str,
-    company: &str,
-    revision: &str,
-    date: &str,
-    bottom_left: (f32, f32),
-    top_right: (f32, f32),
-  ) -> InteractiveHtmlBom {
-    InteractiveHtmlBom {
-      title: title.to_owned(),
-      revision: revision.to_owned(),
-      company: company.to_owned(),
-      date: date.to_owned(),
-      bottom_left,
-      top_right,
-      view_mode: ViewMode::LeftRight,
-      highlight_pin1: HighlightPin1Mode::None,
-      dark_mode: false,
-      board_rotation: 0.0,
-      offset_back_rotation: false,
-      show_silkscreen: true,
-      show_fabrication: true,
-      show_pads: true,
-      checkboxes: vec!["Sourced".into(), "Placed".into()],
-      fields: Vec::new(),
+    kind: DrawingKind,
+    layer: DrawingLayer,
+    svgpath: &str,
+    width: f64,
+    filled: bool,
+  ) -> Drawing {
+    Drawing {
+      kind,
+      layer,
+      svgpath: svgpath.to_owned(),
+      width,
+      filled,
+      text: None,
+      net: None,
+    }
+  }
+
+  /// Construct polygon drawing
+  ///
+  /// # Arguments
+  ///
+  /// * `layer` - Drawing layer.
+  /// * `svgpath` - Outline as an SVG path \[mm\].
+  /// * `width` - Line width \[mm\].
+  /// * `filled` - Whether to fill the shape or not.
+  ///
+  /// # Returns
+  ///
+  /// Returns the new object.
+  pub fn polygon(
+    layer: DrawingLayer,
+    svgpath: &str,
+    width: f64,
+    filled: bool,
+  ) -> Drawing {
+    Drawing::new(DrawingKind::Polygon, layer, svgpath, width, filled)
+  }
+
+  /// Construct polygon drawing from a registered net
+  ///
+  /// Same as [Drawing::polygon] followed by [Drawing::set_net_id], intended
+  /// for drawings on [DrawingLayer::CopperFront] and
+  /// [DrawingLayer::CopperBack].
+  ///
+  /// # Arguments
+  ///
+  /// * `layer` - Drawing layer.
+  /// * `svgpath` - Outline as an SVG path \[mm\].
+  /// * `width` - Line width \[mm\].
+  /// * `filled` - Whether to fill the shape or not.
+  /// * `net` - Net as returned by [InteractiveHtmlBom::add_net] (optional).
+  ///
+  /// # Returns
+  ///
+  /// Returns the new object.
+  pub fn polygon_with_net_id(
+    layer: DrawingLayer,
+    svgpath: &str,
+    width: f64,
+    filled: bool,
+    net: Option<NetId>,
+  ) -> Drawing {
+    let mut drawing = Drawing::polygon(layer, svgpath, width, filled);
+    drawing.net = net;
+    drawing
+  }
+
+  /// Construct reference designator text drawing
+  ///
+  /// The text must already be converted to an SVG path, which is stroked
+  /// with the given thickness. It is hidden in the viewer if references are
+  /// disabled.
+  ///
+  /// # Arguments
+  ///
+  /// * `layer` - Drawing layer.
+  /// * `svgpath` - Text strokes as an SVG path \[mm\].
+  /// * `thickness` - Stroke width \[mm\].
+  ///
+  /// # Returns
+  ///
+  /// Returns the new object.
+  pub fn reference_text(
+    layer: DrawingLayer,
+    svgpath: &str,
+    thickness: f64,
+  ) -> Drawing {
+    Drawing::new(DrawingKind::ReferenceText, layer, svgpath, thickness, false)
+  }
+
+  /// Construct value text drawing
+  ///
+  /// The text must already be converted to an SVG path, which is stroked
+  /// with the given thickness. It is hidden in the viewer if values are
+  /// disabled.
+  ///
+  /// # Arguments
+  ///
+  /// * `layer` - Drawing layer.
+  /// * `svgpath` - Text strokes as an SVG path \[mm\].
+  /// * `thickness` - Stroke width \[mm\].
+  ///
+  /// # Returns
+  ///
+  /// Returns the new object.
+  pub fn value_text(
+    layer: DrawingLayer,
+    svgpath: &str,
+    thickness: f64,
+  ) -> Drawing {
+    Drawing::new(DrawingKind::ValueText, layer, svgpath, thickness, false)
+  }
+
+  /// Construct text drawing
+  ///
+  /// In contrast to [Drawing::new], the text is rendered by the viewer with
+  /// a built-in stroke font. The text is centered at the given position.
+  ///
+  /// # Arguments
+  ///
+  /// * `kind` - Drawing kind, should be one of the text kinds.
+  /// * `layer` - Drawing layer.
+  /// * `text` - Text content, may contain line breaks.
+  /// * `pos` - Center position (x, y) \[mm\].
+  /// * `size` - Character size (w, h) \[mm\].
+  /// * `angle` - Rotation angle [°].
+  /// * `thickness` - Stroke width \[mm\].
+  ///
+  /// # Returns
+  ///
+  /// Returns the new object.
+  pub fn text(
+    kind: DrawingKind,
+    layer: DrawingLayer,
+    text: &str,
+    pos: impl Into<Point>,
+    size: (f64, f64),
+    angle: f64,
+    thickness: f64,
+  ) -> Drawing {
+    let pos = xy(pos);
+    Drawing {
+      kind,
+      layer,
+      svgpath: String::new(),
+      width: thickness,
+      filled: false,
+      text: Some(DrawingText {
+        text: text.to_owned(),
+        pos,
+        size,
+        angle,
+      }),
+      net: None,
+    }
+  }
+
+  /// Construct arc drawing
+  ///
+  /// Angles are measured from the positive X axis towards the positive Y
+  /// axis. The arc is drawn from `start_angle` to `end_angle`, thus the
+  /// sign of their difference determines the direction. A difference of
+  /// 360° or more results in a full circle.
+  ///
+  /// # Arguments
+  ///
+  /// * `layer` - Drawing layer.
+  /// * `center` - Center position (x, y) \[mm\].
+  /// * `radius` - Radius \[mm\].
+  /// * `start_angle` - Start angle [°].
+  /// * `end_angle` - End angle [°].
+  /// * `width` - Line width \[mm\].
+  ///
+  /// # Returns
+  ///
+  /// Returns the new object.
+  pub fn arc(
+    layer: DrawingLayer,
+    center: impl Into<Point>,
+    radius: f64,
+    start_angle: f64,
+    end_angle: f64,
+    width: f64,
+  ) -> Drawing {
+    let center = xy(center);
+    let point = |angle: f64| {
+      let rad = angle.to_radians();
+      (center.0 + radius * rad.cos(), center.1 + radius * rad.sin())
+    };
+    let sweep = end_angle - start_angle;
+    let start = point(start_angle);
+    let mut path = SvgPathBuilder::new();
+    path.move_to(start);
+    if sweep.abs() >= 360.0 {
+      // SVG arcs can't describe a full circle, thus split into two halves.
+      let sweep = 180.0_f64.copysign(sweep);
+      path.arc_to(point(start_angle + sweep), sweep);
+      path.arc_to(start, sweep);
+    } else {
+      path.arc_to(point(end_angle), sweep);
+    }
+    let svgpath = path.build();
+    Drawing::polygon(layer, &svgpath, width, false)
+  }
+
+  /// Construct rectangle drawing
+  ///
+  /// # Arguments
+  ///
+  /// * `layer` - Drawing layer.
+  /// * `center` - Center position (x, y) \[mm\].
+  /// * `size` - Size (w, h) \[mm\].
+  /// * `angle` - Rotation angle around the center [°].
+  /// * `width` - Line width \[mm\].
+  /// * `filled` - Whether to fill the shape or not.
+  ///
+  /// # Returns
+  ///
+  /// Returns the new object.
+  pub fn rect(
+    layer: DrawingLayer,
+    center: impl Into<Point>,
+    size: (f64, f64),
+    angle: f64,
+    width: f64,
+    filled: bool,
+  ) -> Drawing {
+    let center = xy(center);
+    let (dx, dy) = (size.0 / 2.0, size.1 / 2.0);
+    let points = [(-dx, -dy), (dx, -dy), (dx, dy), (-dx, dy)].map(|v| {
+      let (x, y) = rotate(v, angle);
+      (center.0 + x, center.1 + y)
+    });
+    Drawing::polygon_from_points(layer, points, width, filled)
+  }
+
+  /// Construct polygon drawing from a list of vertices
+  ///
+  /// The contour is closed automatically, so the first vertex must not be
+  /// repeated at the end.
+  ///
+  /// # Arguments
+  ///
+  /// * `layer` - Drawing layer.
+  /// * `points` - Vertices \[mm\].
+  /// * `width` - Line width \[mm\].
+  /// * `filled` - Whether to fill the shape or not.
+  ///
+  /// # Returns
+  ///
+  /// Returns the new object.
+  pub fn polygon_from_points(
+    layer: DrawingLayer,
+    points: impl IntoIterator<Item = impl Into<Point>>,
+    width: f64,
+    filled: bool,
+  ) -> Drawing {
+    let mut path = SvgPathBuilder::new();
+    let mut points = points.into_iter();
+    if let Some(first) = points.next() {
+      path.move_to(first);
+      for point in points {
+        path.line_to(point);
+      }
+      path.close();
+    }
+    let svgpath = path.build();
+    Drawing::polygon(layer, &svgpath, width, filled)
+  }
+
+  /// Set net
+  ///
+  /// Only relevant for drawings on [DrawingLayer::CopperFront] and
+  /// [DrawingLayer::CopperBack], which are then highlighted together with
+  /// the tracks, zones and pads of the same net.
+  ///
+  /// # Arguments
+  ///
+  /// * `net` - Net name.
+  pub fn set_net(&mut self, net: Option<&str>) {
+    self.net = net.map(NetId::new);
+  }
+
+  /// Set net from a registered net
+  ///
+  /// Same as [Drawing::set_net], but without copying the net name.
+  ///
+  /// # Arguments
+  ///
+  /// * `net` - Net as returned by [InteractiveHtmlBom::add_net].
+  pub fn set_net_id(&mut self, net: Option<NetId>) {
+    self.net = net;
+  }
+
+  /// Get copper drawing as zone, if it is filled
+  fn zone_json(&self) -> Option<JsonValue> {
+    if !self.filled {
+      return None;
+    }
+    let mut obj = object! {
+      svgpath: self.svgpath.clone(),
+      width: self.width,
+    };
+    if let Some(net) = &self.net {
+      obj["net"] = net.to_json();
+    }
+    Some(obj)
+  }
+
+  /// Get copper drawing as track segments, if it is not filled
+  ///
+  /// Malformed paths result in no segments, they have to be rejected before.
+  fn tracks_json(&self) -> Vec<JsonValue> {
+    if self.filled {
+      return Vec::new();
+    }
+    let mut tracks = Vec::new();
+    for polyline in svgpath::flatten(&self.svgpath).unwrap_or_default() {
+      let mut points = polyline.points.clone();
+      if polyline.closed {
+        points.push(points[0]);
+      }
+      for segment in points.windows(2) {
+        let mut obj = object! {
+          start: segment[0].to_json(),
+          end: segment[1].to_json(),
+          width: self.width,
+        };
+        if let Some(net) = &self.net {
+          obj["net"] = net.to_json();
+        }
+        tracks.push(obj);
+      }
+    }
+    tracks
+  }
+
+  /// Get bounding box, if not empty
+  fn bounds(&self) -> Option<((f64, f64), (f64, f64))> {
+    match &self.text {
+      Some(text) => Some((text.pos, text.pos)),
+      None => svgpath::bounds(&self.svgpath),
+    }
+  }
+
+  /// Get kind
+  ///
+  /// # Returns
+  ///
+  /// Returns the drawing kind.
+  pub fn kind(&self) -> &DrawingKind {
+    &self.kind
+  }
+
+  /// Get layer
+  ///
+  /// # Returns
+  ///
+  /// Returns the drawing layer.
+  pub fn layer(&self) -> &DrawingLayer {
+    &self.layer
+  }
+
+  /// Get SVG path
+  ///
+  /// # Returns
+  ///
+  /// Returns the SVG path \[mm\], empty for text drawings.
+  pub fn svgpath(&self) -> &str {
+    &self.svgpath
+  }
+
+  /// Get line width
+  ///
+  /// # Returns
+  ///
+  /// Returns the line width \[mm\].
+  pub fn width(&self) -> f64 {
+    self.width
+  }
+
+  /// Get fill state
+  ///
+  /// # Returns
+  ///
+  /// Returns whether the shape is filled or not.
+  pub fn filled(&self) -> bool {
+    self.filled
+  }
+
+  /// Get net
+  ///
+  /// # Returns
+  ///
+  /// Returns the net name, if any.
+  pub fn net(&self) -> Option<&str> {
+    self.net.as_ref().map(NetId::name)
+  }
+}
+
+impl ToJson for Drawing {
+  fn to_json(&self) -> JsonValue {
+    if let Some(text) = &self.text {
+      let mirrored = self.layer.is_back();
+      let mut obj = object! {
+        pos: text.pos.to_json(),
+        text: text.text.clone(),
+        width: text.size.0,
+        height: text.size.1,
+        justify: array![0, 0],
+        thickness: self.width,
+        attr: if mirrored { array!["mirrored"] } else { array![] },
+        angle: text.angle,
+      };
+      match self.kind {
+        DrawingKind::ReferenceText => obj["ref"] = 1.into(),
+        DrawingKind::ValueText => obj["val"] = 1.into(),
+        _ => {}
+      }
+      return obj;
+    }
+    let mut obj = object! {
+      svgpath: self.svgpath.clone(),
+      filled: self.filled,
+    };
+    match self.kind {
+      DrawingKind::Polygon => {
+        obj["type"] = "polygon".into();
+        obj["width"] = self.width.into();
+      }
+      DrawingKind::ReferenceText => {
+        obj["thickness"] = self.width.into();
+        obj["ref"] = 1.into();
+      }
+      DrawingKind::ValueText => {
+        obj["thickness"] = self.width.into();
+        obj["val"] = 1.into();
+      }
+      DrawingKind::Text => {
+        obj["thickness"] = self.width.into();
+      }
+    }
+    obj
+  }
+}
+
+/// Track structure
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub struct Track {
+  layer: Layer,
+  start: (f64, f64),
+  end: (f64, f64),
+  width: f64,
+  net: Option<NetId>,
+}
+
+impl Track {
+  /// Construct track
+  ///
+  /// # Arguments
+  ///
+  /// * `layer` - Layer.
+  /// * `start` - Start position (x, y) \[mm\].
+  /// * `end` - End position (x, y) \[mm\].
+  /// * `width` - Track width \[mm\].
+  /// * `net` - Net name (optional).
+  ///
+  /// # Returns
+  ///
+  /// Returns the new object.
+  pub fn new(
+    layer: Layer,
+    start: impl Into<Point>,
+    end: impl Into<Point>,
+    width: f64,
+    net: Option<&str>,
+  ) -> Track {
+    Track::with_net_id(layer, start, end, width, net.map(NetId::new))
+  }
+
+  /// Construct track from a registered net
+  ///
+  /// Same as [Track::new], but without copying the net name.
+  ///
+  /// # Arguments
+  ///
+  /// * `layer` - Layer.
+  /// * `start` - Start position (x, y) \[mm\].
+  /// * `end` - End position (x, y) \[mm\].
+  /// * `width` - Track width \[mm\].
+  /// * `net` - Net as returned by [InteractiveHtmlBom::add_net] (optional).
+  ///
+  /// # Returns
+  ///
+  /// Returns the new object.
+  pub fn with_net_id(
+    layer: Layer,
+    start: impl Into<Point>,
+    end: impl Into<Point>,
+    width: f64,
+    net: Option<NetId>,
+  ) -> Track {
+    let start = xy(start);
+    let end = xy(end);
+    Track {
+      layer,
+      start,
+      end,
+      width,
+      net,
+    }
+  }
+
+  /// Set net from a registered net
+  ///
+  /// Replaces the net passed to the constructor without copying the net
+  /// name.
+  ///
+  /// # Arguments
+  ///
+  /// * `net` - Net as returned by [InteractiveHtmlBom::add_net].
+  pub fn set_net_id(&mut self, net: Option<NetId>) {
+    self.net = net;
+  }
+
+  /// Get layer
+  ///
+  /// # Returns
+  ///
+  /// Returns the track layer.
+  pub fn layer(&self) -> &Layer {
+    &self.layer
+  }
+
+  /// Get start position
+  ///
+  /// # Returns
+  ///
+  /// Returns the start position \[mm\].
+  pub fn start(&self) -> Point {
+    self.start.into()
+  }
+
+  /// Get end position
+  ///
+  /// # Returns
+  ///
+  /// Returns the end position \[mm\].
+  pub fn end(&self) -> Point {
+    self.end.into()
+  }
+
+  /// Get width
+  ///
+  /// # Returns
+  ///
+  /// Returns the track width \[mm\].
+  pub fn width(&self) -> f64 {
+    self.width
+  }
+
+  /// Get net
+  ///
+  /// # Returns
+  ///
+  /// Returns the net name, if any.
+  pub fn net(&self) -> Option<&str> {
+    self.net.as_ref().map(NetId::name)
+  }
+}
+
+impl ToJson for Track {
+  fn to_json(&self) -> JsonValue {
+    let mut obj = object! {
+      start: self.start.to_json(),
+      end: self.end.to_json(),
+      width: self.width,
+    };
+    if let Some(net) = &self.net {
+      obj["net"] = net.to_json();
+    }
+    obj
+  }
+}
+
+/// Via structure
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub struct Via {
+  layers: Vec<Layer>,
+  pos: (f64, f64),
+  diameter: f64,
+  drill_diameter: f64,
+  net: Option<NetId>,
+}
+
+impl Via {
+  /// Construct via
+  ///
+  /// # Arguments
+  ///
+  /// * `layers` - Layers.
+  /// * `pos` - Position (x, y) \[mm\].
+  /// * `diameter` - Outer diameter \[mm\].
+  /// * `drill_diameter` - Drill diameter \[mm\].
+  /// * `net` - Net name (optional).
+  ///
+  /// # Returns
+  ///
+  /// Returns the new object.
+  pub fn new(
+    layers: impl IntoIterator<Item = Layer>,
+    pos: impl Into<Point>,
+    diameter: f64,
+    drill_diameter: f64,
+    net: Option<&str>,
+  ) -> Via {
+    let net = net.map(NetId::new);
+    Via::with_net_id(layers, pos, diameter, drill_diameter, net)
+  }
+
+  /// Construct via from a registered net
+  ///
+  /// Same as [Via::new], but without copying the net name.
+  ///
+  /// # Arguments
+  ///
+  /// * `layers` - Layers.
+  /// * `pos` - Position (x, y) \[mm\].
+  /// * `diameter` - Outer diameter \[mm\].
+  /// * `drill_diameter` - Drill diameter \[mm\].
+  /// * `net` - Net as returned by [InteractiveHtmlBom::add_net] (optional).
+  ///
+  /// # Returns
+  ///
+  /// Returns the new object.
+  pub fn with_net_id(
+    layers: impl IntoIterator<Item = Layer>,
+    pos: impl Into<Point>,
+    diameter: f64,
+    drill_diameter: f64,
+    net: Option<NetId>,
+  ) -> Via {
+    let pos = xy(pos);
+    Via {
+      layers: layers.into_iter().collect(),
+      pos,
+      diameter,
+      drill_diameter,
+      net,
+    }
+  }
+
+  /// Construct via from a copper layer span
+  ///
+  /// Allows specifying blind and buried vias including inner layers. Since
+  /// only the outer layers are rendered, blind vias are shown only on the
+  /// side they are connected to, and buried vias are not shown at all.
+  ///
+  /// # Arguments
+  ///
+  /// * `layer_span` - Indices of the first and last copper layer the via
+  ///   connects, with 0 being the front layer and `layer_count - 1` the back
+  ///   layer.
+  /// * `layer_count` - Total number of copper layers of the board.
+  /// * `pos` - Position (x, y) \[mm\].
+  /// * `diameter` - Outer diameter \[mm\].
+  /// * `drill_diameter` - Drill diameter \[mm\].
+  /// * `net` - Net name (optional).
+  ///
+  /// # Returns
+  ///
+  /// Returns the new object.
+  pub fn with_layer_span(
+    layer_span: (usize, usize),
+    layer_count: usize,
+    pos: impl Into<Point>,
+    diameter: f64,
+    drill_diameter: f64,
+    net: Option<&str>,
+  ) -> Via {
+    let pos = xy(pos);
+    let first = layer_span.0.min(layer_span.1);
+    let last = layer_span.0.max(layer_span.1);
+    let mut layers = Vec::new();
+    if first == 0 {
+      layers.push(Layer::Front);
+    }
+    if last + 1 >= layer_count {
+      layers.push(Layer::Back);
+    }
+    Via::new(layers, pos, diameter, drill_diameter, net)
+  }
+
+  /// Set net from a registered net
+  ///
+  /// Replaces the net passed to the constructor without copying the net
+  /// name.
+  ///
+  /// # Arguments
+  ///
+  /// * `net` - Net as returned by [InteractiveHtmlBom::add_net].
+  pub fn set_net_id(&mut self, net: Option<NetId>) {
+    self.net = net;
+  }
+
+  /// Get layers
+  ///
+  /// # Returns
+  ///
+  /// Returns the layers on which the via is shown.
+  pub fn layers(&self) -> &[Layer] {
+    &self.layers
+  }
+
+  /// Get position
+  ///
+  /// # Returns
+  ///
+  /// Returns the position \[mm\].
+  pub fn pos(&self) -> Point {
+    self.pos.into()
+  }
+
+  /// Get outer diameter
+  ///
+  /// # Returns
+  ///
+  /// Returns the outer diameter \[mm\].
+  pub fn diameter(&self) -> f64 {
+    self.diameter
+  }
+
+  /// Get drill diameter
+  ///
+  /// # Returns
+  ///
+  /// Returns the drill diameter \[mm\].
+  pub fn drill_diameter(&self) -> f64 {
+    self.drill_diameter
+  }
+
+  /// Get net
+  ///
+  /// # Returns
+  ///
+  /// Returns the net name, if any.
+  pub fn net(&self) -> Option<&str> {
+    self.net.as_ref().map(NetId::name)
+  }
+}
+
+impl ToJson for Via {
+  fn to_json(&self) -> JsonValue {
+    let mut obj = object! {
+      start: self.pos.to_json(),
+      end: self.pos.to_json(),
+      width: self.diameter,
+      drillsize: self.drill_diameter,
+    };
+    if let Some(net) = &self.net {
+      obj["net"] = net.to_json();
+    }
+    obj
+  }
+}
+
+/// Zone structure
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub struct Zone {
+  layer: Layer,
+  svgpath: String,
+  holes: Vec<String>,
+  net: Option<NetId>,
+}
+
+impl Zone {
+  /// Construct object
+  ///
+  /// # Arguments
+  ///
+  /// * `layer` - Layer.
+  /// * `svgpath` - Zone outline as SVG path \[mm\].
+  /// * `net` - Net name (optional).
+  ///
+  /// # Returns
+  ///
+  /// Returns the new object.
+  pub fn new(layer: Layer, svgpath: &str, net: Option<&str>) -> Zone {
+    Zone::with_holes(layer, svgpath, &[], net)
+  }
+
+  /// Construct object from a registered net
+  ///
+  /// Same as [Zone::new], but without copying the net name.
+  ///
+  /// # Arguments
+  ///
+  /// * `layer` - Layer.
+  /// * `svgpath` - Zone outline as SVG path \[mm\].
+  /// * `net` - Net as returned by [InteractiveHtmlBom::add_net] (optional).
+  ///
+  /// # Returns
+  ///
+  /// Returns the new object.
+  pub fn with_net_id(layer: Layer, svgpath: &str, net: Option<NetId>) -> Zone {
+    let mut zone = Zone::new(layer, svgpath, None);
+    zone.net = net;
+    zone
+  }
+
+  /// Construct object with holes
+  ///
+  /// # Arguments
+  ///
+  /// * `layer` - Layer.
+  /// * `svgpath` - Zone outline as SVG path \[mm\].
+  /// * `holes` - Hole outlines as SVG paths \[mm\].
+  /// * `net` - Net name (optional).
+  ///
+  /// # Returns
+  ///
+  /// Returns the new object.
+  pub fn with_holes(
+    layer: Layer,
+    svgpath: &str,
+    holes: &[&str],
+    net: Option<&str>,
+  ) -> Zone {
+    Zone {
+      layer,
+      svgpath: svgpath.to_owned(),
+      holes: holes.iter().map(|s| s.to_string()).collect(),
+      net: net.map(NetId::new),
+    }
+  }
+
+  /// Set net from a registered net
+  ///
+  /// Replaces the net passed to the constructor without copying the net
+  /// name.
+  ///
+  /// # Arguments
+  ///
+  /// * `net` - Net as returned by [InteractiveHtmlBom::add_net].
+  pub fn set_net_id(&mut self, net: Option<NetId>) {
+    self.net = net;
+  }
+
+  /// Get layer
+  ///
+  /// # Returns
+  ///
+  /// Returns the zone layer.
+  pub fn layer(&self) -> &Layer {
+    &self.layer
+  }
+
+  /// Get outline
+  ///
+  /// # Returns
+  ///
+  /// Returns the outline as SVG path \[mm\].
+  pub fn svgpath(&self) -> &str {
+    &self.svgpath
+  }
+
+  /// Get holes
+  ///
+  /// # Returns
+  ///
+  /// Returns the holes as SVG paths \[mm\].
+  pub fn holes(&self) -> &[String] {
+    &self.holes
+  }
+
+  /// Get net
+  ///
+  /// # Returns
+  ///
+  /// Returns the net name, if any.
+  pub fn net(&self) -> Option<&str> {
+    self.net.as_ref().map(NetId::name)
+  }
+}
+
+impl ToJson for Zone {
+  fn to_json(&self) -> JsonValue {
+    let mut obj = object! {
+      svgpath: self.svgpath.clone(),
+    };
+    if !self.holes.is_empty() {
+      // Holes are independent of the contour orientation with even-odd.
+      obj["svgpath"] =
+        (self.svgpath.clone() + " " + &self.holes.join(" ")).into();
+      obj["fillrule"] = "evenodd".into();
+    }
+    if let Some(net) = &self.net {
+      obj["net"] = net.to_json();
+    }
+    obj
+  }
+}
+
+/// Board outline structure
+///
+/// A board contour with optional cutouts. Boards consisting of multiple
+/// detached parts (e.g. panels) can be represented by multiple outlines.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub struct BoardOutline {
+  outline: String,
+  cutouts: Vec<String>,
+  width: f64,
+}
+
+impl BoardOutline {
+  /// Construct object
+  ///
+  /// # Arguments
+  ///
+  /// * `outline` - Outer contour as SVG path \[mm\].
+  /// * `cutouts` - Inner contours (cutouts) as SVG paths \[mm\].
+  /// * `width` - Line width \[mm\].
+  ///
+  /// # Returns
+  ///
+  /// Returns the new object.
+  pub fn new(outline: &str, cutouts: &[&str], width: f64) -> BoardOutline {
+    BoardOutline {
+      outline: outline.to_owned(),
+      cutouts: cutouts.iter().map(|s| s.to_string()).collect(),
+      width,
+    }
+  }
+
+  /// Get outline
+  ///
+  /// # Returns
+  ///
+  /// Returns the outline as SVG path \[mm\].
+  pub fn outline(&self) -> &str {
+    &self.outline
+  }
+
+  /// Get cutouts
+  ///
+  /// # Returns
+  ///
+  /// Returns the cutouts as SVG paths \[mm\].
+  pub fn cutouts(&self) -> &[String] {
+    &self.cutouts
+  }
+
+  /// Get line width
+  ///
+  /// # Returns
+  ///
+  /// Returns the line width \[mm\].
+  pub fn width(&self) -> f64 {
+    self.width
+  }
+}
+
+impl BoardOutline {
+  /// Convert to JSON, one edge drawing per contour
+  ///
+  /// The contours are not joined into a single path since relative commands
+  /// at the start of a cutout would then refer to the end of the previous
+  /// contour.
+  fn contours_json(&self) -> impl Iterator<Item = JsonValue> + '_ {
+    std::iter::once(&self.outline)
+      .chain(&self.cutouts)
+      .map(|x| Drawing::polygon(DrawingLayer::Edge, x, self.width, false))
+      .map(|x| x.to_json())
+  }
+}
+
+/// Dimension structure
+///
+/// A linear dimension between two anchor points, drawn on the fabrication
+/// layer with extension lines, arrows and a text.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub struct Dimension {
+  layer: Layer,
+  start: (f64, f64),
+  end: (f64, f64),
+  offset: f64,
+  text: Option<String>,
+  text_size: f64,
+  width: f64,
+}
+
+impl Dimension {
+  /// Construct object
+  ///
+  /// # Arguments
+  ///
+  /// * `layer` - Fabrication layer side.
+  /// * `start` - First anchor point (x, y) \[mm\].
+  /// * `end` - Second anchor point (x, y) \[mm\].
+  /// * `offset` - Distance of the dimension line from the anchor points
+  ///   \[mm\], positive values to the left when looking from `start` to
+  ///   `end` on the front side.
+  /// * `text` - Text to display. If [None], the measured distance is shown.
+  /// * `text_size` - Text height \[mm\], also used as arrow size.
+  /// * `width` - Line width \[mm\].
+  ///
+  /// # Returns
+  ///
+  /// Returns the new object.
+  pub fn new(
+    layer: Layer,
+    start: impl Into<Point>,
+    end: impl Into<Point>,
+    offset: f64,
+    text: Option<&str>,
+    text_size: f64,
+    width: f64,
+  ) -> Dimension {
+    let start = xy(start);
+    let end = xy(end);
+    Dimension {
+      layer,
+      start,
+      end,
+      offset,
+      text: text.map(|s| s.to_owned()),
+      text_size,
+      width,
+    }
+  }
+
+  fn drawings(&self) -> Vec<Drawing> {
+    let layer = match self.layer {
+      Layer::Front => DrawingLayer::FabricationFront,
+      Layer::Back => DrawingLayer::FabricationBack,
+    };
+    let delta = (self.end.0 - self.start.0, self.end.1 - self.start.1);
+    let length = (delta.0 * delta.0 + delta.1 * delta.1).sqrt();
+    if length == 0.0 {
+      return Vec::new();
+    }
+    let dir = (delta.0 / length, delta.1 / length);
+    let normal = (dir.1, -dir.0);
+    let side = if self.offset < 0.0 { -1.0 } else { 1.0 };
+    let along = |p: (f64, f64), dist: f64, across: f64| {
+      (
+        p.0 + dir.0 * dist + normal.0 * across,
+        p.1 + dir.1 * dist + normal.1 * across,
+      )
+    };
+
+    // Extension lines, dimension line and arrows.
+    let extension = self.offset + side * self.text_size * 0.5;
+    let arrow = self.text_size.min(length / 2.0);
+    let a = along(self.start, 0.0, self.offset);
+    let b = along(self.end, 0.0, self.offset);
+    let mut path = SvgPathBuilder::new();
+    path
+      .move_to(self.start)
+      .line_to(along(self.start, 0.0, extension));
+    path
+      .move_to(self.end)
+      .line_to(along(self.end, 0.0, extension));
+    path.move_to(a).line_to(b);
+    path
+      .move_to(along(a, arrow, arrow / 3.0))
+      .line_to(a)
+      .line_to(along(a, arrow, -arrow / 3.0));
+    path
+      .move_to(along(b, -arrow, arrow / 3.0))
+      .line_to(b)
+      .line_to(along(b, -arrow, -arrow / 3.0));
+    let lines =
+      Drawing::polygon(layer.clone(), &path.build(), self.width, false);
+
+    // Text centered above the dimension line, kept readable.
+    let text = match &self.text {
+      Some(text) => text.clone(),
+      None => format!("{:.2} mm", length),
+    };
+    let pos = along(
+      self.start,
+      length / 2.0,
+      self.offset + side * (self.text_size * 0.75 + self.width),
+    );
+    let mut angle = match self.layer {
+      Layer::Front => (-dir.1).atan2(dir.0),
+      Layer::Back => dir.1.atan2(-dir.0),
+    }
+    .to_degrees();
+    if angle > 90.0 {
+      angle -= 180.0;
+    } else if angle <= -90.0 {
+      angle += 180.0;
+    }
+    let label = Drawing::text(
+      DrawingKind::Text,
+      layer,
+      &text,
+      pos,
+      (self.text_size, self.text_size),
+      angle,
+      self.width,
+    );
+    vec![lines, label]
+  }
+
+  /// Get layer
+  ///
+  /// # Returns
+  ///
+  /// Returns the board side.
+  pub fn layer(&self) -> &Layer {
+    &self.layer
+  }
+
+  /// Get start point
+  ///
+  /// # Returns
+  ///
+  /// Returns the start point \[mm\].
+  pub fn start(&self) -> Point {
+    self.start.into()
+  }
+
+  /// Get end point
+  ///
+  /// # Returns
+  ///
+  /// Returns the end point \[mm\].
+  pub fn end(&self) -> Point {
+    self.end.into()
+  }
+
+  /// Get offset
+  ///
+  /// # Returns
+  ///
+  /// Returns the offset of the dimension line \[mm\].
+  pub fn offset(&self) -> f64 {
+    self.offset
+  }
+
+  /// Get custom text
+  ///
+  /// # Returns
+  ///
+  /// Returns the custom text, if any.
+  pub fn text(&self) -> Option<&str> {
+    self.text.as_deref()
+  }
+}
+
+/// Hole structure
+///
+/// Round holes or slots which do not belong to any footprint, e.g. mounting
+/// holes or milled slots. Their outline is shown on both board sides as part
+/// of the board outline, plated holes are additionally drawn as copper.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub struct Hole {
+  pos: (f64, f64),
+  size: (f64, f64),
+  angle: f64,
+  plated: bool,
+}
+
+impl Hole {
+  /// Construct non-plated round hole
+  ///
+  /// # Arguments
+  ///
+  /// * `pos` - Position (x, y) \[mm\].
+  /// * `diameter` - Drill diameter \[mm\].
+  ///
+  /// # Returns
+  ///
+  /// Returns the new object.
+  pub fn new(pos: impl Into<Point>, diameter: f64) -> Hole {
+    let pos = xy(pos);
+    Hole::slot(pos, (diameter, diameter), 0.0, false)
+  }
+
+  /// Construct slot
+  ///
+  /// # Arguments
+  ///
+  /// * `pos` - Center position (x, y) \[mm\].
+  /// * `size` - Outer size (w, h) \[mm\], the smaller dimension being the
+  ///   tool diameter.
+  /// * `angle` - Rotation angle [°].
+  /// * `plated` - Whether the slot is plated or not.
+  ///
+  /// # Returns
+  ///
+  /// Returns the new object.
+  pub fn slot(
+    pos: impl Into<Point>,
+    size: (f64, f64),
+    angle: f64,
+    plated: bool,
+  ) -> Hole {
+    let pos = xy(pos);
+    Hole {
+      pos,
+      size,
+      angle,
+      plated,
+    }
+  }
+
+  /// Get the slot as radius and the end points of its center line
+  fn center_line(&self) -> (f64, (f64, f64), (f64, f64)) {
+    let (length, diameter, angle) = if self.size.0 >= self.size.1 {
+      (self.size.0, self.size.1, self.angle)
+    } else {
+      (self.size.1, self.size.0, self.angle + 90.0)
+    };
+    let (dx, dy) = rotate(((length - diameter) / 2.0, 0.0), angle);
+    let start = (self.pos.0 - dx, self.pos.1 - dy);
+    let end = (self.pos.0 + dx, self.pos.1 + dy);
+    (diameter / 2.0, start, end)
+  }
+
+  /// Get the copper of plated holes as track JSON
+  fn copper_json(&self) -> Option<JsonValue> {
+    let (radius, start, end) = self.center_line();
+    self.plated.then(|| {
+      object! {
+        start: start.to_json(),
+        end: end.to_json(),
+        width: 2.0 * radius,
+      }
+    })
+  }
+
+  /// Get position
+  ///
+  /// # Returns
+  ///
+  /// Returns the position \[mm\].
+  pub fn pos(&self) -> Point {
+    self.pos.into()
+  }
+
+  /// Get size
+  ///
+  /// # Returns
+  ///
+  /// Returns the size (w, h) \[mm\].
+  pub fn size(&self) -> (f64, f64) {
+    self.size
+  }
+
+  /// Get rotation
+  ///
+  /// # Returns
+  ///
+  /// Returns the rotation angle [°].
+  pub fn angle(&self) -> f64 {
+    self.angle
+  }
+
+  /// Get plating
+  ///
+  /// # Returns
+  ///
+  /// Returns whether the hole is plated or not.
+  pub fn plated(&self) -> bool {
+    self.plated
+  }
+}
+
+impl ToJson for Hole {
+  fn to_json(&self) -> JsonValue {
+    let (radius, start, end) = self.center_line();
+    let (nx, ny) = if start == end {
+      (0.0, -radius)
+    } else {
+      let length = (end.0 - start.0).hypot(end.1 - start.1);
+      (
+        (end.1 - start.1) / length * radius,
+        (start.0 - end.0) / length * radius,
+      )
+    };
+    let svgpath = SvgPathBuilder::new()
+      .move_to((start.0 + nx, start.1 + ny))
+      .line_to((end.0 + nx, end.1 + ny))
+      .arc_to((end.0 - nx, end.1 - ny), 180.0)
+      .line_to((start.0 - nx, start.1 - ny))
+      .arc_to((start.0 + nx, start.1 + ny), 180.0)
+      .close()
+      .build();
+    Drawing::polygon(DrawingLayer::Edge, &svgpath, 0.0, false).to_json()
+  }
+}
+
+/// Pad corner style, used by [PadShape::Corners]
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum PadCorner {
+  /// Sharp corner
+  Sharp,
+  /// Rounded corner with radius \[mm\]
+  Round(f64),
+  /// Chamfered corner with chamfer size \[mm\]
+  Chamfer(f64),
+}
+
+/// Pad shape
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum PadShape {
+  /// Rectangle with size (w, h) \[mm\]
+  Rect((f64, f64)),
+  /// Circle with diameter \[mm\]
+  Circle(f64),
+  /// Oval (obround) with size (w, h) \[mm\]
+  Oval((f64, f64)),
+  /// Rounded rectangle with size (w, h) \[mm\] and corner radius \[mm\]
+  RoundRect((f64, f64), f64),
+  /// Rectangle with size (w, h) \[mm\] and individual corners in the
+  /// order (-x, -y), (+x, -y), (-x, +y), (+x, +y)
+  Corners((f64, f64), [PadCorner; 4]),
+  /// Custom shape as SVG path \[mm\]
+  Custom(String),
+}
+
+impl PadShape {
+  fn write_json(&self, obj: &mut JsonValue) {
+    match self {
+      PadShape::Rect(size) => {
+        obj["shape"] = "rect".into();
+        obj["size"] = size.to_json();
+      }
+      PadShape::Circle(diameter) => {
+        obj["shape"] = "circle".into();
+        obj["size"] = (*diameter, *diameter).to_json();
+      }
+      PadShape::Oval(size) => {
+        obj["shape"] = "oval".into();
+        obj["size"] = size.to_json();
+      }
+      PadShape::RoundRect(size, radius) => {
+        obj["shape"] = "roundrect".into();
+        obj["size"] = size.to_json();
+        obj["radius"] = (*radius).into();
+      }
+      PadShape::Corners(size, corners) => {
+        let mut radii = corners.iter().filter_map(|c| match c {
+          PadCorner::Sharp => Some(0.0),
+          PadCorner::Round(radius) => Some(*radius),
+          PadCorner::Chamfer(_) => None,
+        });
+        let mut chamfers = corners.iter().filter_map(|c| match c {
+          PadCorner::Chamfer(chamfer) => Some(*chamfer),
+          _ => None,
+        });
+        let radius = radii.next().unwrap_or(0.0);
+        let chamfer = chamfers.next().unwrap_or(0.0);
+        let min_size = size.0.min(size.1);
+        if radii.all(|r| r == radius)
+          && chamfers.all(|c| c == chamfer)
+          && min_size > 0.0
+        {
+          // Natively supported by the viewer.
+          let mut chamfpos = 0;
+          for (i, corner) in corners.iter().enumerate() {
+            if let PadCorner::Chamfer(_) = corner {
+              chamfpos |= 1 << i;
+            }
+          }
+          obj["shape"] = "chamfrect".into();
+          obj["size"] = size.to_json();
+          obj["radius"] = radius.into();
+          obj["chamfpos"] = chamfpos.into();
+          obj["chamfratio"] = (chamfer / min_size).into();
+        } else {
+          obj["shape"] = "custom".into();
+          obj["svgpath"] = corners_svgpath(*size, corners).into();
+        }
+      }
+      PadShape::Custom(svgpath) => {
+        obj["shape"] = "custom".into();
+        obj["svgpath"] = svgpath.clone().into();
+      }
+    }
+  }
+}
+
+/// Build the outline of a [PadShape::Corners] pad
+fn corners_svgpath(size: (f64, f64), corners: &[PadCorner; 4]) -> String {
+  let (x, y) = (size.0 / 2.0, size.1 / 2.0);
+  let max_inset = x.min(y);
+  // Clockwise in Y-down coordinates, with (dx, dy) of the incoming and
+  // outgoing edge.
+  let outline = [
+    ((-x, -y), corners[0], (0.0, -1.0), (1.0, 0.0)),
+    ((x, -y), corners[1], (1.0, 0.0), (0.0, 1.0)),
+    ((x, y), corners[3], (0.0, 1.0), (-1.0, 0.0)),
+    ((-x, y), corners[2], (-1.0, 0.0), (0.0, -1.0)),
+  ];
+  let mut path = SvgPathBuilder::new();
+  for (i, (pos, corner, dir_in, dir_out)) in outline.iter().enumerate() {
+    let inset = match corner {
+      PadCorner::Sharp => 0.0,
+      PadCorner::Round(inset) | PadCorner::Chamfer(inset) => {
+        inset.clamp(0.0, max_inset)
+      }
+    };
+    let entry = (pos.0 - dir_in.0 * inset, pos.1 - dir_in.1 * inset);
+    let exit = (pos.0 + dir_out.0 * inset, pos.1 + dir_out.1 * inset);
+    if i == 0 {
+      path.move_to(entry);
+    } else {
+      path.line_to(entry);
+    }
+    if inset > 0.0 {
+      match corner {
+        PadCorner::Round(_) => path.arc_to(exit, 90.0),
+        _ => path.line_to(exit),
+      };
+    }
+  }
+  path.close().build()
+}
+
+/// Footprint pad structure
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub struct Pad {
+  layers: Vec<Layer>,
+  pos: (f64, f64),
+  angle: f64,
+  shape: PadShape,
+  drill_size: Option<(f64, f64)>,
+  drill_offset: (f64, f64),
+  net: Option<NetId>,
+  pin1: bool,
+  pin_number: Option<String>,
+  pin_name: Option<String>,
+}
+
+impl Pad {
+  /// Construct object
+  ///
+  /// # Arguments
+  ///
+  /// * `layers` - Layers on which the pad exists.
+  /// * `pos` - Position (x, y) \[mm\].
+  /// * `angle` - Rotation angle [°].
+  /// * `svgpath` - Pad shape as SVG path \[mm\].
+  /// * `drill_size` - Drill size (w, h) \[mm\] (only for THT pads).
+  /// * `net` - Net name (optional).
+  /// * `pin1` - Whether this is considered as the pin-1 or not.
+  ///
+  /// # Returns
+  ///
+  /// Returns the new object.
+  pub fn new(
+    layers: impl IntoIterator<Item = Layer>,
+    pos: impl Into<Point>,
+    angle: impl Into<Angle>,
+    svgpath: impl Into<String>,
+    drill_size: Option<(f64, f64)>,
+    net: Option<&str>,
+    pin1: bool,
+  ) -> Pad {
+    let pos = xy(pos);
+    Pad::with_shape(
+      layers,
+      pos,
+      angle,
+      PadShape::Custom(svgpath.into()),
+      drill_size,
+      net,
+      pin1,
+    )
+  }
+
+  /// Construct object with a built-in shape
+  ///
+  /// Built-in shapes are rendered more efficiently than custom SVG paths.
+  ///
+  /// # Arguments
+  ///
+  /// * `layers` - Layers on which the pad exists.
+  /// * `pos` - Position (x, y) \[mm\].
+  /// * `angle` - Rotation angle [°].
+  /// * `shape` - Pad shape.
+  /// * `drill_size` - Drill size (w, h) \[mm\] (only for THT pads).
+  /// * `net` - Net name (optional).
+  /// * `pin1` - Whether this is considered as the pin-1 or not.
+  ///
+  /// # Returns
+  ///
+  /// Returns the new object.
+  pub fn with_shape(
+    layers: impl IntoIterator<Item = Layer>,
+    pos: impl Into<Point>,
+    angle: impl Into<Angle>,
+    shape: PadShape,
+    drill_size: Option<(f64, f64)>,
+    net: Option<&str>,
+    pin1: bool,
+  ) -> Pad {
+    let pos = xy(pos);
+    Pad {
+      layers: layers.into_iter().collect(),
+      pos,
+      angle: angle.into().degrees(),
+      shape,
+      drill_size,
+      drill_offset: (0.0, 0.0),
+      net: net.map(NetId::new),
+      pin1,
+      pin_number: None,
+      pin_name: None,
+    }
+  }
+
+  /// Set drill offset
+  ///
+  /// By default, the drill is located in the center of the pad. For pads
+  /// with an offset drill, the drill position relative to the pad position
+  /// can be specified with this method.
+  ///
+  /// # Arguments
+  ///
+  /// * `offset` - Drill offset (x, y) \[mm\], in the pad coordinate system
+  ///   (i.e. not rotated by the pad angle).
+  pub fn set_drill_offset(&mut self, offset: impl Into<Point>) {
+    self.drill_offset = xy(offset);
+  }
+
+  /// Set pin number and name
+  ///
+  /// If set, hovering the pad in the viewer shows a tooltip like
+  /// "R5 pin 2 (VCC) / GND".
+  ///
+  /// # Arguments
+  ///
+  /// * `number` - Pin number, e.g. "2" or "A1".
+  /// * `name` - Pin name, e.g. "VCC".
+  pub fn set_pin(&mut self, number: Option<&str>, name: Option<&str>) {
+    self.pin_number = number.map(|s| s.to_owned());
+    self.pin_name = name.map(|s| s.to_owned());
+  }
+
+  /// Set net from a registered net
+  ///
+  /// Replaces the net passed to the constructor without copying the net
+  /// name.
+  ///
+  /// # Arguments
+  ///
+  /// * `net` - Net as returned by [InteractiveHtmlBom::add_net].
+  pub fn set_net_id(&mut self, net: Option<NetId>) {
+    self.net = net;
+  }
+
+  /// Get layers
+  ///
+  /// # Returns
+  ///
+  /// Returns the layers on which the pad exists.
+  pub fn layers(&self) -> &[Layer] {
+    &self.layers
+  }
+
+  /// Get position
+  ///
+  /// # Returns
+  ///
+  /// Returns the position \[mm\].
+  pub fn pos(&self) -> Point {
+    self.pos.into()
+  }
+
+  /// Get rotation
+  ///
+  /// # Returns
+  ///
+  /// Returns the rotation angle [°].
+  pub fn angle(&self) -> f64 {
+    self.angle
+  }
+
+  /// Get shape
+  ///
+  /// # Returns
+  ///
+  /// Returns the pad shape.
+  pub fn shape(&self) -> &PadShape {
+    &self.shape
+  }
+
+  /// Get drill size
+  ///
+  /// # Returns
+  ///
+  /// Returns the drill size (w, h) \[mm\] for THT pads.
+  pub fn drill_size(&self) -> Option<(f64, f64)> {
+    self.drill_size
+  }
+
+  /// Get net
+  ///
+  /// # Returns
+  ///
+  /// Returns the net name, if any.
+  pub fn net(&self) -> Option<&str> {
+    self.net.as_ref().map(NetId::name)
+  }
+
+  /// Get pin-1 state
+  ///
+  /// # Returns
+  ///
+  /// Returns whether this is considered as the pin-1 or not.
+  pub fn pin1(&self) -> bool {
+    self.pin1
+  }
+
+  /// Get pin number
+  ///
+  /// # Returns
+  ///
+  /// Returns the pin number, if any.
+  pub fn pin_number(&self) -> Option<&str> {
+    self.pin_number.as_deref()
+  }
+
+  /// Get pin name
+  ///
+  /// # Returns
+  ///
+  /// Returns the pin name, if any.
+  pub fn pin_name(&self) -> Option<&str> {
+    self.pin_name.as_deref()
+  }
+}
+
+/// Pad builder
+///
+/// Alternative to [Pad::new] and [Pad::with_shape] to avoid passing all the
+/// optional arguments.
+///
+/// # Examples
+///
+/// ```
+/// use interactive_html_bom::{PadBuilder, PadShape};
+///
+/// let pad = PadBuilder::new(PadShape::Circle(1.6))
+///   .tht((0.8, 0.8))
+///   .at((2.54, 0.0))
+///   .net("GND")
+///   .pin1()
+///   .build();
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct PadBuilder {
+  pad: Pad,
+}
+
+impl PadBuilder {
+  /// Construct builder
+  ///
+  /// Defaults to an SMD pad on [Layer::Front] at (0, 0), without rotation,
+  /// net and pin.
+  ///
+  /// # Arguments
+  ///
+  /// * `shape` - Pad shape.
+  ///
+  /// # Returns
+  ///
+  /// Returns the new object.
+  pub fn new(shape: PadShape) -> PadBuilder {
+    PadBuilder {
+      pad: Pad::with_shape(
+        [Layer::Front],
+        (0.0, 0.0),
+        0.0,
+        shape,
+        None,
+        None,
+        false,
+      ),
+    }
+  }
+
+  /// Make it an SMD pad
+  ///
+  /// Removes the drill, if any.
+  ///
+  /// # Arguments
+  ///
+  /// * `layer` - Layer on which the pad exists.
+  pub fn smd(&mut self, layer: Layer) -> &mut Self {
+    self.pad.layers = vec![layer];
+    self.pad.drill_size = None;
+    self
+  }
+
+  /// Make it a THT pad on [Layer::Front] and [Layer::Back]
+  ///
+  /// # Arguments
+  ///
+  /// * `drill_size` - Drill size (w, h) \[mm\].
+  pub fn tht(&mut self, drill_size: (f64, f64)) -> &mut Self {
+    self.pad.layers = vec![Layer::Front, Layer::Back];
+    self.pad.drill_size = Some(drill_size);
+    self
+  }
+
+  /// Set layers
+  ///
+  /// # Arguments
+  ///
+  /// * `layers` - Layers on which the pad exists.
+  pub fn layers(
+    &mut self,
+    layers: impl IntoIterator<Item = Layer>,
+  ) -> &mut Self {
+    self.pad.layers = layers.into_iter().collect();
+    self
+  }
+
+  /// Set position
+  ///
+  /// # Arguments
+  ///
+  /// * `pos` - Position (x, y) \[mm\].
+  pub fn at(&mut self, pos: impl Into<Point>) -> &mut Self {
+    self.pad.pos = xy(pos);
+    self
+  }
+
+  /// Set rotation
+  ///
+  /// # Arguments
+  ///
+  /// * `angle` - Rotation angle [°].
+  pub fn angle(&mut self, angle: impl Into<Angle>) -> &mut Self {
+    self.pad.angle = angle.into().degrees();
+    self
+  }
+
+  /// Set drill offset
+  ///
+  /// See [Pad::set_drill_offset] for details.
+  ///
+  /// # Arguments
+  ///
+  /// * `offset` - Drill offset (x, y) \[mm\].
+  pub fn drill_offset(&mut self, offset: impl Into<Point>) -> &mut Self {
+    self.pad.set_drill_offset(offset);
+    self
+  }
+
+  /// Set net
+  ///
+  /// # Arguments
+  ///
+  /// * `net` - Net name.
+  pub fn net(&mut self, net: &str) -> &mut Self {
+    self.pad.net = Some(NetId::new(net));
+    self
+  }
+
+  /// Set net from a registered net
+  ///
+  /// # Arguments
+  ///
+  /// * `net` - Net as returned by [InteractiveHtmlBom::add_net].
+  pub fn net_id(&mut self, net: NetId) -> &mut Self {
+    self.pad.net = Some(net);
+    self
+  }
+
+  /// Mark the pad as pin-1
+  pub fn pin1(&mut self) -> &mut Self {
+    self.pad.pin1 = true;
+    self
+  }
+
+  /// Set pin number and name
+  ///
+  /// See [Pad::set_pin] for details.
+  ///
+  /// # Arguments
+  ///
+  /// * `number` - Pin number, e.g. "2" or "A1".
+  /// * `name` - Pin name, e.g. "VCC".
+  pub fn pin(&mut self, number: Option<&str>, name: Option<&str>) -> &mut Self {
+    self.pad.set_pin(number, name);
+    self
+  }
+
+  /// Get the built pad
+  ///
+  /// # Returns
+  ///
+  /// Returns the new [Pad].
+  pub fn build(&self) -> Pad {
+    self.pad.clone()
+  }
+}
+
+impl ToJson for Pad {
+  fn to_json(&self) -> JsonValue {
+    let mut obj = object! {
+      layers: self.layers.to_json(),
+      pos: self.pos.to_json(),
+      angle: self.angle,
+    };
+    if self.drill_offset != (0.0, 0.0) {
+      // The viewer draws the drill at the pad position and offsets the
+      // copper shape instead, thus move the pad to the drill position.
+      let (dx, dy) = self.drill_offset;
+      let (x, y) = rotate(self.drill_offset, self.angle);
+      obj["pos"] = (self.pos.0 + x, self.pos.1 + y).to_json();
+      obj["offset"] = (-dx, -dy).to_json();
+    }
+    self.shape.write_json(&mut obj);
+    if let Some(drill) = &self.drill_size {
+      obj["type"] = "th".into();
+      obj["drillsize"] = array![drill.0, drill.1];
+      obj["drillshape"] = if drill.0 != drill.1 {
+        "oblong".into()
+      } else {
+        "circle".into()
+      };
+    } else {
+      obj["type"] = "smd".into();
+    }
+    if let Some(net) = &self.net {
+      obj["net"] = net.to_json();
+    }
+    if self.pin1 {
+      obj["pin1"] = 1.into();
+    }
+    if let Some(number) = &self.pin_number {
+      obj["pinnumber"] = number.as_str().into();
+    }
+    if let Some(name) = &self.pin_name {
+      obj["pinname"] = name.as_str().into();
+    }
+    obj
+  }
+}
+
+/// Footprint structure
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub struct Footprint {
+  layer: Layer,
+  pos: (f64, f64),
+  angle: f64,
+  bottom_left: (f64, f64),
+  top_right: (f64, f64),
+  fields: Vec<String>,
+  pads: Vec<Pad>,
+  drawings: Vec<Drawing>,
+  mount: bool,
+  checked: Vec<String>,
+  tooltip_fields: Vec<(String, String)>,
+}
+
+impl Footprint {
+  /// Construct object
+  ///
+  /// # Arguments
+  ///
+  /// * `layer` - Placement layer.
+  /// * `pos` - Position (x, y) \[mm\].
+  /// * `angle` - Rotation angle [°].
+  /// * `bottom_left` - Bottom left corner of bounding box (x, y) \[mm\].
+  /// * `top_right` - Top right corner of bounding box (x, y) \[mm\].
+  /// * `fields` - Custom fields, corresponding to [InteractiveHtmlBom::fields].
+  /// * `pads` - Footprint pads.
+  /// * `mount` - Whether the footprint is mounted or not.
+  ///
+  /// # Returns
+  ///
+  /// Returns the new object.
+  #[allow(clippy::too_many_arguments)]
+  pub fn new(
+    layer: Layer,
+    pos: impl Into<Point>,
+    angle: impl Into<Angle>,
+    bottom_left: impl Into<Point>,
+    top_right: impl Into<Point>,
+    fields: impl IntoIterator<Item = String>,
+    pads: impl IntoIterator<Item = Pad>,
+    mount: bool,
+  ) -> Footprint {
+    let pos = xy(pos);
+    let bottom_left = xy(bottom_left);
+    let top_right = xy(top_right);
+    Footprint {
+      layer,
+      pos,
+      angle: angle.into().degrees(),
+      bottom_left,
+      top_right,
+      fields: fields.into_iter().collect(),
+      pads: pads.into_iter().collect(),
+      drawings: Vec::new(),
+      mount,
+      checked: Vec::new(),
+      tooltip_fields: Vec::new(),
+    }
+  }
+
+  /// Add drawing
+  ///
+  /// Footprint drawings are highlighted together with the footprint. Their
+  /// coordinates are absolute, i.e. not relative to the footprint position.
+  /// Drawings on [DrawingLayer::Edge] are shown on both board sides. Drawings
+  /// on copper, solder paste, solder mask and auxiliary layers are not
+  /// highlighted but shown together with the board drawings of these layers.
+  ///
+  /// # Arguments
+  ///
+  /// * `drawing` - The drawing to add.
+  pub fn add_drawing(&mut self, drawing: Drawing) {
+    self.drawings.push(drawing);
+  }
+
+  /// Automatically mark pin 1
+  ///
+  /// Does nothing if any pad is already marked as pin 1. Otherwise marks the
+  /// first pad with pin number "1" or "A1" (see [Pad::set_pin]), or the first
+  /// pad if there is no such pin number.
+  pub fn detect_pin1(&mut self) {
+    if self.pads.iter().any(|p| p.pin1) {
+      return;
+    }
+    let is_pin1 = |p: &Pad| {
+      p.pin_number
+        .as_deref()
+        .is_some_and(|n| n == "1" || n.eq_ignore_ascii_case("A1"))
+    };
+    let index = self.pads.iter().position(is_pin1).unwrap_or(0);
+    if let Some(pad) = self.pads.get_mut(index) {
+      pad.pin1 = true;
+    }
+  }
+
+  /// Set initial checkbox state
+  ///
+  /// By default, all checkboxes are unchecked. Once the user modified a
+  /// checkbox column in the viewer, its stored state takes precedence.
+  ///
+  /// # Arguments
+  ///
+  /// * `checkbox` - Checkbox column name, see
+  ///   [InteractiveHtmlBom::checkboxes].
+  /// * `checked` - Whether the checkbox is checked or not.
+  pub fn set_checked(&mut self, checkbox: &str, checked: bool) {
+    self.checked.retain(|x| x != checkbox);
+    if checked {
+      self.checked.push(checkbox.to_owned());
+    }
+  }
+
+  /// Add tooltip field
+  ///
+  /// In contrast to the fields passed to [Footprint::new], tooltip fields
+  /// are not listed as BOM columns but shown when hovering the BOM row,
+  /// e.g. for long descriptions or internal notes.
+  ///
+  /// # Arguments
+  ///
+  /// * `name` - Field name.
+  /// * `value` - Field value.
+  pub fn add_tooltip_field(&mut self, name: &str, value: &str) {
+    self
+      .tooltip_fields
+      .push((name.to_owned(), value.to_owned()));
+  }
+
+  /// Get bounding box in board coordinates, i.e. rotated and translated
+  fn extent(&self) -> ((f64, f64), (f64, f64)) {
+    let (x0, y0) = self.bottom_left;
+    let (x1, y1) = self.top_right;
+    let corners = [(x0, y0), (x1, y0), (x1, y1), (x0, y1)].map(|v| {
+      let (x, y) = rotate(v, self.angle);
+      (self.pos.0 + x, self.pos.1 + y)
+    });
+    corners
+      .into_iter()
+      .fold((corners[0], corners[0]), |(min, max), p| {
+        (
+          (min.0.min(p.0), min.1.min(p.1)),
+          (max.0.max(p.0), max.1.max(p.1)),
+        )
+      })
+  }
+
+  /// Get placement layer
+  ///
+  /// # Returns
+  ///
+  /// Returns the placement layer.
+  pub fn layer(&self) -> &Layer {
+    &self.layer
+  }
+
+  /// Get position
+  ///
+  /// # Returns
+  ///
+  /// Returns the position \[mm\].
+  pub fn pos(&self) -> Point {
+    self.pos.into()
+  }
+
+  /// Get rotation
+  ///
+  /// # Returns
+  ///
+  /// Returns the normalized rotation angle [°], see [Angle].
+  pub fn angle(&self) -> f64 {
+    self.angle
+  }
+
+  /// Get custom fields
+  ///
+  /// # Returns
+  ///
+  /// Returns the field values, corresponding to
+  /// [InteractiveHtmlBom::fields].
+  pub fn fields(&self) -> &[String] {
+    &self.fields
+  }
+
+  /// Get pads
+  ///
+  /// # Returns
+  ///
+  /// Returns the footprint pads.
+  pub fn pads(&self) -> &[Pad] {
+    &self.pads
+  }
+
+  /// Get drawings
+  ///
+  /// # Returns
+  ///
+  /// Returns the footprint drawings.
+  pub fn drawings(&self) -> &[Drawing] {
+    &self.drawings
+  }
+
+  /// Get mount state
+  ///
+  /// # Returns
+  ///
+  /// Returns whether the footprint is mounted or not.
+  pub fn mount(&self) -> bool {
+    self.mount
+  }
+}
+
+impl ToJson for Footprint {
+  fn to_json(&self) -> JsonValue {
+    let mut drawings = array![];
+    for drawing in &self.drawings {
+      let layers = match drawing.layer {
+        DrawingLayer::Edge => vec![Layer::Front, Layer::Back],
+        DrawingLayer::SilkscreenFront | DrawingLayer::FabricationFront => {
+          vec![Layer::Front]
+        }
+        DrawingLayer::SilkscreenBack | DrawingLayer::FabricationBack => {
+          vec![Layer::Back]
+        }
+        DrawingLayer::PasteFront
+        | DrawingLayer::PasteBack
+        | DrawingLayer::MaskFront
+        | DrawingLayer::MaskBack
+        | DrawingLayer::CopperFront
+        | DrawingLayer::CopperBack
+        | DrawingLayer::AuxiliaryFront(_)
+        | DrawingLayer::AuxiliaryBack(_) => vec![],
+      };
+      for layer in layers {
+        drawings
+          .push(object! {
+            layer: layer.to_json(),
+            drawing: drawing.to_json(),
+          })
+          .unwrap();
+      }
+    }
+    object! {
+      bbox: object!{
+        pos: self.pos.to_json(),
+        angle: self.angle,
+        relpos: self.bottom_left.to_json(),
+        size: array![
+          self.top_right.0 - self.bottom_left.0,
+          self.top_right.1 - self.bottom_left.1],
+      },
+      drawings: drawings,
+      layer: self.layer.to_json(),
+      pads: self.pads.to_json(),
+    }
+  }
+}
+
+/// Footprint builder
+///
+/// Alternative to [Footprint::new] to assemble footprints incrementally.
+///
+/// # Examples
+///
+/// ```
+/// use interactive_html_bom::{FootprintBuilder, Layer, PadBuilder, PadShape};
+///
+/// let footprint = FootprintBuilder::new()
+///   .layer(Layer::Back)
+///   .at((50.0, 40.0))
+///   .angle(90.0)
+///   .bbox((-2.0, -1.0), (2.0, 1.0))
+///   .field("100R")
+///   .field("0603")
+///   .pad(
+///     PadBuilder::new(PadShape::Rect((1.0, 1.0)))
+///       .smd(Layer::Back)
+///       .at((-1.0, 0.0))
+///       .pin1()
+///       .build(),
+///   )
+///   .mount(true)
+///   .build();
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct FootprintBuilder {
+  layer: Layer,
+  pos: (f64, f64),
+  angle: f64,
+  bottom_left: (f64, f64),
+  top_right: (f64, f64),
+  fields: Vec<String>,
+  pads: Vec<Pad>,
+  mount: bool,
+}
+
+impl FootprintBuilder {
+  /// Construct builder
+  ///
+  /// Defaults to a mounted footprint on [Layer::Front] at (0, 0), without
+  /// rotation, fields and pads.
+  ///
+  /// # Returns
+  ///
+  /// Returns the new object.
+  pub fn new() -> FootprintBuilder {
+    FootprintBuilder {
+      layer: Layer::Front,
+      pos: (0.0, 0.0),
+      angle: 0.0,
+      bottom_left: (0.0, 0.0),
+      top_right: (0.0, 0.0),
+      fields: Vec::new(),
+      pads: Vec::new(),
+      mount: true,
+    }
+  }
+
+  /// Set placement layer
+  ///
+  /// # Arguments
+  ///
+  /// * `layer` - Placement layer.
+  pub fn layer(&mut self, layer: Layer) -> &mut Self {
+    self.layer = layer;
+    self
+  }
+
+  /// Set position
+  ///
+  /// # Arguments
+  ///
+  /// * `pos` - Position (x, y) \[mm\].
+  pub fn at(&mut self, pos: impl Into<Point>) -> &mut Self {
+    self.pos = xy(pos);
+    self
+  }
+
+  /// Set rotation
+  ///
+  /// # Arguments
+  ///
+  /// * `angle` - Rotation angle [°].
+  pub fn angle(&mut self, angle: impl Into<Angle>) -> &mut Self {
+    self.angle = angle.into().degrees();
+    self
+  }
+
+  /// Set bounding box
+  ///
+  /// # Arguments
+  ///
+  /// * `bottom_left` - Bottom left corner of bounding box (x, y) \[mm\].
+  /// * `top_right` - Top right corner of bounding box (x, y) \[mm\].
+  pub fn bbox(
+    &mut self,
+    bottom_left: impl Into<Point>,
+    top_right: impl Into<Point>,
+  ) -> &mut Self {
+    self.bottom_left = xy(bottom_left);
+    self.top_right = xy(top_right);
+    self
+  }
+
+  /// Append custom field
+  ///
+  /// Fields must be added in the order of [InteractiveHtmlBom::fields].
+  ///
+  /// # Arguments
+  ///
+  /// * `value` - Field value.
+  pub fn field(&mut self, value: impl Into<String>) -> &mut Self {
+    self.fields.push(value.into());
+    self
+  }
+
+  /// Add pad
+  ///
+  /// # Arguments
+  ///
+  /// * `pad` - The pad to add.
+  pub fn pad(&mut self, pad: Pad) -> &mut Self {
+    self.pads.push(pad);
+    self
+  }
+
+  /// Set whether the footprint is mounted or not
+  ///
+  /// # Arguments
+  ///
+  /// * `mount` - Whether the footprint is mounted or not.
+  pub fn mount(&mut self, mount: bool) -> &mut Self {
+    self.mount = mount;
+    self
+  }
+
+  /// Get the built footprint
+  ///
+  /// # Returns
+  ///
+  /// Returns the new [Footprint].
+  pub fn build(&self) -> Footprint {
+    Footprint::new(
+      self.layer.clone(),
+      self.pos,
+      self.angle,
+      self.bottom_left,
+      self.top_right,
+      self.fields.clone(),
+      self.pads.clone(),
+      self.mount,
+    )
+  }
+}
+
+impl Default for FootprintBuilder {
+  fn default() -> Self {
+    FootprintBuilder::new()
+  }
+}
+
+/// Footprint ID
+///
+/// Returned by [InteractiveHtmlBom::add_footprint] to reference footprints
+/// in BOM rows. For legacy code, it can be converted explicitly from and to
+/// the index of the footprint in [InteractiveHtmlBom::footprints], e.g. with
+/// `FootprintId::from(index)`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FootprintId(usize);
+
+impl From<usize> for FootprintId {
+  fn from(index: usize) -> Self {
+    FootprintId(index)
+  }
+}
+
+impl From<FootprintId> for usize {
+  fn from(id: FootprintId) -> Self {
+    id.0
+  }
+}
+
+impl std::fmt::Display for FootprintId {
+  fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+    write!(f, "{}", self.0)
+  }
+}
+
+/// Reference-FootprintID map
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub struct RefMap {
+  reference: String,
+  footprint_id: usize,
+}
+
+impl RefMap {
+  /// Construct object
+  ///
+  /// # Arguments
+  ///
+  /// * `reference` - Component reference (e.g. "R1").
+  /// * `footprint_id` - ID of footprint as returned by
+  ///   [InteractiveHtmlBom::add_footprint].
+  ///
+  /// # Returns
+  ///
+  /// Returns the new object.
+  pub fn new(reference: &str, footprint_id: FootprintId) -> RefMap {
+    RefMap {
+      reference: reference.to_owned(),
+      footprint_id: footprint_id.0,
+    }
+  }
+
+  /// Get reference
+  ///
+  /// # Returns
+  ///
+  /// Returns the component reference.
+  pub fn reference(&self) -> &str {
+    &self.reference
+  }
+
+  /// Get footprint ID
+  ///
+  /// # Returns
+  ///
+  /// Returns the ID of the referenced footprint.
+  pub fn footprint_id(&self) -> FootprintId {
+    FootprintId(self.footprint_id)
+  }
+}
+
+/// Footprint removed by [InteractiveHtmlBom::remove_footprint]
+///
+/// Since footprint IDs are indices, the IDs of all subsequent footprints are
+/// decremented by one. Use [RemovedFootprint::remap] to update stored IDs.
+#[derive(Clone, Debug, PartialEq)]
+#[non_exhaustive]
+pub struct RemovedFootprint {
+  /// The removed footprint.
+  pub footprint: Footprint,
+
+  /// The references to the footprint which have been removed from the BOM
+  /// rows, without duplicates.
+  pub references: Vec<RefMap>,
+
+  id: usize,
+}
+
+impl RemovedFootprint {
+  /// Get the ID of a footprint after the removal
+  ///
+  /// # Arguments
+  ///
+  /// * `id` - ID of the footprint before the removal.
+  ///
+  /// # Returns
+  ///
+  /// Returns the new ID, or [None] if `id` is the removed footprint.
+  pub fn remap(&self, id: FootprintId) -> Option<FootprintId> {
+    match id.0.cmp(&self.id) {
+      std::cmp::Ordering::Less => Some(id),
+      std::cmp::Ordering::Equal => None,
+      std::cmp::Ordering::Greater => Some(FootprintId(id.0 - 1)),
+    }
+  }
+}
+
+impl ToJson for RefMap {
+  fn to_json(&self) -> JsonValue {
+    array! {
+      self.reference.as_str(),
+      self.footprint_id,
+    }
+  }
+}
+
+/// Viewer settings
+///
+/// Initial state of the viewer. Most settings can be changed by the user
+/// in the viewer, which are then stored in the browser and take precedence.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub struct RenderSettings {
+  /// Initial view mode
+  pub view_mode: ViewMode,
+
+  /// Initial layer view
+  ///
+  /// If [None], it is auto-detected from which BOM rows are non-empty.
+  pub layer_view: Option<LayerView>,
+
+  /// Initial BOM sort column
+  ///
+  /// Either "References", "Value", "Footprint", "Quantity" or one of
+  /// [InteractiveHtmlBom::fields]. If [None], the BOM is not sorted.
+  pub sort_column: Option<String>,
+
+  /// Initial BOM sort order
+  pub sort_order: SortOrder,
+
+  /// Initial BOM filter
+  ///
+  /// Only rows containing this text (case-insensitive) in any column are
+  /// shown. Empty to show all rows.
+  pub filter: String,
+
+  /// Initial BOM reference lookup
+  ///
+  /// Only rows containing this exact reference (case-insensitive) are shown.
+  /// Empty to show all rows.
+  pub ref_lookup: String,
+
+  /// Hightlight pin-1 mode
+  pub highlight_pin1: HighlightPin1Mode,
+
+  /// Dark mode on/off
+  pub dark_mode: bool,
+
+  /// Board rendering colors
+  pub theme: Theme,
+
+  /// Board drawings rotation \[°\]
+  pub board_rotation: f64,
+
+  /// Whether to offset the back side rotation or not
+  pub offset_back_rotation: bool,
+
+  /// Whether to interpret KiCad text markup or not
+  ///
+  /// If enabled, overbar markup like `~RESET~` and tabs in text drawings are
+  /// rendered like KiCad does. Field values in the BOM table are not
+  /// affected.
+  pub kicad_text_formatting: bool,
+
+  /// Whether to redraw the board continuously while dragging or not
+  ///
+  /// Disabling it makes dragging smoother on large boards.
+  pub redraw_on_drag: bool,
+
+  /// Silkscreen visibility
+  pub show_silkscreen: bool,
+
+  /// Fabrication layer visibility
+  pub show_fabrication: bool,
+
+  /// Solder paste layer visibility
+  pub show_paste: bool,
+
+  /// Solder mask layer visibility
+  pub show_mask: bool,
+
+  /// Pads visibility
+  pub show_pads: bool,
+
+  /// Names of columns hidden by default
+  ///
+  /// Either one of [InteractiveHtmlBom::fields], "References", "Quantity"
+  /// or "checkboxes". Hidden columns can be shown in the viewer.
+  pub hidden_columns: Vec<String>,
+
+  /// Additional config options passed to the viewer
+  ///
+  /// Pairs of option name and JSON-encoded value (e.g. `"true"` or
+  /// `"\"text\""`), overriding the options generated from other settings.
+  ///
+  /// <div class="warning">
+  /// This should be used carefully as we neither guarantee forward- nor
+  /// backward-compatibility.
+  /// </div>
+  pub extra_config: Vec<(String, String)>,
+}
+
+impl Default for RenderSettings {
+  fn default() -> Self {
+    RenderSettings {
+      view_mode: ViewMode::default(),
+      layer_view: None,
+      sort_column: None,
+      sort_order: SortOrder::default(),
+      filter: String::new(),
+      ref_lookup: String::new(),
+      highlight_pin1: HighlightPin1Mode::default(),
+      dark_mode: false,
+      theme: Theme::default(),
+      board_rotation: 0.0,
+      offset_back_rotation: false,
+      kicad_text_formatting: false,
+      redraw_on_drag: true,
+      show_silkscreen: true,
+      show_fabrication: true,
+      show_paste: false,
+      show_mask: false,
+      show_pads: true,
+      hidden_columns: Vec::new(),
+      extra_config: Vec::new(),
+    }
+  }
+}
+
+/// Format of an imported file, see [GenerateError::InvalidInput]
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[non_exhaustive]
+pub enum InputFormat {
+  /// KiCad netlist, see [InteractiveHtmlBom::merge_kicad_netlist]
+  KicadNetlist,
+  /// Gerber file, see [InteractiveHtmlBom::import_gerber]
+  Gerber,
+  /// Excellon drill file, see [InteractiveHtmlBom::import_excellon]
+  Excellon,
+  /// Pick and place file, see [InteractiveHtmlBom::import_pick_and_place]
+  PickAndPlace,
+  /// LibrePCB project, see [InteractiveHtmlBom::from_librepcb_project]
+  LibrePcbProject,
+  /// Eagle board, see [InteractiveHtmlBom::from_eagle_board]
+  EagleBoard,
+  /// Generic JSON, see [InteractiveHtmlBom::from_generic_json]
+  GenericJson,
+}
+
+impl std::fmt::Display for InputFormat {
+  fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+    match self {
+      InputFormat::KicadNetlist => write!(f, "KiCad netlist"),
+      InputFormat::Gerber => write!(f, "Gerber file"),
+      InputFormat::Excellon => write!(f, "Excellon file"),
+      InputFormat::PickAndPlace => write!(f, "pick and place file"),
+      InputFormat::LibrePcbProject => write!(f, "LibrePCB project"),
+      InputFormat::EagleBoard => write!(f, "Eagle board"),
+      InputFormat::GenericJson => write!(f, "generic JSON"),
+    }
+  }
+}
+
+/// Error returned when generating or building a BOM fails
+#[derive(Clone, Debug, PartialEq)]
+#[non_exhaustive]
+pub enum GenerateError {
+  /// A BOM row references a footprint which does not exist
+  InvalidFootprintId {
+    /// Index of the BOM row, or of the reference passed to
+    /// [InteractiveHtmlBom::group_bom] or
+    /// [InteractiveHtmlBom::merge_kicad_netlist].
+    row: usize,
+    /// The invalid footprint ID.
+    index: usize,
+  },
+  /// A footprint has not the same number of fields as
+  /// [InteractiveHtmlBom::fields]
+  FieldCountMismatch {
+    /// Footprint ID.
+    footprint: FootprintId,
+  },
+  /// A field name passed to [InteractiveHtmlBom::group_bom] does not exist
+  UnknownField {
+    /// The unknown field name.
+    name: String,
+  },
+  /// A drawing is on an auxiliary layer which does not exist
+  InvalidAuxiliaryLayer {
+    /// The invalid index into [InteractiveHtmlBom::aux_layers].
+    index: usize,
+  },
+  /// A text drawing is on a copper layer
+  CopperText,
+  /// A color of [RenderSettings::theme] is not a valid CSS color
+  InvalidColor {
+    /// The invalid color.
+    color: String,
+  },
+  /// A drawing on a copper layer has a malformed SVG path
+  ///
+  /// Copper drawings are converted to track segments, which is not possible
+  /// for paths which can't be parsed.
+  InvalidCopperPath,
+  /// A value of [RenderSettings::extra_config] is not valid JSON
+  InvalidExtraConfig {
+    /// Config key.
+    key: String,
+  },
+  /// [InteractiveHtmlBom::user_js] is set although user-defined content is
+  /// untrusted, see [InteractiveHtmlBom::sanitize_user_content]
+  UntrustedUserJs,
+  /// A warning occurred in [Strictness::Strict] mode
+  Diagnostic {
+    /// The warning.
+    warning: Warning,
+  },
+  /// A placeholder of [InteractiveHtmlBom::placeholders] is empty
+  EmptyPlaceholder,
+  /// A number is NaN or infinite
+  NonFiniteNumber {
+    /// Path of the number in the generated data, e.g. `tracks.F[0].width`
+    /// or `config.board_rotation`.
+    path: String,
+  },
+  /// An imported file is invalid
+  InvalidInput {
+    /// Format of the file.
+    format: InputFormat,
+    /// Description of the problem.
+    reason: String,
+  },
+  /// A generated document failed [InteractiveHtmlBom::verify_html]
+  InvalidOutput {
+    /// Description of the problem.
+    reason: String,
+  },
+  /// Reading the input or writing the output failed
+  Io {
+    /// Error kind.
+    kind: std::io::ErrorKind,
+    /// Error message.
+    message: String,
+  },
+}
+
+impl std::fmt::Display for GenerateError {
+  fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+    match self {
+      GenerateError::InvalidFootprintId { row, index } => {
+        write!(f, "Invalid footprint ID {} in row {}.", index, row)
+      }
+      GenerateError::FieldCountMismatch { footprint } => {
+        write!(
+          f,
+          "Inconsistent number of fields in footprint {}.",
+          footprint
+        )
+      }
+      GenerateError::UnknownField { name } => {
+        write!(f, "Unknown field \"{}\".", name)
+      }
+      GenerateError::InvalidAuxiliaryLayer { index } => {
+        write!(f, "Invalid auxiliary layer index {}.", index)
+      }
+      GenerateError::CopperText => {
+        write!(f, "Text drawings on copper layers not supported.")
+      }
+      GenerateError::InvalidColor { color } => {
+        write!(f, "Invalid color \"{}\".", color)
+      }
+      GenerateError::InvalidCopperPath => {
+        write!(f, "Invalid path of drawing on copper layer.")
+      }
+      GenerateError::InvalidExtraConfig { key } => {
+        write!(f, "Invalid value of extra config \"{}\".", key)
+      }
+      GenerateError::UntrustedUserJs => {
+        write!(f, "User-defined JavaScript not allowed if untrusted.")
+      }
+      GenerateError::Diagnostic { warning } => {
+        write!(f, "{}", warning)
+      }
+      GenerateError::EmptyPlaceholder => {
+        write!(f, "Empty template placeholder.")
+      }
+      GenerateError::NonFiniteNumber { path } => {
+        write!(f, "Invalid number (NaN or infinite) at \"{}\".", path)
+      }
+      GenerateError::InvalidInput { format, reason } => {
+        write!(f, "Invalid {}: {}.", format, reason)
+      }
+      GenerateError::InvalidOutput { reason } => {
+        write!(f, "Invalid generated HTML: {}.", reason)
+      }
+      GenerateError::Io { message, .. } => {
+        write!(f, "Failed to write output: {}", message)
+      }
+    }
+  }
+}
+
+impl std::error::Error for GenerateError {}
+
+/// Error returned by [InteractiveHtmlBom::try_add_footprint]
+///
+/// The footprint has not the same number of fields as
+/// [InteractiveHtmlBom::fields]. It was not added and is handed back.
+#[derive(Clone, Debug, PartialEq)]
+#[non_exhaustive]
+pub struct FieldCountError {
+  /// Expected number of fields.
+  pub expected: usize,
+
+  /// The rejected footprint.
+  pub footprint: Box<Footprint>,
+}
+
+impl std::fmt::Display for FieldCountError {
+  fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+    write!(
+      f,
+      "Footprint has {} fields {:?} instead of {}.",
+      self.footprint.fields.len(),
+      self.footprint.fields,
+      self.expected
+    )
+  }
+}
+
+impl std::error::Error for FieldCountError {}
+
+impl From<std::io::Error> for GenerateError {
+  fn from(err: std::io::Error) -> Self {
+    GenerateError::Io {
+      kind: err.kind(),
+      message: err.to_string(),
+    }
+  }
+}
+
+/// Handling of invalid or suspicious input when generating
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Strictness {
+  /// Fail on warnings too, see [InteractiveHtmlBom::warnings]
+  Strict,
+  /// Fail on invalid input, ignore warnings
+  #[default]
+  Normal,
+  /// Drop or repair invalid input, reported by [InteractiveHtmlBom::warnings]
+  ///
+  /// Invalid BOM references, drawings on invalid layers, copper drawings
+  /// with malformed paths and invalid extra config options are dropped, and
+  /// missing fields are added as empty strings. NaN and infinite numbers are
+  /// still rejected.
+  Lenient,
+}
+
+/// Encoding of the viewer data in the generated output
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum DataEncoding {
+  /// LZString-compressed and base64-encoded
+  #[default]
+  LzString,
+  /// Plain JSON, larger but human-readable
+  Json,
+  /// DEFLATE-compressed and base64-encoded
+  ///
+  /// Typically much smaller than [DataEncoding::LzString] for large boards,
+  /// but requires a browser supporting `TextDecoder`.
+  Deflate,
+  /// CBOR-encoded, DEFLATE-compressed and base64-encoded
+  ///
+  /// Like [DataEncoding::Deflate], but avoids parsing a huge JSON string in
+  /// the browser. Numbers are stored in binary form, which is smaller for
+  /// boards with many coordinates.
+  Cbor,
+}
+
+/// Warning about suspicious input, see [InteractiveHtmlBom::warnings]
+#[derive(Clone, Debug, PartialEq)]
+#[non_exhaustive]
+pub enum Warning {
+  /// The board bounding box has zero or negative area
+  EmptyBoundingBox,
+  /// A footprint lies entirely outside the board bounding box
+  FootprintOutsideBoard {
+    /// Footprint ID.
+    footprint: FootprintId,
+  },
+  /// A drawing lies entirely outside the board bounding box
+  DrawingOutsideBoard {
+    /// Index into [InteractiveHtmlBom::drawings].
+    index: usize,
+  },
+  /// A mounted footprint is not referenced by any BOM row
+  ///
+  /// Such footprints are drawn on the board, but missing in the BOM table.
+  UnreferencedFootprint {
+    /// Footprint ID.
+    footprint: FootprintId,
+  },
+  /// A footprint is referenced multiple times in the same BOM, or in both
+  /// [InteractiveHtmlBom::bom_front] and [InteractiveHtmlBom::bom_back]
+  MultipleBomRows {
+    /// Footprint ID.
+    footprint: FootprintId,
+  },
+  /// An SMD pad is not on the placement layer of its footprint
+  PadLayerMismatch {
+    /// Footprint ID.
+    footprint: FootprintId,
+    /// Index of the pad within the footprint.
+    pad: usize,
+  },
+  /// A drawing has an empty or malformed SVG path
+  EmptyDrawing {
+    /// Index into [InteractiveHtmlBom::drawings].
+    index: usize,
+  },
+  /// An edge drawing has an open end not connected to another edge drawing
+  OpenOutline {
+    /// Index into [InteractiveHtmlBom::drawings].
+    index: usize,
+  },
+  /// A zone has an empty or malformed SVG path
+  EmptyZone {
+    /// Index into [InteractiveHtmlBom::zones].
+    index: usize,
+  },
+  /// A board outline or one of its cutouts has an empty or malformed SVG
+  /// path
+  ///
+  /// Such contours are ignored for the board bounding box and the 3D view.
+  EmptyOutline {
+    /// Index into [InteractiveHtmlBom::outlines].
+    index: usize,
+  },
+  /// A track has zero or negative width
+  ZeroWidthTrack {
+    /// Index into [InteractiveHtmlBom::tracks].
+    index: usize,
+  },
+  /// A BOM reference to a footprint which does not exist has been dropped
+  ///
+  /// Only reported in [Strictness::Lenient] mode, otherwise
+  /// [GenerateError::InvalidFootprintId] is returned.
+  DroppedReference {
+    /// Index of the BOM row.
+    row: usize,
+    /// The invalid footprint ID.
+    index: usize,
+  },
+  /// A drawing on an invalid layer or with an invalid path has been dropped
+  ///
+  /// Only reported in [Strictness::Lenient] mode, otherwise
+  /// [GenerateError::InvalidAuxiliaryLayer], [GenerateError::CopperText] or
+  /// [GenerateError::InvalidCopperPath] is returned.
+  DroppedDrawing {
+    /// Footprint ID, or [None] for [InteractiveHtmlBom::drawings].
+    footprint: Option<FootprintId>,
+    /// Index into the drawings of the board or footprint.
+    index: usize,
+  },
+  /// Fields of a footprint have been added or dropped to match
+  /// [InteractiveHtmlBom::fields]
+  ///
+  /// Only reported in [Strictness::Lenient] mode, otherwise
+  /// [GenerateError::FieldCountMismatch] is returned.
+  ResizedFields {
+    /// Footprint ID.
+    footprint: FootprintId,
+  },
+  /// A value of [RenderSettings::extra_config] is not valid JSON and has
+  /// been dropped
+  ///
+  /// Only reported in [Strictness::Lenient] mode, otherwise
+  /// [GenerateError::InvalidExtraConfig] is returned.
+  DroppedExtraConfig {
+    /// Config key.
+    key: String,
+  },
+}
+
+impl std::fmt::Display for Warning {
+  fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+    match self {
+      Warning::EmptyBoundingBox => {
+        write!(f, "Board bounding box has zero or negative area.")
+      }
+      Warning::FootprintOutsideBoard { footprint } => {
+        write!(f, "Footprint {} is outside the board.", footprint)
+      }
+      Warning::DrawingOutsideBoard { index } => {
+        write!(f, "Drawing {} is outside the board.", index)
+      }
+      Warning::UnreferencedFootprint { footprint } => {
+        write!(f, "Footprint {} is not referenced by the BOM.", footprint)
+      }
+      Warning::MultipleBomRows { footprint } => {
+        write!(f, "Footprint {} is referenced multiple times.", footprint)
+      }
+      Warning::PadLayerMismatch { footprint, pad } => {
+        write!(
+          f,
+          "SMD pad {} of footprint {} is not on the footprint layer.",
+          pad, footprint
+        )
+      }
+      Warning::EmptyDrawing { index } => {
+        write!(f, "Drawing {} has an empty or invalid path.", index)
+      }
+      Warning::OpenOutline { index } => {
+        write!(f, "Edge drawing {} is not a closed contour.", index)
+      }
+      Warning::EmptyZone { index } => {
+        write!(f, "Zone {} has an empty or invalid path.", index)
+      }
+      Warning::EmptyOutline { index } => {
+        write!(f, "Board outline {} has an empty or invalid path.", index)
+      }
+      Warning::ZeroWidthTrack { index } => {
+        write!(f, "Track {} has zero width.", index)
+      }
+      Warning::DroppedReference { row, index } => {
+        write!(f, "Dropped invalid footprint ID {} in row {}.", index, row)
+      }
+      Warning::DroppedDrawing {
+        footprint: Some(footprint),
+        index,
+      } => {
+        write!(
+          f,
+          "Dropped invalid drawing {} of footprint {}.",
+          index, footprint
+        )
+      }
+      Warning::DroppedDrawing {
+        footprint: None,
+        index,
+      } => {
+        write!(f, "Dropped invalid drawing {}.", index)
+      }
+      Warning::ResizedFields { footprint } => {
+        write!(f, "Fixed number of fields in footprint {}.", footprint)
+      }
+      Warning::DroppedExtraConfig { key } => {
+        write!(f, "Dropped invalid value of extra config \"{}\".", key)
+      }
+    }
+  }
+}
+
+/// Interactive HTML BOM structure
+///
+/// The top-level structure to build & generate a HTML BOM.
+///
+/// <div class="warning">
+/// Please note that this struct is not completely fool-proof as it does not
+/// validate lots of the added data. So make sure you add only valid BOM data.
+/// Only the most important things are validated to avoid generating completely
+/// broken HTML pages: Footprint IDs in BOM rows, and number of fields in
+/// footprints.
+/// </div>
+///
+/// # Examples
+///
+/// ```
+/// use interactive_html_bom::*;
+///
+/// let mut ibom = InteractiveHtmlBom::new(
+///   "My Project",   // Title
+///   "My Company",   // Company
+///   "Rev. 1",       // Revision
+///   "1970-01-01",   // Date
+///   (0.0, 0.0),     // Bottom left
+///   (100.0, 80.0),  // Top right
+/// );
+///
+/// // Set configuration.
+/// ibom.fields = vec!["Value".into(), "Footprint".into()];
+/// ibom.settings.dark_mode = true;
+///
+/// // Draw PCB.
+/// ibom.drawings.push(Drawing::polygon(
+///   DrawingLayer::Edge,               // Layer
+///   "M 0 0 H 100 V 80 H -100 V -80",  // SVG path
+///   0.1,                              // Line width
+///   false,                            // Filled
+/// ));
+/// ibom.drawings.push(Drawing::reference_text(
+///   DrawingLayer::SilkscreenFront,
+///   "M 10 10 H 80 V 60 H -80 V -60",
+///   0.1,
+/// ));
+///
+/// // Add footprints.
+/// let id = ibom.add_footprint(
+///   Footprint::new(
+///     Layer::Front,                       // Layer
+///     (50.0, 40.0),                       // Position
+///     45.0,                               // Rotation
+///     (-2.0, -1.0),                       // Bottom left
+///     (2.0, 1.0),                         // Top right
+///     ["100R".into(), "0603".into()],     // Fields
+///     [Pad::new(
+///         [Layer::Front],                 // Pad layers
+///         (-2.0, 0.0),                    // Pad position
+///         0.0,                            // Pad rotation
+///         "M -1 -1 H 2 V 2 H -2 V -2",    // Pad shape (SVG)
+///         None,                           // Pad drill
+///         None,                           // Pad net
+///         true,                           // Pin 1
+///       ),
+///       // [...]
+///     ],
+///     true,                               // Mount or not
+///   ),
+/// );
+///
+/// // Add BOM rows (designators and their footprint IDs).
+/// ibom.add_single(LayerView::Front, "R1", id);
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub struct InteractiveHtmlBom {
+  // Metadata
+  title: String,
+  company: String,
+  revision: String,
+  date: String,
+  bottom_left: (f64, f64),
+  top_right: (f64, f64),
+
+  // Registered nets, and the same nets by name for fast lookup
+  nets: Vec<NetId>,
+  #[cfg_attr(feature = "serde", serde(skip))]
+  net_index: NetIndex,
+
+  /// Viewer settings
+  pub settings: RenderSettings,
+
+  /// Auxiliary drawing layers
+  pub aux_layers: Vec<AuxiliaryLayer>,
+
+  /// Whether to add a drill table below the viewer or not
+  ///
+  /// The table lists the number of drills grouped by size and plating,
+  /// computed from vias, pads and holes.
+  pub drill_table: bool,
+
+  /// Whether to add a simple 3D view or not
+  ///
+  /// Adds a toolbar button showing the board outline extruded to 1.6 mm,
+  /// with the footprint bounding boxes as 1 mm high blocks on top of it.
+  /// Components highlighted in the BOM are highlighted in the 3D view too.
+  /// Intended for a quick overview, real 3D models (e.g. glTF) are not
+  /// supported.
+  pub view_3d: bool,
+
+  /// Checkbox column names
+  pub checkboxes: Vec<String>,
+
+  /// Custom field names, listed as columns
+  pub fields: Vec<String>,
+
+  /// Names of the fields the BOM rows are grouped by
+  ///
+  /// Set by [InteractiveHtmlBom::group_bom] and passed to the viewer config
+  /// as `group_fields`, e.g. for custom scripts.
+  pub group_fields: Vec<String>,
+
+  /// Browser tab title
+  ///
+  /// If [None], the viewer uses the title of the title block.
+  pub page_title: Option<String>,
+
+  /// Favicon
+  pub favicon: Option<Image>,
+
+  /// Logo shown next to the title block
+  pub logo: Option<Image>,
+
+  /// Additional images like photos, renders or assembly drawings
+  ///
+  /// Pairs of title and image. Each image gets a toolbar button showing it
+  /// in a floating panel, next to the interactive view.
+  pub images: Vec<(String, Image)>,
+
+  /// Schematic sheets
+  ///
+  /// Each sheet gets a toolbar button showing it in a floating panel. While
+  /// the panel is open, highlighting a component (e.g. by clicking a BOM
+  /// row) jumps to the first sheet containing it and marks its location.
+  /// References not contained in the BOM are ignored.
+  pub schematic_sheets: Vec<SchematicSheet>,
+
+  /// Whether to treat user-defined content as untrusted or not
+  ///
+  /// If enabled, [InteractiveHtmlBom::user_header] and
+  /// [InteractiveHtmlBom::user_footer] are HTML-escaped, i.e. displayed as
+  /// plain text, and generating fails if [InteractiveHtmlBom::user_js] is
+  /// not empty. Intended for services accepting these from end users.
+  pub sanitize_user_content: bool,
+
+  /// Handling of invalid or suspicious input
+  pub strictness: Strictness,
+
+  /// Number of decimal places of numbers in the generated data
+  ///
+  /// Defaults to 6, i.e. 1 nm. Lower values reduce the file size.
+  pub decimals: u8,
+
+  /// Whether to minify the bundled CSS and JavaScript or not
+  ///
+  /// Removes comments, indentation and empty lines of the viewer code to
+  /// reduce the file size. License notices of the bundled libraries are
+  /// kept. User-defined content is not modified.
+  pub minify: bool,
+
+  /// URL of an external data file
+  ///
+  /// If set, the generated HTML loads the viewer data from this URL instead
+  /// of embedding it, e.g. `"pcbdata.js"`. The file must contain the output
+  /// of [InteractiveHtmlBom::generate_pcbdata_js]. This allows caching the
+  /// viewer separately from the data when hosting BOMs on a web server.
+  pub pcbdata_url: Option<String>,
+
+  /// Encoding of the viewer data
+  pub data_encoding: DataEncoding,
+
+  /// Base URL of external viewer assets
+  ///
+  /// If set, the generated HTML references the viewer CSS and JavaScript
+  /// files at this URL (e.g. `"https://cdn.example.com/ibom/"`) instead of
+  /// embedding them. The files must contain the output of
+  /// [InteractiveHtmlBom::generate_assets]. This reduces the file size when
+  /// many BOMs are hosted on the same server.
+  pub assets_url: Option<String>,
+
+  /// Nonce for a Content-Security-Policy
+  ///
+  /// If set, it is added to all script and style elements, and inline event
+  /// handlers of the template are moved into a script. This allows serving
+  /// the page with a policy like `script-src 'nonce-...'`, without
+  /// `'unsafe-inline'`. Note that [InteractiveHtmlBom::user_header] and
+  /// [InteractiveHtmlBom::user_footer] are not modified, and inline `style`
+  /// attributes remain. Must be unique for each response.
+  pub csp_nonce: Option<String>,
+
+  /// User-defined HTML header
+  ///
+  /// <div class="warning">
+  /// This should be used carefully as we neither guarantee forward- nor
+  /// backward-compatibility.
+  /// </div>
+  pub user_header: String,
+
+  /// User-defined HTML footer
+  ///
+  /// <div class="warning">
+  /// This should be used carefully as we neither guarantee forward- nor
+  /// backward-compatibility.
+  /// </div>
+  pub user_footer: String,
+
+  /// User-defined JavaScript
+  ///
+  /// <div class="warning">
+  /// This should be used carefully as we neither guarantee forward- nor
+  /// backward-compatibility.
+  /// </div>
+  pub user_js: String,
+
+  /// Custom HTML template
+  ///
+  /// If set, used instead of the bundled page. The same placeholders are
+  /// replaced as in the bundled template, e.g. `///CSS///` or
+  /// `///PCBDATA///`, thus it is best derived from the bundled one.
+  ///
+  /// <div class="warning">
+  /// This should be used carefully as we neither guarantee forward- nor
+  /// backward-compatibility.
+  /// </div>
+  pub template: Option<String>,
+
+  /// User-defined template placeholders
+  ///
+  /// Pairs of placeholder (e.g. `"///NOTICE///"`) and the HTML to replace
+  /// it with. The HTML is escaped if
+  /// [InteractiveHtmlBom::sanitize_user_content] is enabled. Built-in
+  /// placeholders take precedence.
+  pub placeholders: Vec<(String, String)>,
+
+  /// Drawings (PCB edges, silkscreen, fabrication)
+  pub drawings: Vec<Drawing>,
+
+  /// PCB tracks
+  pub tracks: Vec<Track>,
+
+  /// PCB vias
+  pub vias: Vec<Via>,
+
+  /// PCB zones
+  pub zones: Vec<Zone>,
+
+  /// Board outlines
+  ///
+  /// If not empty, the bounding box of the board is determined from these
+  /// outlines instead of the bounding box passed to
+  /// [InteractiveHtmlBom::new].
+  pub outlines: Vec<BoardOutline>,
+
+  /// Non-plated holes
+  pub holes: Vec<Hole>,
+
+  /// Dimensions, drawn on the fabrication layer
+  pub dimensions: Vec<Dimension>,
+
+  /// Footprints
+  pub footprints: Vec<Footprint>,
+
+  /// BOM rows front side
+  pub bom_front: Vec<Vec<RefMap>>,
+
+  /// BOM rows back side
+  pub bom_back: Vec<Vec<RefMap>>,
+
+  /// BOM rows front+back
+  pub bom_both: Vec<Vec<RefMap>>,
+}
+
+/// Input validated by [InteractiveHtmlBom::validate]
+struct Validated<'a> {
+  bom_back: Vec<Vec<&'a RefMap>>,
+  bom_front: Vec<Vec<&'a RefMap>>,
+  bom_both: Vec<Vec<&'a RefMap>>,
+  board_drawings: Vec<&'a Drawing>,
+  footprint_drawings: Vec<&'a Drawing>,
+}
+
+impl InteractiveHtmlBom {
+  /// Construct object
+  ///
+  /// # Arguments
+  ///
+  /// * `title` - Project title.
+  /// * `company` - Company/author name.
+  /// * `revision` - Project revision.
+  /// * `date` - Date/time as desired.
+  /// * `bottom_left` - Bottom left corner of bounding box (x, y) \[mm\].
+  /// * `top_right` - Top right corner of bounding box (x, y) \[mm\].
+  ///
+  /// # Returns
+  ///
+  /// Returns the new object.
+  pub fn new(
+    title: &str,
+    company: &str,
+    revision: &str,
+    date: &str,
+    bottom_left: impl Into<Point>,
+    top_right: impl Into<Point>,
+  ) -> InteractiveHtmlBom {
+    let bottom_left = xy(bottom_left);
+    let top_right = xy(top_right);
+    InteractiveHtmlBom {
+      title: title.to_owned(),
+      revision: revision.to_owned(),
+      company: company.to_owned(),
+      date: date.to_owned(),
+      bottom_left,
+      top_right,
+      nets: Vec::new(),
+      net_index: NetIndex::default(),
+      settings: RenderSettings::default(),
+      aux_layers: Vec::new(),
+      drill_table: false,
+      view_3d: false,
+      sanitize_user_content: false,
+      strictness: Strictness::default(),
+      decimals: DECIMALS,
+      minify: false,
+      pcbdata_url: None,
+      data_encoding: DataEncoding::default(),
+      assets_url: None,
+      csp_nonce: None,
+      checkboxes: vec!["Sourced".into(), "Placed".into()],
+      fields: Vec::new(),
+      group_fields: Vec::new(),
       user_js: String::new(),
+      page_title: None,
+      favicon: None,
+      logo: None,
+      images: Vec::new(),
+      schematic_sheets: Vec::new(),
       user_header: String::new(),
       user_footer: String::new(),
+      template: None,
+      placeholders: Vec::new(),
       drawings: Vec::new(),
       tracks: Vec::new(),
       vias: Vec::new(),
       zones: Vec::new(),
+      outlines: Vec::new(),
+      holes: Vec::new(),
+      dimensions: Vec::new(),
       footprints: Vec::new(),
       bom_front: Vec::new(),
       bom_back: Vec::new(),
@@ -745,106 +4669,1342 @@ impl InteractiveHtmlBom {
     }
   }
 
-  /// Add footprint
-  ///
-  /// # Arguments
-  ///
-  /// * `fpt` - The footprint to add.
-  ///
-  /// # Returns
+  /// Get project title
+  ///
+  /// # Returns
+  ///
+  /// Returns the project title.
+  pub fn title(&self) -> &str {
+    &self.title
+  }
+
+  /// Set project title
+  ///
+  /// # Arguments
+  ///
+  /// * `title` - Project title.
+  pub fn set_title(&mut self, title: &str) {
+    self.title = title.to_owned();
+  }
+
+  /// Get company/author name
+  ///
+  /// # Returns
+  ///
+  /// Returns the company/author name.
+  pub fn company(&self) -> &str {
+    &self.company
+  }
+
+  /// Set company/author name
+  ///
+  /// # Arguments
+  ///
+  /// * `company` - Company/author name.
+  pub fn set_company(&mut self, company: &str) {
+    self.company = company.to_owned();
+  }
+
+  /// Get project revision
+  ///
+  /// # Returns
+  ///
+  /// Returns the project revision.
+  pub fn revision(&self) -> &str {
+    &self.revision
+  }
+
+  /// Set project revision
+  ///
+  /// # Arguments
+  ///
+  /// * `revision` - Project revision.
+  pub fn set_revision(&mut self, revision: &str) {
+    self.revision = revision.to_owned();
+  }
+
+  /// Get date
+  ///
+  /// # Returns
+  ///
+  /// Returns the date.
+  pub fn date(&self) -> &str {
+    &self.date
+  }
+
+  /// Set date
+  ///
+  /// # Arguments
+  ///
+  /// * `date` - Date/time as desired.
+  pub fn set_date(&mut self, date: &str) {
+    self.date = date.to_owned();
+  }
+
+  /// Get bottom left corner of bounding box
+  ///
+  /// # Returns
+  ///
+  /// Returns the position \[mm\].
+  pub fn bottom_left(&self) -> Point {
+    self.bottom_left.into()
+  }
+
+  /// Set bottom left corner of bounding box
+  ///
+  /// # Arguments
+  ///
+  /// * `bottom_left` - Bottom left corner of bounding box (x, y) \[mm\].
+  pub fn set_bottom_left(&mut self, bottom_left: impl Into<Point>) {
+    self.bottom_left = xy(bottom_left);
+  }
+
+  /// Get top right corner of bounding box
+  ///
+  /// # Returns
+  ///
+  /// Returns the position \[mm\].
+  pub fn top_right(&self) -> Point {
+    self.top_right.into()
+  }
+
+  /// Set top right corner of bounding box
+  ///
+  /// # Arguments
+  ///
+  /// * `top_right` - Top right corner of bounding box (x, y) \[mm\].
+  pub fn set_top_right(&mut self, top_right: impl Into<Point>) {
+    self.top_right = xy(top_right);
+  }
+
+  /// Add footprint
+  ///
+  /// # Arguments
+  ///
+  /// * `fpt` - The footprint to add.
+  ///
+  /// # Returns
+  ///
+  /// Returns the ID of the added footprint, to be used for referencing it
+  /// in BOM rows.
+  pub fn add_footprint(&mut self, fpt: Footprint) -> FootprintId {
+    self.footprints.push(fpt);
+    FootprintId(self.footprints.len() - 1)
+  }
+
+  /// Add footprint after checking its fields
+  ///
+  /// Same as [InteractiveHtmlBom::add_footprint], but fails immediately if
+  /// the number of fields doesn't match [InteractiveHtmlBom::fields] instead
+  /// of when generating the HTML. Thus [InteractiveHtmlBom::fields] must be
+  /// set before adding footprints.
+  ///
+  /// # Arguments
+  ///
+  /// * `fpt` - The footprint to add.
+  ///
+  /// # Returns
+  ///
+  /// Returns the ID of the added footprint, or a [FieldCountError]
+  /// containing the footprint if it was not added.
+  pub fn try_add_footprint(
+    &mut self,
+    fpt: Footprint,
+  ) -> Result<FootprintId, FieldCountError> {
+    if fpt.fields.len() != self.fields.len() {
+      return Err(FieldCountError {
+        expected: self.fields.len(),
+        footprint: Box::new(fpt),
+      });
+    }
+    Ok(self.add_footprint(fpt))
+  }
+
+  /// Replace footprint
+  ///
+  /// BOM rows referencing the footprint stay unchanged, i.e. they reference
+  /// the new footprint afterwards.
+  ///
+  /// # Arguments
+  ///
+  /// * `id` - ID of the footprint to replace.
+  /// * `fpt` - The new footprint.
+  ///
+  /// # Returns
+  ///
+  /// Returns the replaced footprint, or [None] if the ID does not exist.
+  pub fn replace_footprint(
+    &mut self,
+    id: FootprintId,
+    fpt: Footprint,
+  ) -> Option<Footprint> {
+    let old = self.footprints.get_mut(id.0)?;
+    Some(std::mem::replace(old, fpt))
+  }
+
+  /// Remove footprint
+  ///
+  /// All references to the footprint are removed from the BOM rows, and rows
+  /// which become empty are removed too. Since footprint IDs are indices,
+  /// the IDs of all subsequent footprints are decremented by one and the BOM
+  /// rows are updated accordingly. IDs stored elsewhere can be updated with
+  /// [RemovedFootprint::remap].
+  ///
+  /// # Arguments
+  ///
+  /// * `id` - ID of the footprint to remove.
+  ///
+  /// # Returns
+  ///
+  /// Returns the removed footprint with its references, or [None] if the ID
+  /// does not exist.
+  pub fn remove_footprint(
+    &mut self,
+    id: FootprintId,
+  ) -> Option<RemovedFootprint> {
+    let id = id.0;
+    if id >= self.footprints.len() {
+      return None;
+    }
+    let mut references = Vec::new();
+    for bom in [&mut self.bom_front, &mut self.bom_back, &mut self.bom_both] {
+      for row in bom.iter_mut() {
+        row.retain(|x| {
+          let keep = x.footprint_id != id;
+          if !keep && !references.contains(x) {
+            references.push(x.clone());
+          }
+          keep
+        });
+        for refmap in row.iter_mut() {
+          if refmap.footprint_id > id {
+            refmap.footprint_id -= 1;
+          }
+        }
+      }
+      bom.retain(|row| !row.is_empty());
+    }
+    Some(RemovedFootprint {
+      footprint: self.footprints.remove(id),
+      references,
+      id,
+    })
+  }
+
+  /// Add multiple footprints
+  ///
+  /// # Arguments
+  ///
+  /// * `fpts` - The footprints to add.
+  ///
+  /// # Returns
+  ///
+  /// Returns the range of IDs of the added footprints, each convertible to
+  /// [FootprintId].
+  pub fn add_footprints(
+    &mut self,
+    fpts: impl IntoIterator<Item = Footprint>,
+  ) -> Range<usize> {
+    let start = self.footprints.len();
+    self.footprints.extend(fpts);
+    start..self.footprints.len()
+  }
+
+  /// Add multiple drawings
+  ///
+  /// # Arguments
+  ///
+  /// * `drawings` - The drawings to add.
+  ///
+  /// # Returns
+  ///
+  /// Returns the range of indices in [InteractiveHtmlBom::drawings].
+  pub fn add_drawings(
+    &mut self,
+    drawings: impl IntoIterator<Item = Drawing>,
+  ) -> Range<usize> {
+    let start = self.drawings.len();
+    self.drawings.extend(drawings);
+    start..self.drawings.len()
+  }
+
+  /// Add multiple tracks
+  ///
+  /// # Arguments
+  ///
+  /// * `tracks` - The tracks to add.
+  ///
+  /// # Returns
+  ///
+  /// Returns the range of indices in [InteractiveHtmlBom::tracks].
+  pub fn add_tracks(
+    &mut self,
+    tracks: impl IntoIterator<Item = Track>,
+  ) -> Range<usize> {
+    let start = self.tracks.len();
+    self.tracks.extend(tracks);
+    start..self.tracks.len()
+  }
+
+  /// Add tracks along a polyline
+  ///
+  /// Each pair of consecutive points results in a separate [Track] segment.
+  /// The net, if any, is registered with [InteractiveHtmlBom::add_net] and
+  /// shared between all segments.
+  ///
+  /// # Arguments
+  ///
+  /// * `layer` - Copper layer.
+  /// * `points` - Points (x, y) of the polyline \[mm\].
+  /// * `width` - Track width \[mm\].
+  /// * `net` - Net name (optional).
+  ///
+  /// # Returns
+  ///
+  /// Returns the range of indices in [InteractiveHtmlBom::tracks].
+  pub fn add_track_polyline(
+    &mut self,
+    layer: Layer,
+    points: impl IntoIterator<Item = impl Into<Point>>,
+    width: f64,
+    net: Option<&str>,
+  ) -> Range<usize> {
+    let net = net.map(|name| self.add_net(name));
+    let points: Vec<Point> = points.into_iter().map(Into::into).collect();
+    self.add_tracks(points.windows(2).map(|segment| {
+      let mut track =
+        Track::new(layer.clone(), segment[0], segment[1], width, None);
+      track.set_net_id(net.clone());
+      track
+    }))
+  }
+
+  /// Add BOM row
+  ///
+  /// # Arguments
+  ///
+  /// * `view` - The BOM to add the row to, i.e.
+  ///   [InteractiveHtmlBom::bom_front], [InteractiveHtmlBom::bom_back] or
+  ///   [InteractiveHtmlBom::bom_both].
+  /// * `refs` - References and footprint IDs of the row.
+  pub fn add_bom_row(&mut self, view: LayerView, refs: &[(&str, FootprintId)]) {
+    let row = refs.iter().map(|(r, id)| RefMap::new(r, *id)).collect();
+    match view {
+      LayerView::Front => self.bom_front.push(row),
+      LayerView::Back => self.bom_back.push(row),
+      LayerView::FrontAndBack => self.bom_both.push(row),
+    }
+  }
+
+  /// Add BOM row with a single footprint
+  ///
+  /// # Arguments
+  ///
+  /// * `view` - The BOM to add the row to, see
+  ///   [InteractiveHtmlBom::add_bom_row].
+  /// * `reference` - Component reference (e.g. "R1").
+  /// * `id` - ID of footprint as returned by
+  ///   [InteractiveHtmlBom::add_footprint].
+  pub fn add_single(
+    &mut self,
+    view: LayerView,
+    reference: &str,
+    id: FootprintId,
+  ) {
+    self.add_bom_row(view, &[(reference, id)]);
+  }
+
+  /// Register net
+  ///
+  /// Registered nets are listed in the viewer even if no object is
+  /// connected to them. Registering the same name again returns the existing
+  /// net.
+  ///
+  /// # Arguments
+  ///
+  /// * `name` - Net name.
+  ///
+  /// # Returns
+  ///
+  /// Returns the net, to be assigned with e.g. [Pad::set_net_id].
+  pub fn add_net(&mut self, name: &str) -> NetId {
+    let index = &mut self.net_index.0;
+    if index.len() != self.nets.len() {
+      *index = self.nets.iter().map(|x| (x.0.clone(), x.clone())).collect();
+    }
+    if let Some(net) = index.get(name) {
+      return net.clone();
+    }
+    let net = NetId::new(name);
+    self.nets.push(net.clone());
+    index.insert(net.0.clone(), net.clone());
+    net
+  }
+
+  /// Build BOM rows by grouping footprints
+  ///
+  /// Footprints with identical values in all the given fields are combined
+  /// into one row. Rows are appended to [InteractiveHtmlBom::bom_both] and,
+  /// split by footprint layer, to [InteractiveHtmlBom::bom_front] and
+  /// [InteractiveHtmlBom::bom_back]. Footprints which are not mounted are
+  /// never grouped together with mounted ones, so they get separate rows.
+  /// The field names are stored in [InteractiveHtmlBom::group_fields].
+  ///
+  /// # Arguments
+  ///
+  /// * `refs` - References of all footprints to consider.
+  /// * `group_fields` - Names of the fields to group by, see
+  ///   [InteractiveHtmlBom::fields].
+  ///
+  /// # Returns
+  ///
+  /// Returns an error if a field or footprint does not exist.
+  pub fn group_bom(
+    &mut self,
+    refs: &[RefMap],
+    group_fields: &[&str],
+  ) -> Result<(), GenerateError> {
+    let indices = group_fields
+      .iter()
+      .map(|name| {
+        self.fields.iter().position(|x| x == name).ok_or_else(|| {
+          GenerateError::UnknownField {
+            name: name.to_string(),
+          }
+        })
+      })
+      .collect::<Result<Vec<_>, _>>()?;
+    let mut groups: Vec<(_, Vec<RefMap>)> = Vec::new();
+    for (row, refmap) in refs.iter().enumerate() {
+      let fpt = self.footprints.get(refmap.footprint_id).ok_or(
+        GenerateError::InvalidFootprintId {
+          row,
+          index: refmap.footprint_id,
+        },
+      )?;
+      let values = indices
+        .iter()
+        .map(|i| {
+          fpt.fields.get(*i).ok_or(GenerateError::FieldCountMismatch {
+            footprint: refmap.footprint_id(),
+          })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+      let key = (fpt.mount, values);
+      match groups.iter_mut().find(|(k, _)| *k == key) {
+        Some((_, row)) => row.push(refmap.clone()),
+        None => groups.push((key, vec![refmap.clone()])),
+      }
+    }
+    self.add_grouped_rows(groups.into_iter().map(|(_, row)| row).collect());
+    self.group_fields = group_fields.iter().map(|x| x.to_string()).collect();
+    Ok(())
+  }
+
+  /// Add grouped BOM rows, split by footprint layer for the one-sided BOMs
+  fn add_grouped_rows(&mut self, rows: Vec<Vec<RefMap>>) {
+    for row in rows {
+      for layer in [Layer::Front, Layer::Back] {
+        let side = row
+          .iter()
+          .filter(|x| self.footprints[x.footprint_id].layer == layer)
+          .cloned()
+          .collect::<Vec<_>>();
+        if !side.is_empty() {
+          match layer {
+            Layer::Front => self.bom_front.push(side),
+            Layer::Back => self.bom_back.push(side),
+          }
+        }
+      }
+      self.bom_both.push(row);
+    }
+  }
+
+  /// Merge component fields from a KiCad XML netlist
+  ///
+  /// Reads the XML netlist exported by KiCad's schematic editor (also used
+  /// for BOM exports) and sets the fields of the footprints with matching
+  /// references, e.g. "Value", "Datasheet" or custom fields like "MPN".
+  /// Only fields listed in [InteractiveHtmlBom::fields] are set, fields
+  /// missing in the netlist keep their current value. Usually called before
+  /// [InteractiveHtmlBom::group_bom] with the same references.
+  ///
+  /// # Arguments
+  ///
+  /// * `xml` - Content of the XML netlist.
+  /// * `refs` - References of all footprints to consider.
+  ///
+  /// # Returns
+  ///
+  /// Returns the references of netlist components not contained in `refs`,
+  /// or an error if the netlist is invalid or a footprint does not exist.
+  #[cfg(feature = "kicad")]
+  pub fn merge_kicad_netlist(
+    &mut self,
+    xml: &str,
+    refs: &[RefMap],
+  ) -> Result<Vec<String>, GenerateError> {
+    let components =
+      kicad::components(xml).map_err(|reason| GenerateError::InvalidInput {
+        format: InputFormat::KicadNetlist,
+        reason,
+      })?;
+    // Check all references first to not merge fields partially.
+    for (row, refmap) in refs.iter().enumerate() {
+      if refmap.footprint_id >= self.footprints.len() {
+        return Err(GenerateError::InvalidFootprintId {
+          row,
+          index: refmap.footprint_id,
+        });
+      }
+    }
+    let mut unknown = Vec::new();
+    for (reference, fields) in components {
+      let mut found = false;
+      for refmap in refs.iter().filter(|x| x.reference == reference) {
+        let fpt = &mut self.footprints[refmap.footprint_id];
+        for (name, value) in &fields {
+          let index = self.fields.iter().position(|x| x == name);
+          if let Some(field) = index.and_then(|i| fpt.fields.get_mut(i)) {
+            field.clone_from(value);
+          }
+        }
+        found = true;
+      }
+      if !found {
+        unknown.push(reference);
+      }
+    }
+    Ok(unknown)
+  }
+
+  /// Import a Gerber file
+  ///
+  /// Adds the image of a Gerber (RS-274X) file as drawings on the given
+  /// layer. Together with [InteractiveHtmlBom::import_excellon], this allows
+  /// generating a BOM for designs where only fabrication data is available.
+  /// Draws become lines, flashes (e.g. pads) and regions (e.g. zones) become
+  /// filled shapes. Objects on [DrawingLayer::Edge] are added as
+  /// [InteractiveHtmlBom::outlines] instead, thus they determine the board
+  /// bounding box.
+  ///
+  /// Gerber coordinates have the Y axis pointing upwards, thus they are
+  /// mirrored (y → -y). Objects with clear polarity, step and repeat blocks
+  /// and thermal macro primitives are not supported.
+  ///
+  /// # Arguments
+  ///
+  /// * `layer` - Layer to add the drawings to.
+  /// * `gerber` - Content of the Gerber file.
+  ///
+  /// # Returns
+  ///
+  /// Returns an error if the file is invalid.
+  #[cfg(feature = "gerber")]
+  pub fn import_gerber(
+    &mut self,
+    layer: DrawingLayer,
+    gerber: &str,
+  ) -> Result<(), GenerateError> {
+    let shapes =
+      gerber::parse(gerber).map_err(|reason| GenerateError::InvalidInput {
+        format: InputFormat::Gerber,
+        reason,
+      })?;
+    for shape in shapes {
+      if layer == DrawingLayer::Edge {
+        let outline = BoardOutline::new(&shape.path, &[], shape.width);
+        self.outlines.push(outline);
+      } else {
+        let drawing = Drawing::polygon(
+          layer.clone(),
+          &shape.path,
+          shape.width,
+          shape.filled,
+        );
+        self.drawings.push(drawing);
+      }
+    }
+    Ok(())
+  }
+
+  /// Import an Excellon drill file
+  ///
+  /// Adds the drills and slots as [InteractiveHtmlBom::holes], with
+  /// coordinates mirrored like [InteractiveHtmlBom::import_gerber].
+  ///
+  /// # Arguments
+  ///
+  /// * `excellon` - Content of the drill file.
+  /// * `plated` - Whether the holes are plated or not, e.g. depending on
+  ///   whether it is the PTH or NPTH file.
+  ///
+  /// # Returns
+  ///
+  /// Returns an error if the file is invalid.
+  #[cfg(feature = "gerber")]
+  pub fn import_excellon(
+    &mut self,
+    excellon: &str,
+    plated: bool,
+  ) -> Result<(), GenerateError> {
+    let drills = excellon::parse(excellon).map_err(|reason| {
+      GenerateError::InvalidInput {
+        format: InputFormat::Excellon,
+        reason,
+      }
+    })?;
+    for drill in drills {
+      let (dx, dy) = (drill.end.0 - drill.start.0, drill.end.1 - drill.start.1);
+      let center = (drill.start.0 + dx / 2.0, drill.start.1 + dy / 2.0);
+      let size = (dx.hypot(dy) + drill.diameter, drill.diameter);
+      let angle = -dy.atan2(dx).to_degrees() + 0.0;
+      self.holes.push(Hole::slot(center, size, angle, plated));
+    }
+    Ok(())
+  }
+
+  /// Import a pick and place file
+  ///
+  /// Reads a pick and place (centroid) CSV file as exported by EDA tools for
+  /// assembly, and adds a footprint for each component. Together with
+  /// [InteractiveHtmlBom::import_gerber], this allows generating a BOM for
+  /// designs where only fabrication data is available. The columns are
+  /// identified by the header line, and at least the reference and X/Y
+  /// columns are required. Rotation, side (top/bottom), value and package
+  /// columns are optional. Coordinates are expected in millimeters, unless
+  /// they have a unit suffix ("mm", "mil" or "in").
+  ///
+  /// Since the files contain no pads, the footprints get a bounding box
+  /// only. Its size is guessed from the package name for chip packages like
+  /// "0603", otherwise `default_size` is used. The fields listed in
+  /// [InteractiveHtmlBom::fields] named "Value", "Package" and "Footprint"
+  /// are set from the file, all others are left empty. Footprints with equal
+  /// value and package are grouped into BOM rows like
+  /// [InteractiveHtmlBom::group_bom] does. If there are no
+  /// [InteractiveHtmlBom::outlines], the board bounding box is extended to
+  /// contain all footprints.
+  ///
+  /// As in Gerber files, the Y axis is mirrored (y → -y).
+  ///
+  /// # Arguments
+  ///
+  /// * `csv` - Content of the pick and place file.
+  /// * `default_size` - Footprint size (width, height) for unknown packages
+  ///   \[mm\].
+  ///
+  /// # Returns
+  ///
+  /// Returns the references of the added footprints, or an error if the file
+  /// is invalid.
+  #[cfg(feature = "pnp")]
+  pub fn import_pick_and_place(
+    &mut self,
+    csv: &str,
+    default_size: (f64, f64),
+  ) -> Result<Vec<RefMap>, GenerateError> {
+    let placements =
+      pnp::parse(csv).map_err(|reason| GenerateError::InvalidInput {
+        format: InputFormat::PickAndPlace,
+        reason,
+      })?;
+    let mut refs = Vec::new();
+    let mut groups: Vec<((String, String), Vec<RefMap>)> = Vec::new();
+    for placement in placements {
+      let size = pnp::package_size(&placement.package).unwrap_or(default_size);
+      let fields = self.fields.iter().map(|name| match name.as_str() {
+        "Value" => placement.value.clone(),
+        "Package" | "Footprint" => placement.package.clone(),
+        _ => String::new(),
+      });
+      let footprint = Footprint::new(
+        if placement.back {
+          Layer::Back
+        } else {
+          Layer::Front
+        },
+        placement.pos,
+        placement.angle,
+        (-size.0 / 2.0, -size.1 / 2.0),
+        (size.0 / 2.0, size.1 / 2.0),
+        fields.collect::<Vec<_>>(),
+        [],
+        true,
+      );
+      if self.outlines.is_empty() {
+        // Conservative, i.e. large enough for any rotation.
+        let r = size.0.hypot(size.1) / 2.0;
+        let (x, y) = placement.pos;
+        self.bottom_left.0 = self.bottom_left.0.min(x - r);
+        self.bottom_left.1 = self.bottom_left.1.min(y - r);
+        self.top_right.0 = self.top_right.0.max(x + r);
+        self.top_right.1 = self.top_right.1.max(y + r);
+      }
+      let refmap =
+        RefMap::new(&placement.reference, self.add_footprint(footprint));
+      let key = (placement.value, placement.package);
+      match groups.iter_mut().find(|(k, _)| *k == key) {
+        Some((_, row)) => row.push(refmap.clone()),
+        None => groups.push((key, vec![refmap.clone()])),
+      }
+      refs.push(refmap);
+    }
+    self.add_grouped_rows(groups.into_iter().map(|(_, row)| row).collect());
+    Ok(refs)
+  }
+
+  /// Read a LibrePCB project
+  ///
+  /// Creates the object from a board of a LibrePCB project, without going
+  /// through LibrePCB's own BOM export. The project must be a directory
+  /// (i.e. not a zipped *.lppz file) as saved by LibrePCB 1.x. Metadata,
+  /// board outlines, holes, drawings, traces, vias and footprints with their
+  /// pads, nets and names/values are imported. Plane fills are not stored
+  /// in the project and thus missing.
+  ///
+  /// The fields are set to "Value" and "Package", and the BOM is grouped by
+  /// them. All settings have their default values and may be changed before
+  /// generating the output. As LibrePCB's Y axis points upwards, the
+  /// coordinates are mirrored (y → -y).
+  ///
+  /// # Arguments
+  ///
+  /// * `path` - Project directory, or the *.lpp file within it.
+  /// * `board` - Name of the board to read, or [None] for the first one.
+  ///
+  /// # Returns
+  ///
+  /// Returns the new object, or an error if reading the project failed or
+  /// it is invalid.
+  #[cfg(feature = "librepcb")]
+  pub fn from_librepcb_project(
+    path: impl AsRef<Path>,
+    board: Option<&str>,
+  ) -> Result<InteractiveHtmlBom, GenerateError> {
+    librepcb::import(path.as_ref(), board)
+  }
+
+  /// Read an Eagle board
+  ///
+  /// Creates the object from an Eagle board file (*.brd) in the XML format
+  /// of Eagle 6 and later, as also exported by Fusion Electronics. The board
+  /// outline (dimension layer), holes, drawings, tracks, vias and elements
+  /// with their pads, nets and names/values are imported. Polygon pours are
+  /// not stored in the file and thus missing, curved tracks are approximated
+  /// by straight segments. Elements marked as not populated are added as
+  /// footprints which are not mounted.
+  ///
+  /// The fields are set to "Value" and "Package", and the BOM is grouped by
+  /// them. All settings have their default values and may be changed before
+  /// generating the output. As Eagle's Y axis points upwards, the
+  /// coordinates are mirrored (y → -y).
+  ///
+  /// # Arguments
+  ///
+  /// * `xml` - Content of the board file.
+  ///
+  /// # Returns
+  ///
+  /// Returns the new object, or an error if the board is invalid.
+  #[cfg(feature = "eagle")]
+  pub fn from_eagle_board(
+    xml: &str,
+  ) -> Result<InteractiveHtmlBom, GenerateError> {
+    eagle::import(xml)
+  }
+
+  /// Read a generic JSON file
+  ///
+  /// Creates the object from the "generic JSON" input format of the upstream
+  /// InteractiveHtmlBom (specification version 1), as written by
+  /// [InteractiveHtmlBom::generate_generic_json] or by exporters written for
+  /// the Python tool. Board edges, drawings, footprints with their pads,
+  /// tracks, vias, zones and nets are imported. Footprint drawings are put
+  /// on the silkscreen layers, arc tracks are approximated by straight
+  /// segments. Components with the attribute "Virtual" are added as
+  /// footprints which are not mounted.
+  ///
+  /// The fields are set to "Value", "Footprint" and the extra fields of the
+  /// components, and the BOM is grouped by value and footprint. All settings
+  /// have their default values and may be changed before generating the
+  /// output.
+  ///
+  /// # Arguments
+  ///
+  /// * `json` - Content of the JSON file.
+  ///
+  /// # Returns
+  ///
+  /// Returns the new object, or an error if the file is invalid.
+  #[cfg(feature = "generic")]
+  pub fn from_generic_json(
+    json: &str,
+  ) -> Result<InteractiveHtmlBom, GenerateError> {
+    generic::import(json)
+  }
+
+  /// Build drill table HTML
+  fn drill_table_html(&self) -> String {
+    // Count drills by size (in µm, smaller dimension first) and plating.
+    let mut drills: BTreeMap<((i64, i64), bool), usize> = BTreeMap::new();
+    let mut add = |size: (f64, f64), plated: bool| {
+      let um = |x: f64| (x * 1000.0).round() as i64;
+      let size = (um(size.0.min(size.1)), um(size.0.max(size.1)));
+      *drills.entry((size, !plated)).or_default() += 1;
+    };
+    for via in &self.vias {
+      add((via.drill_diameter, via.drill_diameter), true);
+    }
+    for pad in self.footprints.iter().flat_map(|x| &x.pads) {
+      if let Some(size) = pad.drill_size {
+        add(size, true);
+      }
+    }
+    for hole in &self.holes {
+      add(hole.size, hole.plated);
+    }
+
+    let mut rows = String::new();
+    for (((w, h), non_plated), count) in &drills {
+      let mm = |x: i64| (x as f64 / 1000.0).to_string();
+      let size = if w == h {
+        mm(*w)
+      } else {
+        format!("{} x {}", mm(*w), mm(*h))
+      };
+      let plated = if *non_plated { "No" } else { "Yes" };
+      rows += &format!(
+        "<tr><td>{}</td><td>{}</td><td>{}</td></tr>\n",
+        size, plated, count
+      );
+    }
+    format!(
+      "<div id=\"drilltable\" style=\"padding: 10px;\">\n\
+       <h3>Drill Table</h3>\n\
+       <table class=\"bom\">\n\
+       <thead>\n\
+       <tr><th>Size [mm]</th><th>Plated</th><th>Count</th></tr>\n\
+       </thead>\n\
+       <tbody>\n{}</tbody>\n\
+       </table>\n\
+       </div>\n",
+      rows
+    )
+  }
+
+  /// Get the board bounding box, determined from outlines if there are any
+  fn board_bbox(&self) -> ((f64, f64), (f64, f64)) {
+    self
+      .outlines
+      .iter()
+      .filter_map(|x| svgpath::bounds(&x.outline))
+      .reduce(|a, b| {
+        (
+          (a.0 .0.min(b.0 .0), a.0 .1.min(b.0 .1)),
+          (a.1 .0.max(b.1 .0), a.1 .1.max(b.1 .1)),
+        )
+      })
+      .unwrap_or((self.bottom_left, self.top_right))
+  }
+
+  /// Get the configuration of the 3D view, if enabled
+  ///
+  /// The board outlines and cutouts are flattened to polygons, falling back
+  /// to the board bounding box if there are no outlines. Malformed contours
+  /// are skipped, see [Warning::EmptyOutline].
+  fn view_3d_json(&self) -> JsonValue {
+    if !self.view_3d {
+      return JsonValue::Null;
+    }
+    let point = |p: &(f64, f64)| {
+      array![round(p.0, self.decimals), round(p.1, self.decimals)]
+    };
+    let mut outlines = Vec::new();
+    let paths = self
+      .outlines
+      .iter()
+      .flat_map(|x| std::iter::once(&x.outline).chain(&x.cutouts));
+    for path in paths {
+      for polyline in svgpath::flatten(path).unwrap_or_default() {
+        let mut points = polyline.points;
+        if points.len() > 1 && points.first() == points.last() {
+          points.pop();
+        }
+        if points.len() >= 3 {
+          outlines.push(points.iter().map(point).collect::<Vec<_>>());
+        }
+      }
+    }
+    if outlines.is_empty() {
+      let (min, max) = self.board_bbox();
+      let corners = [min, (max.0, min.1), max, (min.0, max.1)];
+      outlines.push(corners.iter().map(point).collect());
+    }
+    object! {
+      thickness: BOARD_THICKNESS,
+      outlines: outlines,
+    }
+  }
+
+  /// Get the schematic sheets configuration
+  ///
+  /// Locations are resolved to footprint IDs since the viewer identifies
+  /// highlighted components by them.
+  fn schematic_sheets_json(&self) -> Vec<JsonValue> {
+    let mut ids: BTreeMap<&str, BTreeSet<usize>> = BTreeMap::new();
+    for rows in [&self.bom_both, &self.bom_front, &self.bom_back] {
+      for refmap in rows.iter().flatten() {
+        ids
+          .entry(refmap.reference.as_str())
+          .or_default()
+          .insert(refmap.footprint_id);
+      }
+    }
+    self
+      .schematic_sheets
+      .iter()
+      .map(|sheet| {
+        let mut locations = Vec::new();
+        for (reference, (x, y)) in &sheet.locations {
+          for id in ids.get(reference.as_str()).into_iter().flatten() {
+            locations.push(array![
+              *id,
+              round(*x, self.decimals),
+              round(*y, self.decimals)
+            ]);
+          }
+        }
+        object! {
+          title: sheet.title.as_str(),
+          src: sheet.image.data_url(),
+          locations: locations,
+        }
+      })
+      .collect()
+  }
+
+  /// Check a drawing for an invalid layer or path
+  fn check_drawing(&self, drawing: &Drawing) -> Result<(), GenerateError> {
+    match drawing.layer {
+      DrawingLayer::AuxiliaryFront(id) | DrawingLayer::AuxiliaryBack(id)
+        if id >= self.aux_layers.len() =>
+      {
+        Err(GenerateError::InvalidAuxiliaryLayer { index: id })
+      }
+      DrawingLayer::CopperFront | DrawingLayer::CopperBack
+        if drawing.text.is_some() =>
+      {
+        Err(GenerateError::CopperText)
+      }
+      DrawingLayer::CopperFront | DrawingLayer::CopperBack
+        if svgpath::flatten(&drawing.svgpath).is_none() =>
+      {
+        Err(GenerateError::InvalidCopperPath)
+      }
+      _ => Ok(()),
+    }
+  }
+
+  /// Get BOM rows with valid footprint IDs
+  ///
+  /// In [Strictness::Lenient] mode, invalid references are dropped together
+  /// with rows becoming empty, otherwise an error is returned.
+  fn valid_rows<'a>(
+    &self,
+    rows: &'a [Vec<RefMap>],
+  ) -> Result<Vec<Vec<&'a RefMap>>, GenerateError> {
+    let mut valid_rows = Vec::with_capacity(rows.len());
+    for (index, row) in rows.iter().enumerate() {
+      let mut valid_row = Vec::with_capacity(row.len());
+      for map in row {
+        if map.footprint_id < self.footprints.len() {
+          valid_row.push(map);
+        } else if self.strictness != Strictness::Lenient {
+          return Err(GenerateError::InvalidFootprintId {
+            row: index,
+            index: map.footprint_id,
+          });
+        }
+      }
+      if !valid_row.is_empty() {
+        valid_rows.push(valid_row);
+      }
+    }
+    Ok(valid_rows)
+  }
+
+  /// Get the fields of a footprint
+  ///
+  /// In [Strictness::Lenient] mode, missing fields are added as empty
+  /// strings and excess fields are dropped, otherwise an error is returned.
+  fn footprint_fields(
+    &self,
+    id: usize,
+  ) -> Result<Cow<'_, [String]>, GenerateError> {
+    let fields = &self.footprints[id].fields;
+    if fields.len() == self.fields.len() {
+      Ok(Cow::Borrowed(fields))
+    } else if self.strictness == Strictness::Lenient {
+      let mut fields = fields.clone();
+      fields.resize(self.fields.len(), String::new());
+      Ok(Cow::Owned(fields))
+    } else {
+      let footprint = FootprintId(id);
+      Err(GenerateError::FieldCountMismatch { footprint })
+    }
+  }
+
+  /// Get warnings about invalid input dropped or repaired when generating
+  fn repairs(&self) -> Vec<Warning> {
+    let mut warnings = Vec::new();
+    for bom in [&self.bom_back, &self.bom_front, &self.bom_both] {
+      for (row, maps) in bom.iter().enumerate() {
+        for map in maps {
+          if map.footprint_id >= self.footprints.len() {
+            let index = map.footprint_id;
+            warnings.push(Warning::DroppedReference { row, index });
+          }
+        }
+      }
+    }
+    for (index, drawing) in self.drawings.iter().enumerate() {
+      if self.check_drawing(drawing).is_err() {
+        let footprint = None;
+        warnings.push(Warning::DroppedDrawing { footprint, index });
+      }
+    }
+    for (id, fpt) in self.footprints.iter().enumerate() {
+      for (index, drawing) in fpt.drawings.iter().enumerate() {
+        if self.check_drawing(drawing).is_err() {
+          let footprint = Some(FootprintId(id));
+          warnings.push(Warning::DroppedDrawing { footprint, index });
+        }
+      }
+    }
+    for (id, fpt) in self.footprints.iter().enumerate() {
+      if fpt.fields.len() != self.fields.len() {
+        let footprint = FootprintId(id);
+        warnings.push(Warning::ResizedFields { footprint });
+      }
+    }
+    for (key, value) in &self.settings.extra_config {
+      if jzon::parse(value).is_err() {
+        let key = key.clone();
+        warnings.push(Warning::DroppedExtraConfig { key });
+      }
+    }
+    warnings
+  }
+
+  /// Get indices of edge drawings which are not part of a closed contour
+  ///
+  /// Contours may consist of several drawings, thus every open end must
+  /// coincide with the open end of another (or the same) drawing.
+  fn open_outlines(&self) -> Vec<usize> {
+    const TOLERANCE: f64 = 0.001;
+    let mut ends = Vec::new();
+    for (index, drawing) in self.drawings.iter().enumerate() {
+      if drawing.layer != DrawingLayer::Edge || drawing.text.is_some() {
+        continue;
+      }
+      for polyline in svgpath::flatten(&drawing.svgpath).unwrap_or_default() {
+        let (Some(first), Some(last)) =
+          (polyline.points.first(), polyline.points.last())
+        else {
+          continue;
+        };
+        if !polyline.closed && first != last {
+          ends.push((index, *first));
+          ends.push((index, *last));
+        }
+      }
+    }
+    let mut indices = Vec::new();
+    for (i, (index, p)) in ends.iter().enumerate() {
+      let connected = ends.iter().enumerate().any(|(j, (_, q))| {
+        i != j
+          && (p.0 - q.0).abs() <= TOLERANCE
+          && (p.1 - q.1).abs() <= TOLERANCE
+      });
+      if !connected && !indices.contains(index) {
+        indices.push(*index);
+      }
+    }
+    indices
+  }
+
+  /// Check for suspicious input
+  ///
+  /// Detects common mistakes which don't prevent generating the HTML, but
+  /// typically result in a blank or incomplete viewer, like footprints not
+  /// referenced by the BOM, empty paths or zero-width tracks. The board
+  /// bounding box is determined from [InteractiveHtmlBom::outlines] if there
+  /// are any, otherwise the bounding box passed to [InteractiveHtmlBom::new]
+  /// is used.
+  ///
+  /// # Returns
+  ///
+  /// Returns all detected warnings, empty if everything looks fine.
+  pub fn warnings(&self) -> Vec<Warning> {
+    let mut warnings = match self.strictness {
+      Strictness::Lenient => self.repairs(),
+      _ => Vec::new(),
+    };
+
+    // Board bounding box.
+    let (min, max) = self.board_bbox();
+    if min.0 >= max.0 || min.1 >= max.1 {
+      warnings.push(Warning::EmptyBoundingBox);
+    } else {
+      let outside = |(a, b): ((f64, f64), (f64, f64))| {
+        b.0 < min.0 || b.1 < min.1 || a.0 > max.0 || a.1 > max.1
+      };
+      for (id, fpt) in self.footprints.iter().enumerate() {
+        if outside(fpt.extent()) {
+          let footprint = FootprintId(id);
+          warnings.push(Warning::FootprintOutsideBoard { footprint });
+        }
+      }
+      for (index, drawing) in self.drawings.iter().enumerate() {
+        if drawing.bounds().is_some_and(outside) {
+          warnings.push(Warning::DrawingOutsideBoard { index });
+        }
+      }
+    }
+
+    // Footprints, with number of references in front, back and both BOM.
+    let mut counts = vec![[0; 3]; self.footprints.len()];
+    for (i, bom) in [&self.bom_front, &self.bom_back, &self.bom_both]
+      .iter()
+      .enumerate()
+    {
+      for map in bom.iter().flatten() {
+        if let Some(count) = counts.get_mut(map.footprint_id) {
+          count[i] += 1;
+        }
+      }
+    }
+    for (id, fpt) in self.footprints.iter().enumerate() {
+      let footprint = FootprintId(id);
+      let [front, back, both] = counts[id];
+      if front + back + both == 0 {
+        if fpt.mount {
+          warnings.push(Warning::UnreferencedFootprint { footprint });
+        }
+      } else if front > 1 || back > 1 || both > 1 || (front > 0 && back > 0) {
+        warnings.push(Warning::MultipleBomRows { footprint });
+      }
+      for (pad, p) in fpt.pads.iter().enumerate() {
+        if p.drill_size.is_none() && !p.layers.contains(&fpt.layer) {
+          warnings.push(Warning::PadLayerMismatch { footprint, pad });
+        }
+      }
+    }
+
+    // Empty or malformed geometry.
+    for (index, drawing) in self.drawings.iter().enumerate() {
+      if drawing.bounds().is_none() {
+        warnings.push(Warning::EmptyDrawing { index });
+      }
+    }
+    for index in self.open_outlines() {
+      warnings.push(Warning::OpenOutline { index });
+    }
+    for (index, zone) in self.zones.iter().enumerate() {
+      if svgpath::bounds(&zone.svgpath).is_none() {
+        warnings.push(Warning::EmptyZone { index });
+      }
+    }
+    for (index, outline) in self.outlines.iter().enumerate() {
+      let mut paths = std::iter::once(&outline.outline).chain(&outline.cutouts);
+      if paths.any(|x| svgpath::bounds(x).is_none()) {
+        warnings.push(Warning::EmptyOutline { index });
+      }
+    }
+    for (index, track) in self.tracks.iter().enumerate() {
+      if track.width <= 0.0 {
+        warnings.push(Warning::ZeroWidthTrack { index });
+      }
+    }
+    warnings
+  }
+
+  /// Validate the input according to [InteractiveHtmlBom::strictness]
   ///
-  /// Returns the ID of the added footprint, to be used for referencing it
-  /// in BOM rows.
-  pub fn add_footprint(&mut self, fpt: Footprint) -> usize {
-    self.footprints.push(fpt);
-    self.footprints.len() - 1
-  }
+  /// In [Strictness::Lenient] mode, invalid BOM references and drawings are
+  /// dropped, otherwise an error is returned.
+  fn validate(&self) -> Result<Validated<'_>, GenerateError> {
+    match self.strictness {
+      Strictness::Strict => {
+        if let Some(warning) = self.warnings().into_iter().next() {
+          return Err(GenerateError::Diagnostic { warning });
+        }
+      }
+      Strictness::Normal | Strictness::Lenient => {}
+    }
 
-  /// Generate HTML
-  pub fn generate_html(&self) -> Result<String, String> {
     // Validate footprint IDs.
-    for bom in [&self.bom_back, &self.bom_front, &self.bom_both] {
-      for row in bom {
-        for map in row {
-          if map.footprint_id >= self.footprints.len() {
-            return Err("Invalid footprint ID.".into());
-          }
-        }
+    let bom_back = self.valid_rows(&self.bom_back)?;
+    let bom_front = self.valid_rows(&self.bom_front)?;
+    let bom_both = self.valid_rows(&self.bom_both)?;
+
+    // Check drawing layers and paths, invalid drawings are dropped in lenient
+    // mode.
+    let mut board_drawings = Vec::with_capacity(self.drawings.len());
+    let mut footprint_drawings = Vec::new();
+    let drawings = self.drawings.iter().map(|x| (false, x)).chain(
+      self
+        .footprints
+        .iter()
+        .flat_map(|x| &x.drawings)
+        .map(|x| (true, x)),
+    );
+    for (in_footprint, drawing) in drawings {
+      match self.check_drawing(drawing) {
+        Ok(()) if in_footprint => footprint_drawings.push(drawing),
+        Ok(()) => board_drawings.push(drawing),
+        Err(_) if self.strictness == Strictness::Lenient => {}
+        Err(e) => return Err(e),
       }
     }
 
+    Ok(Validated {
+      bom_back,
+      bom_front,
+      bom_both,
+      board_drawings,
+      footprint_drawings,
+    })
+  }
+
+  /// Build the viewer config and data
+  fn build_data(&self) -> Result<(JsonValue, JsonValue), GenerateError> {
+    let Validated {
+      bom_back,
+      bom_front,
+      bom_both,
+      board_drawings,
+      footprint_drawings,
+    } = self.validate()?;
+
     // Calculate some additional data.
-    let mut nets = Vec::new();
     let mut dnp_footprint_ids: Vec<usize> = Vec::new();
     for (index, footprint) in self.footprints.iter().enumerate() {
       if !footprint.mount {
         dnp_footprint_ids.push(index);
       }
-      for pad in &footprint.pads {
-        if let Some(net) = &pad.net {
-          if !nets.contains(net) {
-            nets.push(net.clone());
-          }
-        }
-      }
     }
+    let footprint_nets = self
+      .footprints
+      .iter()
+      .flat_map(|x| x.pads.iter().map(|x| &x.net))
+      .chain(
+        self
+          .footprints
+          .iter()
+          .flat_map(|x| x.drawings.iter().map(|x| &x.net)),
+      );
+    // Sorted to get a stable output independent of the insertion order.
+    let nets = footprint_nets
+      .chain(self.tracks.iter().map(|x| &x.net))
+      .chain(self.vias.iter().map(|x| &x.net))
+      .chain(self.zones.iter().map(|x| &x.net))
+      .chain(self.drawings.iter().map(|x| &x.net))
+      .flatten()
+      .chain(&self.nets)
+      .map(NetId::name)
+      .collect::<BTreeSet<_>>();
 
-    // Auto-detect visibility of front/back sides depending on BOM.
-    let layer_view = if !self.bom_front.is_empty() && self.bom_back.is_empty() {
-      "F"
-    } else if self.bom_front.is_empty() && !self.bom_back.is_empty() {
-      "B"
-    } else {
-      "FB"
+    // Convert dimensions to fabrication layer drawings.
+    let dimension_drawings = self
+      .dimensions
+      .iter()
+      .flat_map(Dimension::drawings)
+      .collect::<Vec<_>>();
+
+    // Collect glyphs of all used characters, sorted for stable output.
+    let chars = board_drawings
+      .iter()
+      .copied()
+      .chain(&dimension_drawings)
+      .chain(footprint_drawings.iter().copied())
+      .filter_map(|x| x.text.as_ref())
+      .flat_map(|x| x.text.chars())
+      .filter(|c| *c != '\n')
+      .collect::<BTreeSet<_>>();
+    let mut font_data = object! {};
+    for c in chars {
+      font_data[c.to_string()] = font::glyph_json(c);
+    }
+
+    // Determine board bounding box.
+    let (bottom_left, top_right) = self.board_bbox();
+
+    // Collect copper drawings, including footprint drawings.
+    let copper = |layer: DrawingLayer| {
+      board_drawings
+        .iter()
+        .chain(&footprint_drawings)
+        .copied()
+        .filter(move |x| x.layer == layer)
     };
 
-    let config = object! {
-        board_rotation: (self.board_rotation / 5.0) as i32,
-        bom_view: self.view_mode.to_json(),
-        checkboxes: self.checkboxes.join(","),
-        dark_mode: self.dark_mode,
-        fields: self.fields.to_json(),
-        highlight_pin1: self.highlight_pin1.to_json(),
-        kicad_text_formatting: false,
-        layer_view: layer_view,
-        offset_back_rotation: self.offset_back_rotation,
-        redraw_on_drag: true,
-        show_fabrication: self.show_fabrication,
-        show_pads: self.show_pads,
-        show_silkscreen: self.show_silkscreen,
+    // Collect drawings of layers not natively supported by the viewer,
+    // including the corresponding footprint drawings.
+    let drawings = |layer: DrawingLayer| {
+      board_drawings
+        .iter()
+        .chain(&footprint_drawings)
+        .copied()
+        .filter(|x| x.layer == layer)
+        .map(ToJson::to_json)
+        .collect::<Vec<_>>()
     };
+    let mut extra_drawings = object! {};
+    for (name, _, _, _, front, back) in self.extra_layers(&self.settings) {
+      let (f, b) = (drawings(front), drawings(back));
+      if !f.is_empty() || !b.is_empty() {
+        extra_drawings[name] = object! { F: f, B: b };
+      }
+    }
 
     let mut data = object! {
       ibom_version: String::from_utf8_lossy(include_bytes!("web/version.txt")).to_string(),
       metadata: object!{
-        title: self.title.clone(),
-        company: self.company.clone(),
-        revision: self.revision.clone(),
-        date: self.date.clone(),
+        title: self.title.as_str(),
+        company: self.company.as_str(),
+        revision: self.revision.as_str(),
+        date: self.date.as_str(),
       },
       edges_bbox: object!{
-        minx: self.bottom_left.0,
-        maxx: self.top_right.0,
-        miny: self.bottom_left.1,
-        maxy: self.top_right.1,
+        minx: bottom_left.0,
+        maxx: top_right.0,
+        miny: bottom_left.1,
+        maxy: top_right.1,
       },
-      edges: self.drawings.iter()
+      edges: board_drawings.iter().copied()
         .filter(|x| x.layer == DrawingLayer::Edge)
-        .map(ToJson::to_json).collect::<Vec<_>>(),
+        .map(ToJson::to_json)
+        .chain(self.outlines.iter().flat_map(BoardOutline::contours_json))
+        .chain(self.holes.iter().map(ToJson::to_json))
+        .collect::<Vec<_>>(),
       drawings: object!{
         silkscreen: object!{
-          F: self.drawings.iter()
+          F: board_drawings.iter().copied()
               .filter(|x| x.layer == DrawingLayer::SilkscreenFront)
               .map(ToJson::to_json).collect::<Vec<_>>(),
-          B: self.drawings.iter()
+          B: board_drawings.iter().copied()
               .filter(|x| x.layer == DrawingLayer::SilkscreenBack)
               .map(ToJson::to_json).collect::<Vec<_>>(),
         },
         fabrication: object!{
-          F: self.drawings.iter()
+          F: board_drawings.iter().copied()
+              .chain(&dimension_drawings)
               .filter(|x| x.layer == DrawingLayer::FabricationFront)
               .map(ToJson::to_json).collect::<Vec<_>>(),
-          B: self.drawings.iter()
+          B: board_drawings.iter().copied()
+              .chain(&dimension_drawings)
               .filter(|x| x.layer == DrawingLayer::FabricationBack)
               .map(ToJson::to_json).collect::<Vec<_>>(),
         },
@@ -856,6 +6016,9 @@ impl InteractiveHtmlBom {
             .chain(self.vias.iter()
               .filter(|x| x.layers.contains(&Layer::Front))
               .map(ToJson::to_json))
+            .chain(self.holes.iter().filter_map(Hole::copper_json))
+            .chain(copper(DrawingLayer::CopperFront)
+              .flat_map(Drawing::tracks_json))
             .collect::<Vec<_>>(),
         B: self.tracks.iter()
             .filter(|x| x.layer == Layer::Back)
@@ -863,89 +6026,882 @@ impl InteractiveHtmlBom {
             .chain(self.vias.iter()
               .filter(|x| x.layers.contains(&Layer::Back))
               .map(ToJson::to_json))
+            .chain(self.holes.iter().filter_map(Hole::copper_json))
+            .chain(copper(DrawingLayer::CopperBack)
+              .flat_map(Drawing::tracks_json))
             .collect::<Vec<_>>(),
       },
       zones: object!{
         F: self.zones.iter()
             .filter(|x| x.layer == Layer::Front)
-            .map(ToJson::to_json).collect::<Vec<_>>(),
+            .map(ToJson::to_json)
+            .chain(copper(DrawingLayer::CopperFront)
+              .filter_map(Drawing::zone_json))
+            .collect::<Vec<_>>(),
         B: self.zones.iter()
             .filter(|x| x.layer == Layer::Back)
-            .map(ToJson::to_json).collect::<Vec<_>>(),
+            .map(ToJson::to_json)
+            .chain(copper(DrawingLayer::CopperBack)
+              .filter_map(Drawing::zone_json))
+            .collect::<Vec<_>>(),
       },
-      nets: nets.to_json(),
+      font_data: font_data,
+      nets: nets.iter().copied().collect::<Vec<_>>(),
       footprints: self.footprints.to_json(),
       bom: object!{
-        F: self.bom_front.to_json(),
-        B: self.bom_back.to_json(),
-        both: self.bom_both.to_json(),
+        F: bom_front.to_json(),
+        B: bom_back.to_json(),
+        both: bom_both.to_json(),
         skipped: dnp_footprint_ids.to_json(),
         fields: object!{},  // Filled below.
       },
     };
 
-    // Fill in footprint fields and check their length.
+    // Add drawings of extra layers.
+    for (name, drawings) in extra_drawings.entries() {
+      data["drawings"][name] = drawings.clone();
+    }
+
+    // Add tooltip fields.
+    let mut tooltips = object! {};
     for (id, fpt) in self.footprints.iter().enumerate() {
-      if fpt.fields.len() != self.fields.len() {
-        return Err("Inconsistent number of fields.".into());
+      if !fpt.tooltip_fields.is_empty() {
+        tooltips[id.to_string()] = fpt
+          .tooltip_fields
+          .iter()
+          .map(|(name, value)| array![name.as_str(), value.as_str()])
+          .collect::<Vec<_>>()
+          .into();
       }
-      data["bom"]["fields"][id.to_string()] = fpt.fields.to_json();
     }
+    data["tooltips"] = tooltips;
 
-    // Build JS variables.
-    let config_str = "var config = ".to_owned() + &config.dump();
-    let pcbdata_str =
-      "var pcbdata = JSON.parse(LZString.decompressFromBase64(\"".to_owned()
-        + &lz_str::compress_to_base64(&data.dump())
-        + "\"))";
+    // Fill in footprint fields and check their length.
+    for id in 0..self.footprints.len() {
+      let fields = self.footprint_fields(id)?;
+      data["bom"]["fields"][id.to_string()] = fields.to_vec().to_json();
+    }
 
-    // Load HTML.
-    let mut html =
-      String::from_utf8_lossy(include_bytes!("web/ibom.html")).to_string();
+    // Reject NaN and infinite numbers since they are not valid JSON.
+    if let Some(path) = find_non_finite(&data, "") {
+      return Err(GenerateError::NonFiniteNumber { path });
+    }
 
-    // Replace placeholders.
-    let replacements = [
-      (
-        "///CSS///",
-        String::from_utf8_lossy(include_bytes!("web/ibom.css")),
-      ),
-      (
-        "///SPLITJS///",
-        String::from_utf8_lossy(include_bytes!("web/split.js")),
-      ),
-      (
-        "///LZ-STRING///",
-        String::from_utf8_lossy(include_bytes!("web/lz-string.js")),
-      ),
+    // Round numbers to avoid floating point noise in the output.
+    round_json(&mut data, self.decimals);
+
+    Ok((self.build_config(&self.settings)?, data))
+  }
+
+  /// Build the viewer config and data, with the data escaped for the HTML
+  fn build_viewer_data(&self) -> Result<(JsonValue, JsonValue), GenerateError> {
+    let (config, mut data) = self.build_data()?;
+    escape_data(&mut data);
+    Ok((config, data))
+  }
+
+  /// List the layers not natively supported by the viewer
+  ///
+  /// These are drawn by the web extensions and listed in the settings menu,
+  /// if they contain any drawings.
+  fn extra_layers<'a>(
+    &'a self,
+    settings: &RenderSettings,
+  ) -> Vec<(String, &'a str, &'a str, bool, DrawingLayer, DrawingLayer)> {
+    let mut extra_layers = vec![
       (
-        "///POINTER_EVENTS_POLYFILL///",
-        String::from_utf8_lossy(include_bytes!("web/pep.js")),
+        "paste".to_owned(),
+        "Solder paste",
+        "rgba(160, 160, 160, 0.8)",
+        settings.show_paste,
+        DrawingLayer::PasteFront,
+        DrawingLayer::PasteBack,
       ),
       (
-        "///UTILJS///",
-        String::from_utf8_lossy(include_bytes!("web/util.js")),
+        "mask".to_owned(),
+        "Solder mask",
+        "rgba(0, 160, 80, 0.5)",
+        settings.show_mask,
+        DrawingLayer::MaskFront,
+        DrawingLayer::MaskBack,
       ),
+    ];
+    for (id, layer) in self.aux_layers.iter().enumerate() {
+      extra_layers.push((
+        format!("aux{}", id),
+        &layer.title,
+        &layer.color,
+        layer.visible,
+        DrawingLayer::AuxiliaryFront(id),
+        DrawingLayer::AuxiliaryBack(id),
+      ));
+    }
+    extra_layers
+  }
+
+  /// Build the viewer config for the given settings
+  ///
+  /// Kept separate from the viewer data, which does not depend on the
+  /// settings and thus can be shared by multiple documents.
+  fn build_config(
+    &self,
+    settings: &RenderSettings,
+  ) -> Result<JsonValue, GenerateError> {
+    // Visibility of front/back sides, auto-detected depending on BOM if not
+    // specified.
+    let layer_view = if let Some(layer_view) = &settings.layer_view {
+      layer_view.clone()
+    } else if !self.bom_front.is_empty() && self.bom_back.is_empty() {
+      LayerView::Front
+    } else if self.bom_front.is_empty() && !self.bom_back.is_empty() {
+      LayerView::Back
+    } else {
+      LayerView::FrontAndBack
+    };
+
+    // Extra layers are listed in the settings menu, if not empty.
+    let mut extra_layers_config = array![];
+    for (name, title, color, visible, front, back) in
+      self.extra_layers(settings)
+    {
+      if self
+        .drawings
+        .iter()
+        .chain(self.footprints.iter().flat_map(|x| x.drawings.iter()))
+        .any(|x| x.layer == front || x.layer == back)
+      {
+        extra_layers_config
+          .push(object! {
+            name: name,
+            title: title,
+            color: color,
+            visible: visible,
+          })
+          .unwrap();
+      }
+    }
+
+    // Initial checkbox states as comma-separated footprint IDs.
+    let mut checkbox_defaults = object! {};
+    for checkbox in &self.checkboxes {
+      let ids = self
+        .footprints
+        .iter()
+        .enumerate()
+        .filter(|(_, x)| x.checked.contains(checkbox))
+        .map(|(id, _)| id.to_string())
+        .collect::<Vec<_>>();
+      if !ids.is_empty() {
+        checkbox_defaults[escape_html(checkbox)] = ids.join(",").into();
+      }
+    }
+
+    // Checked here since the theme is inserted into the stylesheet.
+    settings.theme.validate()?;
+
+    // Checked here since it is converted to an integer below.
+    if !settings.board_rotation.is_finite() {
+      return Err(GenerateError::NonFiniteNumber {
+        path: "config.board_rotation".into(),
+      });
+    }
+    let mut config = object! {
+        board_rotation: (settings.board_rotation / 5.0) as i32,
+        bom_view: settings.view_mode.to_json(),
+        checkbox_defaults: checkbox_defaults,
+        checkboxes: escape_html(&self.checkboxes.join(",")),
+        dark_mode: settings.dark_mode,
+        favicon: self.favicon.as_ref().map(Image::data_url),
+        extra_layers: extra_layers_config,
+        fields: escape_html_json(&self.fields),
+        group_fields: escape_html_json(&self.group_fields),
+        hidden_columns: escape_html_json(&settings.hidden_columns),
+        filter: settings.filter.clone(),
+        highlight_pin1: settings.highlight_pin1.to_json(),
+        images: self.images.iter().map(|(title, image)| object!{
+          title: title.as_str(),
+          src: image.data_url(),
+        }).collect::<Vec<_>>(),
+        kicad_text_formatting: settings.kicad_text_formatting,
+        layer_view: layer_view.to_json(),
+        logo: self.logo.as_ref().map(Image::data_url),
+        offset_back_rotation: settings.offset_back_rotation,
+        page_title: self.page_title.clone(),
+        redraw_on_drag: settings.redraw_on_drag,
+        ref_lookup: settings.ref_lookup.clone(),
+        schematic_sheets: self.schematic_sheets_json(),
+        show_fabrication: settings.show_fabrication,
+        show_pads: settings.show_pads,
+        show_silkscreen: settings.show_silkscreen,
+        sort_column: settings.sort_column.as_deref().map(escape_html),
+        sort_order: settings.sort_order.to_json(),
+        view_3d: self.view_3d_json(),
+    };
+    for (key, value) in &settings.extra_config {
+      match jzon::parse(value) {
+        Ok(value) => config[key.as_str()] = value,
+        Err(_) if self.strictness == Strictness::Lenient => {}
+        Err(_) => {
+          return Err(GenerateError::InvalidExtraConfig { key: key.clone() })
+        }
+      }
+    }
+
+    // Reject NaN and infinite numbers since they are not valid JSON.
+    if let Some(path) = find_non_finite(&config, "config") {
+      return Err(GenerateError::NonFiniteNumber { path });
+    }
+    Ok(config)
+  }
+
+  /// Generate the viewer data as JSON
+  ///
+  /// This is the `pcbdata` structure embedded (compressed) into the HTML,
+  /// e.g. for diffing or for custom viewers. Its format is defined by the
+  /// viewer and thus may change with any release. Unlike in the HTML, texts
+  /// are not HTML-escaped.
+  ///
+  /// # Returns
+  ///
+  /// Returns the JSON document.
+  pub fn generate_pcbdata_json(&self) -> Result<String, GenerateError> {
+    Ok(self.build_data()?.1.dump())
+  }
+
+  /// Get a bundled viewer asset, minified if desired
+  fn asset(&self, name: &str) -> Cow<'static, str> {
+    let code = ASSETS
+      .iter()
+      .find(|(x, _)| *x == name)
+      .map(|(_, code)| String::from_utf8_lossy(code))
+      .unwrap_or_default();
+    match (self.minify, name.ends_with(".css")) {
+      (true, true) => minify::css(&code).into(),
+      (true, false) => minify::js(&code).into(),
+      (false, _) => code,
+    }
+  }
+
+  /// Generate the viewer assets
+  ///
+  /// These are the files to be served at [InteractiveHtmlBom::assets_url].
+  /// They only depend on the crate version and
+  /// [InteractiveHtmlBom::minify], not on the board.
+  ///
+  /// # Returns
+  ///
+  /// Returns pairs of file name (e.g. `"ibom.js"`) and file content.
+  pub fn generate_assets(&self) -> Vec<(&'static str, String)> {
+    ASSETS
+      .iter()
+      .map(|(name, _)| (*name, self.asset(name).into_owned()))
+      .collect()
+  }
+
+  /// Generate the viewer data as JavaScript
+  ///
+  /// This is the script to be served at [InteractiveHtmlBom::pcbdata_url].
+  ///
+  /// # Returns
+  ///
+  /// Returns the JavaScript document.
+  pub fn generate_pcbdata_js(&self) -> Result<String, GenerateError> {
+    let data = self.build_viewer_data()?.1;
+    Ok(pcbdata_js(&data, &self.data_encoding).concat())
+  }
+
+  /// Generate the viewer config as JSON
+  ///
+  /// This is the `config` object embedded into the HTML, i.e. the settings
+  /// the BOM was generated with. Like the data, its format is defined by
+  /// the viewer.
+  ///
+  /// # Returns
+  ///
+  /// Returns the JSON document.
+  pub fn generate_config_json(&self) -> Result<String, GenerateError> {
+    Ok(self.build_data()?.0.dump())
+  }
+
+  /// Generate the "generic JSON" input format of the upstream project
+  ///
+  /// This allows feeding the board through the original Python
+  /// [InteractiveHtmlBom][upstream] tool, e.g. to use newer viewer features.
+  /// The BOM rows are not exported since the upstream tool groups components
+  /// itself, based on the `Value` and `Footprint` fields. Other fields are
+  /// exported as extra fields.
+  ///
+  /// [upstream]: https://github.com/openscopeproject/InteractiveHtmlBom
+  ///
+  /// # Returns
+  ///
+  /// Returns the JSON document.
+  pub fn generate_generic_json(&self) -> Result<String, GenerateError> {
+    let mut data = self.build_data()?.1;
+
+    // Remove data generated by the upstream tool itself.
+    for key in ["ibom_version", "bom", "tooltips"] {
+      data.remove(key);
+    }
+    data["drawings"] = object! {
+      silkscreen: data["drawings"]["silkscreen"].take(),
+      fabrication: data["drawings"]["fabrication"].take(),
+    };
+
+    // Determine the reference of each footprint.
+    let mut refs = vec![""; self.footprints.len()];
+    for map in self
+      .valid_rows(&self.bom_both)?
+      .into_iter()
+      .chain(self.valid_rows(&self.bom_front)?)
+      .chain(self.valid_rows(&self.bom_back)?)
+      .flatten()
+    {
+      if refs[map.footprint_id].is_empty() {
+        refs[map.footprint_id] = &map.reference;
+      }
+    }
+
+    let field = |fields: &[String], name: &str| {
+      self
+        .fields
+        .iter()
+        .position(|x| x == name)
+        .map(|i| fields[i].clone())
+        .unwrap_or_default()
+    };
+    let mut components = array![];
+    for (id, fpt) in self.footprints.iter().enumerate() {
+      let fields = self.footprint_fields(id)?;
+      let reference = refs[id].to_string();
+      data["footprints"][id]["ref"] = reference.as_str().into();
+      data["footprints"][id]["center"] = fpt.pos.to_json();
+      let mut extra_fields = object! {};
+      for (name, value) in self.fields.iter().zip(fields.iter()) {
+        if name != "Value" && name != "Footprint" {
+          extra_fields[name.as_str()] = value.as_str().into();
+        }
+      }
+      let mut component = object! {
+        "ref": reference,
+        val: field(&fields, "Value"),
+        footprint: field(&fields, "Footprint"),
+        layer: fpt.layer.to_json(),
+        extra_fields: extra_fields,
+      };
+      if !fpt.mount {
+        component["attr"] = "Virtual".into();
+      }
+      components.push(component).unwrap();
+    }
+
+    let mut json = object! {
+      spec_version: 1,
+      pcbdata: data,
+      components: components,
+    };
+    round_json(&mut json, self.decimals);
+    Ok(json.dump())
+  }
+
+  /// Generate a printable PDF of the BOM
+  ///
+  /// Lists the rows of [InteractiveHtmlBom::bom_both] with their quantity,
+  /// references and [InteractiveHtmlBom::fields] as a plain table, e.g. for
+  /// manufacturing partners needing a non-interactive document. Optionally
+  /// each board side is drawn on an additional page, showing the board
+  /// outline, silkscreen, footprint bounding boxes and references. Text is
+  /// limited to Latin-1 characters, others are replaced by "?".
+  ///
+  /// # Arguments
+  ///
+  /// * `boards` - Board sides to draw after the table, e.g.
+  ///   `&[Layer::Front, Layer::Back]`, or empty for the table only.
+  ///
+  /// # Returns
+  ///
+  /// Returns the PDF document.
+  #[cfg(feature = "pdf")]
+  pub fn generate_pdf(
+    &self,
+    boards: &[Layer],
+  ) -> Result<Vec<u8>, GenerateError> {
+    // Validate the input the same way as for the HTML output.
+    let valid = self.validate()?;
+
+    let subtitle: Vec<String> = [
+      ("Revision", &self.revision),
+      ("Company", &self.company),
+      ("Date", &self.date),
+    ]
+    .iter()
+    .filter(|(_, value)| !value.is_empty())
+    .map(|(name, value)| format!("{}: {}", name, value))
+    .collect();
+    let header: Vec<String> = ["#", "Qty", "References"]
+      .iter()
+      .map(|x| x.to_string())
+      .chain(self.fields.iter().cloned())
+      .collect();
+    let rows = valid
+      .bom_both
+      .iter()
+      .enumerate()
+      .map(|(i, row)| {
+        let refs = row
+          .iter()
+          .map(|x| x.reference.as_str())
+          .collect::<Vec<_>>()
+          .join(", ");
+        let fields = self.footprint_fields(row[0].footprint_id)?;
+        Ok(
+          [(i + 1).to_string(), row.len().to_string(), refs]
+            .into_iter()
+            .chain(fields.iter().cloned())
+            .collect(),
+        )
+      })
+      .collect::<Result<Vec<Vec<String>>, GenerateError>>()?;
+    let boards = boards
+      .iter()
+      .map(|layer| self.pdf_board(&valid, layer))
+      .collect::<Vec<_>>();
+    Ok(pdf::document(
+      &self.title,
+      &subtitle,
+      &header,
+      &rows,
+      &boards,
+    ))
+  }
+
+  /// Collect the drawings of a board side for a PDF page
+  #[cfg(feature = "pdf")]
+  fn pdf_board(&self, valid: &Validated, layer: &Layer) -> pdf::Board {
+    let (title, silkscreen) = match layer {
+      Layer::Front => ("Front", DrawingLayer::SilkscreenFront),
+      Layer::Back => ("Back", DrawingLayer::SilkscreenBack),
+    };
+
+    // Board outlines, edge and silkscreen drawings. Texts are not drawn.
+    let contours = self.outlines.iter().flat_map(|x| {
+      std::iter::once(&x.outline)
+        .chain(&x.cutouts)
+        .map(|path| (path.as_str(), x.width))
+    });
+    let drawings = valid
+      .board_drawings
+      .iter()
+      .chain(&valid.footprint_drawings)
+      .filter(|x| x.layer == DrawingLayer::Edge || x.layer == silkscreen)
+      .map(|x| (x.svgpath.as_str(), x.width));
+    let mut lines = Vec::new();
+    for (path, width) in contours.chain(drawings) {
+      for polyline in svgpath::flatten(path).unwrap_or_default() {
+        lines.push((polyline, width));
+      }
+    }
+
+    // Footprint bounding boxes.
+    for fpt in self.footprints.iter().filter(|x| x.layer == *layer) {
+      let ((l, b), (r, t)) = (fpt.bottom_left, fpt.top_right);
+      let points = [(l, b), (r, b), (r, t), (l, t)]
+        .into_iter()
+        .map(|p| rotate(p, fpt.angle))
+        .map(|(x, y)| (fpt.pos.0 + x, fpt.pos.1 + y))
+        .collect();
+      let polyline = svgpath::Polyline {
+        points,
+        closed: true,
+      };
+      lines.push((polyline, 0.0));
+    }
+
+    // References at the footprint positions.
+    let labels = valid
+      .bom_both
+      .iter()
+      .flatten()
+      .map(|x| (&self.footprints[x.footprint_id], &x.reference))
+      .filter(|(fpt, _)| fpt.layer == *layer)
+      .map(|(fpt, reference)| (fpt.pos, reference.clone()))
+      .collect();
+
+    pdf::Board {
+      title: format!("{} ({})", self.title, title),
+      bbox: self.board_bbox(),
+      mirrored: *layer == Layer::Back,
+      lines,
+      labels,
+    }
+  }
+
+  /// Generate HTML
+  ///
+  /// The output is reproducible, i.e. identical inputs always result in a
+  /// byte-identical document with the same build of this crate on the same
+  /// platform, independent of the environment and the time of generation.
+  /// For example nets and font glyphs are sorted, and numbers are rounded
+  /// to [InteractiveHtmlBom::decimals] places. This allows caching and
+  /// verifying generated documents. Across platforms, the output may still
+  /// differ in the last decimal place since trigonometric functions (e.g.
+  /// for rotations and arcs) are provided by the platform.
+  pub fn generate_html(&self) -> Result<String, GenerateError> {
+    let mut html = Vec::new();
+    self.generate_html_to(&mut html)?;
+    html_string(html)
+  }
+
+  /// Generate HTML into a writer
+  ///
+  /// Same as [InteractiveHtmlBom::generate_html], but streams the document
+  /// instead of building it in memory.
+  ///
+  /// # Arguments
+  ///
+  /// * `w` - Writer to write the HTML document to.
+  pub fn generate_html_to(
+    &self,
+    w: &mut impl std::io::Write,
+  ) -> Result<(), GenerateError> {
+    let (config, data) = self.build_viewer_data()?;
+    let pcbdata = match &self.pcbdata_url {
+      Some(_) => Vec::new(),
+      None => pcbdata_js(&data, &self.data_encoding),
+    };
+    self.write_html(w, &self.settings, &config, &pcbdata)
+  }
+
+  /// Generate HTML for several viewer settings at once
+  ///
+  /// Same as [InteractiveHtmlBom::generate_html] for each of the given
+  /// settings, e.g. for dark and light mode or for internal and
+  /// customer-facing documents. The viewer data is identical for all of
+  /// them, thus it is built, encoded and compressed only once.
+  ///
+  /// # Arguments
+  ///
+  /// * `variants` - Viewer settings to generate documents for, replacing
+  ///   [InteractiveHtmlBom::settings].
+  ///
+  /// # Returns
+  ///
+  /// Returns the HTML documents in the order of `variants`.
+  pub fn generate_html_variants(
+    &self,
+    variants: &[RenderSettings],
+  ) -> Result<Vec<String>, GenerateError> {
+    let data = self.build_viewer_data()?.1;
+    let pcbdata = match &self.pcbdata_url {
+      Some(_) => Vec::new(),
+      None => pcbdata_js(&data, &self.data_encoding),
+    };
+    variants
+      .iter()
+      .map(|settings| {
+        let config = self.build_config(settings)?;
+        let mut html = Vec::new();
+        self.write_html(&mut html, settings, &config, &pcbdata)?;
+        html_string(html)
+      })
+      .collect()
+  }
+
+  /// Write the HTML document with the given viewer configuration and data
+  ///
+  /// The viewer data is ignored if [InteractiveHtmlBom::pcbdata_url] is set.
+  fn write_html(
+    &self,
+    w: &mut impl std::io::Write,
+    settings: &RenderSettings,
+    config: &JsonValue,
+    pcbdata: &[Cow<'static, str>],
+  ) -> Result<(), GenerateError> {
+    // Build user-defined HTML, escaped if it is untrusted.
+    let (user_header, user_footer_html) = if self.sanitize_user_content {
+      if !self.user_js.is_empty() {
+        return Err(GenerateError::UntrustedUserJs);
+      }
       (
-        "///RENDERJS///",
-        String::from_utf8_lossy(include_bytes!("web/render.js")),
-      ),
+        Cow::Owned(escape_html(&self.user_header)),
+        Cow::Owned(escape_html(&self.user_footer)),
+      )
+    } else {
       (
-        "///TABLEUTILJS///",
-        String::from_utf8_lossy(include_bytes!("web/table-util.js")),
-      ),
+        Cow::Borrowed(self.user_header.as_str()),
+        Cow::Borrowed(self.user_footer.as_str()),
+      )
+    };
+
+    // Build HTML footer.
+    let mut user_footer = String::new();
+    if self.drill_table {
+      user_footer += &self.drill_table_html();
+    }
+    user_footer += &user_footer_html;
+
+    // Load HTML.
+    let nonce = self.nonce_attribute();
+    let (html, handlers_js) = self.html_template();
+
+    // Inline bundled code or reference it externally.
+    let asset = |name: &str| -> Cow<str> {
+      match &self.assets_url {
+        Some(url) if name.ends_with(".css") => {
+          external_style(&format!("{}{}", url, name), &nonce).into()
+        }
+        Some(url) => {
+          external_script(&format!("{}{}", url, name), &nonce).into()
+        }
+        None => self.asset(name),
+      }
+    };
+
+    // Build JS variables.
+    let user_js = vec![
+      asset("ibom-ext.js"),
+      "\n".into(),
+      handlers_js.as_str().into(),
+      self.user_js.as_str().into(),
+    ];
+    let pcbdata = match &self.pcbdata_url {
+      Some(url) => vec![external_script(url, &nonce).into()],
+      None => pcbdata.iter().map(|x| x.as_ref().into()).collect(),
+    };
+
+    // Write HTML, replacing placeholders.
+    let theme_css = settings.theme.css();
+    let mut replacements: Vec<(&str, Vec<Cow<str>>)> = vec![
+      ("///CSS///", vec![asset("ibom.css")]),
+      ("///USERCSS///", vec![theme_css.as_str().into()]),
+      ("///SPLITJS///", vec![asset("split.js")]),
+      ("///LZ-STRING///", vec![asset("lz-string.js")]),
+      ("///POINTER_EVENTS_POLYFILL///", vec![asset("pep.js")]),
+      ("///UTILJS///", vec![asset("util.js")]),
+      ("///RENDERJS///", vec![asset("render.js")]),
+      ("///TABLEUTILJS///", vec![asset("table-util.js")]),
+      ("///IBOMJS///", vec![asset("ibom.js")]),
       (
-        "///IBOMJS///",
-        String::from_utf8_lossy(include_bytes!("web/ibom.js")),
+        "///CONFIG///",
+        vec![
+          "var config = ".into(),
+          // Avoid terminating the script element with "</script>" in strings.
+          config.dump().replace('<', "\\u003c").into(),
+        ],
       ),
-      ("///CONFIG///", config_str.as_str().into()),
-      ("///PCBDATA///", pcbdata_str.as_str().into()),
-      ("///USERJS///", self.user_js.as_str().into()),
-      ("///USERHEADER///", self.user_header.as_str().into()),
-      ("///USERFOOTER///", self.user_footer.as_str().into()),
+      ("///PCBDATA///", pcbdata),
+      ("///USERJS///", user_js),
+      ("///USERHEADER///", vec![user_header]),
+      ("///USERFOOTER///", vec![user_footer.as_str().into()]),
     ];
-    for replacement in &replacements {
-      html = html.replace(replacement.0, &replacement.1);
+    for (placeholder, value) in &self.placeholders {
+      if placeholder.is_empty() {
+        return Err(GenerateError::EmptyPlaceholder);
+      }
+      let value = match self.sanitize_user_content {
+        true => Cow::Owned(escape_html(value)),
+        false => Cow::Borrowed(value.as_str()),
+      };
+      replacements.push((placeholder, vec![value]));
+    }
+    let placeholders: Vec<&str> = replacements.iter().map(|x| x.0).collect();
+    debug_assert!(placeholders.starts_with(&TEMPLATE_PLACEHOLDERS));
+    let (parts, tail) = split_template(&html, &placeholders);
+    for (text, index) in parts {
+      w.write_all(text.as_bytes())?;
+      for value in &replacements[index].1 {
+        w.write_all(value.as_bytes())?;
+      }
+    }
+    w.write_all(tail.as_bytes())?;
+    Ok(())
+  }
+
+  /// Get the nonce attribute of script and style elements
+  ///
+  /// Returns an empty string if [InteractiveHtmlBom::csp_nonce] is not set.
+  fn nonce_attribute(&self) -> String {
+    match &self.csp_nonce {
+      Some(nonce) => format!(" nonce=\"{}\"", escape_html(nonce)),
+      None => String::new(),
+    }
+  }
+
+  /// Load the HTML template
+  ///
+  /// The template is adjusted to [InteractiveHtmlBom::minify] and
+  /// [InteractiveHtmlBom::csp_nonce].
+  ///
+  /// # Returns
+  ///
+  /// Returns the template and the script registering the event handlers
+  /// removed from it, see [csp_template].
+  fn html_template(&self) -> (Cow<'_, str>, String) {
+    let mut html = match &self.template {
+      Some(template) => Cow::Borrowed(template.as_str()),
+      None => String::from_utf8_lossy(include_bytes!("web/ibom.html")),
+    };
+    if self.minify {
+      // Remove the separator comments around placeholders.
+      html = html
+        .lines()
+        .filter(|line| line.len() < 4 || line.chars().any(|c| c != '/'))
+        .collect::<Vec<_>>()
+        .join("\n")
+        .into();
+    }
+    let nonce = self.nonce_attribute();
+    if nonce.is_empty() {
+      return (html, String::new());
+    }
+    let (template, js) = csp_template(&html, &nonce);
+    (template.into(), js)
+  }
+
+  /// Verify a generated HTML document
+  ///
+  /// Checks that the document matches the template with all placeholders
+  /// (including [InteractiveHtmlBom::placeholders]) replaced, that the
+  /// viewer configuration is valid JSON, and that the embedded viewer data
+  /// can be decoded and matches the data generated from this object. Meant
+  /// to catch template or asset regressions before documents are shipped.
+  /// The viewer data is not checked if [InteractiveHtmlBom::pcbdata_url] is
+  /// set, and the configuration may differ (e.g. for documents of
+  /// [InteractiveHtmlBom::generate_html_variants]).
+  ///
+  /// # Arguments
+  ///
+  /// * `html` - Document returned by [InteractiveHtmlBom::generate_html].
+  ///
+  /// # Returns
+  ///
+  /// Returns [GenerateError::InvalidOutput] if the document is invalid.
+  pub fn verify_html(&self, html: &str) -> Result<(), GenerateError> {
+    let invalid = |reason: String| GenerateError::InvalidOutput { reason };
+    let mismatch = || invalid("document does not match the template".into());
+
+    // Determine the values of the placeholders by matching the texts of the
+    // template in between, so user content and data are not inspected.
+    let (template, _) = self.html_template();
+    let placeholders: Vec<&str> = TEMPLATE_PLACEHOLDERS
+      .into_iter()
+      .chain(self.placeholders.iter().map(|x| x.0.as_str()))
+      .collect();
+    let (parts, tail) = split_template(&template, &placeholders);
+    let mut values = vec![None; placeholders.len()];
+    let mut rest = html;
+    for (i, (text, index)) in parts.iter().enumerate() {
+      rest = rest.strip_prefix(text).ok_or_else(mismatch)?;
+      let end = match parts.get(i + 1) {
+        Some((next, _)) => rest.find(next),
+        None => rest.strip_suffix(tail).map(str::len),
+      }
+      .ok_or_else(mismatch)?;
+      let value = &rest[..end];
+      if value == placeholders[*index] {
+        return Err(invalid(format!("placeholder {} not replaced", value)));
+      }
+      values[*index].get_or_insert(value);
+      rest = &rest[end..];
+    }
+    if rest != tail {
+      return Err(mismatch());
+    }
+
+    // Get the JavaScript value assigned to a variable by a placeholder.
+    let value = |placeholder: &str, name: &str| {
+      let index = placeholders.iter().position(|x| *x == placeholder)?;
+      let code = values[index]?;
+      let start = code.find(&format!("var {} = ", name))? + name.len() + 7;
+      let rest = &code[start..];
+      Some(&rest[..rest.find('\n').unwrap_or(rest.len())])
+    };
+    let config = value("///CONFIG///", "config")
+      .ok_or(invalid("config missing".into()))?;
+    jzon::parse(config).map_err(|_| invalid("config invalid".into()))?;
+    if self.pcbdata_url.is_some() {
+      return Ok(());
     }
-    Ok(html)
+
+    let pcbdata = value("///PCBDATA///", "pcbdata")
+      .ok_or(invalid("data missing".into()))?;
+    let string = |prefix: &str| {
+      let rest = pcbdata.strip_prefix(prefix)?;
+      Some(&rest[..rest.find('"')?])
+    };
+    let data = if let Some(text) =
+      string("JSON.parse(LZString.decompressFromBase64(\"")
+    {
+      lz_str::decompress_from_base64(text)
+        .and_then(|x| String::from_utf16(&x).ok())
+        .and_then(|x| jzon::parse(&x).ok())
+    } else if let Some(text) = string("JSON.parse(IbomInflate.inflateBase64(\"")
+    {
+      base64_decode(text)
+        .and_then(|x| miniz_oxide::inflate::decompress_to_vec(&x).ok())
+        .and_then(|x| String::from_utf8(x).ok())
+        .and_then(|x| jzon::parse(&x).ok())
+    } else if let Some(text) =
+      string("IbomCbor.decode(IbomInflate.inflateBase64Bytes(\"")
+    {
+      base64_decode(text)
+        .and_then(|x| miniz_oxide::inflate::decompress_to_vec(&x).ok())
+        .and_then(|x| cbor::decode(&x))
+    } else {
+      jzon::parse(pcbdata).ok()
+    };
+    let data = data.ok_or(invalid("data cannot be decoded".into()))?;
+    if data.dump() != self.build_viewer_data()?.1.dump() {
+      return Err(invalid("data differs".into()));
+    }
+    Ok(())
+  }
+
+  /// Generate HTML and write it to a file
+  ///
+  /// The document is written to a temporary file in the same directory
+  /// first, which then atomically replaces the target file. Thus readers
+  /// never observe a partially written file. The temporary file name is
+  /// unique per process and call, so concurrent calls don't interfere.
+  ///
+  /// # Arguments
+  ///
+  /// * `path` - Path of the HTML file to write.
+  pub fn write_html_file(
+    &self,
+    path: impl AsRef<Path>,
+  ) -> Result<(), GenerateError> {
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+    let path = path.as_ref();
+    let mut tmp_name = OsString::from(".");
+    tmp_name.push(path.file_name().unwrap_or_default());
+    tmp_name.push(format!(
+      ".{}-{}.tmp",
+      std::process::id(),
+      COUNTER.fetch_add(1, Ordering::Relaxed)
+    ));
+    let tmp_path = path.with_file_name(tmp_name);
+    let write = || -> Result<(), GenerateError> {
+      let file = OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(&tmp_path)?;
+      let mut w = BufWriter::new(file);
+      self.generate_html_to(&mut w)?;
+      w.into_inner().map_err(|e| e.into_error())?.sync_all()?;
+      fs::rename(&tmp_path, path)?;
+      Ok(())
+    };
+    let result = write();
+    if result.is_err() {
+      let _ = fs::remove_file(&tmp_path);
+    }
+    result
+  }
+}
+
+impl Default for InteractiveHtmlBom {
+  fn default() -> Self {
+    InteractiveHtmlBom::new("", "", "", "", (0.0, 0.0), (0.0, 0.0))
   }
 }
@@ -0,0 +1,797 @@
+//! Reader for LibrePCB projects
+//!
+//! Reads the S-expression files of a project directory as written by
+//! LibrePCB 1.x. The library elements are taken from the project's library,
+//! which contains all elements used in the project. The Y axis is mirrored to
+//! point downwards.
+
+use crate::{
+  BoardOutline, Drawing, DrawingKind, DrawingLayer, Footprint, GenerateError,
+  Hole, InputFormat, InteractiveHtmlBom, Layer, NetId, Pad, PadCorner,
+  PadShape, RefMap, SvgPathBuilder, Track, Via,
+};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// Unit prefixes of attribute units, with their symbols
+const UNIT_PREFIXES: [(&str, &str); 7] = [
+  ("pico", "p"),
+  ("nano", "n"),
+  ("micro", "μ"),
+  ("milli", "m"),
+  ("kilo", "k"),
+  ("mega", "M"),
+  ("giga", "G"),
+];
+
+/// Base units of attribute units, with their symbols
+const UNIT_BASES: [(&str, &str); 7] = [
+  ("ohm", "Ω"),
+  ("farad", "F"),
+  ("henry", "H"),
+  ("volt", "V"),
+  ("ampere", "A"),
+  ("watt", "W"),
+  ("hertz", "Hz"),
+];
+
+/// Approximate text width per character, relative to the text height
+const CHAR_WIDTH: f64 = 0.8;
+
+/// S-expression list
+#[derive(Debug, Default)]
+struct Node {
+  name: String,
+  values: Vec<String>,
+  children: Vec<Node>,
+}
+
+impl Node {
+  /// First value, e.g. the UUID of an object
+  fn value(&self) -> &str {
+    self.values.first().map_or("", |x| x.as_str())
+  }
+
+  fn child(&self, name: &str) -> Option<&Node> {
+    self.children.iter().find(|x| x.name == name)
+  }
+
+  fn children<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a Node> {
+    self.children.iter().filter(move |x| x.name == name)
+  }
+
+  /// First value of a child, or an empty string if there is no such child
+  fn text(&self, name: &str) -> &str {
+    self.child(name).map_or("", |x| x.value())
+  }
+
+  fn number(&self, name: &str) -> Result<f64, String> {
+    let value = self.text(name);
+    value
+      .parse()
+      .map_err(|_| format!("invalid {} \"{}\" of {}", name, value, self.name))
+  }
+
+  fn point(&self, name: &str) -> Result<(f64, f64), String> {
+    let invalid = || format!("invalid {} of {}", name, self.name);
+    let node = self.child(name).ok_or_else(invalid)?;
+    let number = |i: usize| {
+      let value = node.values.get(i).map_or("", |x| x.as_str());
+      value.parse::<f64>().map_err(|_| invalid())
+    };
+    Ok((number(0)?, number(1)?))
+  }
+
+  fn flag(&self, name: &str) -> bool {
+    self.text(name) == "true"
+  }
+}
+
+/// Parse an S-expression file
+fn parse(text: &str) -> Result<Node, String> {
+  let mut stack: Vec<Node> = Vec::new();
+  let mut chars = text.char_indices().peekable();
+  let line = |pos: usize| text[..pos].matches('\n').count() + 1;
+  while let Some((pos, c)) = chars.next() {
+    match c {
+      '(' => {
+        let mut node = Node::default();
+        while let Some((_, c)) =
+          chars.next_if(|(_, c)| !c.is_whitespace() && !"()\"".contains(*c))
+        {
+          node.name.push(c);
+        }
+        if node.name.is_empty() {
+          return Err(format!("missing name at line {}", line(pos)));
+        }
+        stack.push(node);
+      }
+      ')' => {
+        let node = stack
+          .pop()
+          .ok_or_else(|| format!("unexpected ')' at line {}", line(pos)))?;
+        match stack.last_mut() {
+          Some(parent) => parent.children.push(node),
+          None if text[pos + 1..].trim().is_empty() => return Ok(node),
+          None => {
+            return Err(format!("unexpected content at line {}", line(pos)))
+          }
+        }
+      }
+      c if c.is_whitespace() => {}
+      _ => {
+        let node = stack
+          .last_mut()
+          .ok_or_else(|| format!("unexpected content at line {}", line(pos)))?;
+        let mut value = String::new();
+        if c == '"' {
+          loop {
+            match chars.next() {
+              Some((_, '"')) => break,
+              Some((_, '\\')) => match chars.next() {
+                Some((_, 'n')) => value.push('\n'),
+                Some((_, 't')) => value.push('\t'),
+                Some((_, c)) => value.push(c),
+                None => break,
+              },
+              Some((_, c)) => value.push(c),
+              None => {
+                return Err(format!(
+                  "unterminated string at line {}",
+                  line(pos)
+                ))
+              }
+            }
+          }
+        } else {
+          value.push(c);
+          while let Some((_, c)) =
+            chars.next_if(|(_, c)| !c.is_whitespace() && !"()".contains(*c))
+          {
+            value.push(c);
+          }
+        }
+        node.values.push(value);
+      }
+    }
+  }
+  Err("unexpected end of file".into())
+}
+
+/// Project directory with loaded files
+struct Project<'a> {
+  dir: &'a Path,
+  files: BTreeMap<String, Node>,
+}
+
+impl Project<'_> {
+  /// Load a file relative to the project directory, if not loaded yet
+  fn load(&mut self, path: &str) -> Result<(), GenerateError> {
+    if !self.files.contains_key(path) {
+      let text = std::fs::read_to_string(self.dir.join(path))?;
+      let node = parse(&text).map_err(|reason| invalid(path, reason))?;
+      self.files.insert(path.to_owned(), node);
+    }
+    Ok(())
+  }
+
+  fn file(&self, path: &str) -> &Node {
+    &self.files[path]
+  }
+}
+
+fn invalid(path: &str, reason: String) -> GenerateError {
+  GenerateError::InvalidInput {
+    format: InputFormat::LibrePcbProject,
+    reason: format!("{}: {}", path, reason),
+  }
+}
+
+/// Placement of a device
+struct Transform {
+  pos: (f64, f64),
+  rotation: f64,
+  flip: bool,
+}
+
+impl Transform {
+  /// Map a footprint coordinate to a board coordinate
+  fn map(&self, p: (f64, f64)) -> (f64, f64) {
+    let x = if self.flip { -p.0 } else { p.0 };
+    let (sin, cos) = self.rotation.to_radians().sin_cos();
+    let (x, y) = (x * cos - p.1 * sin, x * sin + p.1 * cos);
+    (self.pos.0 + x + 0.0, -(self.pos.1 + y) + 0.0)
+  }
+
+  /// Map a footprint coordinate to a footprint-local viewer coordinate
+  fn local(&self, p: (f64, f64)) -> (f64, f64) {
+    (if self.flip { -p.0 } else { p.0 } + 0.0, -p.1 + 0.0)
+  }
+
+  /// Map an arc angle of a footprint path
+  fn arc(&self, angle: f64) -> f64 {
+    if self.flip {
+      angle
+    } else {
+      -angle
+    }
+  }
+
+  /// Map a footprint layer name to the board side
+  fn layer(&self, name: &str) -> Option<Layer> {
+    match (name.split('_').next()?, self.flip) {
+      ("top", false) | ("bot", true) => Some(Layer::Front),
+      ("bot", false) | ("top", true) => Some(Layer::Back),
+      _ => None,
+    }
+  }
+}
+
+/// Map a board layer name to a drawing layer
+fn drawing_layer(layer: &str) -> Option<DrawingLayer> {
+  match layer {
+    "top_legend" | "top_names" | "top_values" => {
+      Some(DrawingLayer::SilkscreenFront)
+    }
+    "bot_legend" | "bot_names" | "bot_values" => {
+      Some(DrawingLayer::SilkscreenBack)
+    }
+    "top_documentation" => Some(DrawingLayer::FabricationFront),
+    "bot_documentation" => Some(DrawingLayer::FabricationBack),
+    "top_cu" => Some(DrawingLayer::CopperFront),
+    "bot_cu" => Some(DrawingLayer::CopperBack),
+    _ => None,
+  }
+}
+
+/// Map a copper layer name to a board side
+fn copper_layer(layer: &str) -> Option<Layer> {
+  match layer {
+    "top_cu" => Some(Layer::Front),
+    "bot_cu" => Some(Layer::Back),
+    _ => None,
+  }
+}
+
+/// Build an SVG path from the vertices of a polygon, hole or custom pad
+fn path(
+  node: &Node,
+  map: impl Fn((f64, f64)) -> (f64, f64),
+  arc: impl Fn(f64) -> f64,
+) -> Result<String, String> {
+  let mut builder = SvgPathBuilder::new();
+  let mut angle = None;
+  for vertex in node.children("vertex") {
+    let pos = map(vertex.point("position")?);
+    match angle {
+      None => builder.move_to(pos),
+      Some(angle) => builder.arc_to(pos, arc(angle)),
+    };
+    angle = Some(vertex.number("angle")?);
+  }
+  Ok(builder.build())
+}
+
+/// Build an SVG path of a circle
+fn circle(center: (f64, f64), diameter: f64) -> String {
+  let r = diameter / 2.0;
+  SvgPathBuilder::new()
+    .move_to((center.0 + r, center.1))
+    .arc_to((center.0 - r, center.1), 180.0)
+    .arc_to((center.0 + r, center.1), 180.0)
+    .close()
+    .build()
+}
+
+/// Get the symbol of an attribute unit, e.g. "kΩ" for "kiloohm"
+fn unit_symbol(unit: &str) -> String {
+  let (prefix, base) = UNIT_PREFIXES
+    .iter()
+    .find_map(|(name, symbol)| Some((*symbol, unit.strip_prefix(name)?)))
+    .unwrap_or(("", unit));
+  match UNIT_BASES.iter().find(|(name, _)| *name == base) {
+    Some((_, symbol)) => format!("{}{}", prefix, symbol),
+    None => String::new(),
+  }
+}
+
+/// Replace attribute variables like "{{RESISTANCE}}" of a component
+fn substitute(text: &str, component: &Node) -> String {
+  let mut out = String::new();
+  let mut rest = text;
+  while let Some(start) = rest.find("{{") {
+    let Some(end) = rest[start..].find("}}") else {
+      break;
+    };
+    out += &rest[..start];
+    let key = rest[start + 2..start + end].trim();
+    if let Some(attribute) =
+      component.children("attribute").find(|x| x.value() == key)
+    {
+      out += attribute.text("value");
+      out += &unit_symbol(attribute.text("unit"));
+    }
+    rest = &rest[start + end + 2..];
+  }
+  out += rest;
+  out.trim().to_owned()
+}
+
+/// Board with the information needed to resolve trace endpoints
+struct Board {
+  /// Positions of footprint pads by device and pad UUID
+  pads: BTreeMap<(String, String), (f64, f64)>,
+  /// Registered nets by UUID
+  nets: BTreeMap<String, NetId>,
+}
+
+/// Add a footprint for a device of the board
+fn add_device(
+  bom: &mut InteractiveHtmlBom,
+  project: &Project,
+  board: &mut Board,
+  circuit: &Node,
+  device: &Node,
+) -> Result<RefMap, String> {
+  let component = circuit
+    .children("component")
+    .find(|x| x.value() == device.value())
+    .ok_or("device without component")?;
+  let lib_device = project.file(&format!(
+    "library/dev/{}/device.lp",
+    device.text("lib_device")
+  ));
+  let package = project.file(&format!(
+    "library/pac/{}/package.lp",
+    lib_device.text("package")
+  ));
+  let lib_component = project.files.get(&format!(
+    "library/cmp/{}/component.lp",
+    component.text("lib_component")
+  ));
+  let footprint = package
+    .children("footprint")
+    .find(|x| x.value() == device.text("lib_footprint"))
+    .ok_or("footprint not found in package")?;
+  let transform = Transform {
+    pos: device.point("position")?,
+    rotation: device.number("rotation")?,
+    flip: device.flag("flip"),
+  };
+  let name = component.text("name");
+  let value = substitute(component.text("value"), component);
+
+  let mut pads = Vec::new();
+  let mut bounds: Vec<(f64, f64)> = Vec::new();
+  for pad in footprint.children("pad") {
+    let pos = pad.point("position")?;
+    let rotation = pad.number("rotation")?;
+    let (w, h) = pad.point("size")?;
+    let layers = match pad.text("side") {
+      "tht" => vec![Layer::Front, Layer::Back],
+      side => transform.layer(side).into_iter().collect(),
+    };
+    let radius = pad.number("radius").unwrap_or(0.0) * w.min(h) / 2.0;
+    let shape = match pad.text("shape") {
+      "roundrect" if radius >= w.min(h) / 2.0 && w == h => PadShape::Circle(w),
+      "roundrect" if radius >= w.min(h) / 2.0 => PadShape::Oval((w, h)),
+      "roundrect" if radius > 0.0 => PadShape::RoundRect((w, h), radius),
+      "octagon" => {
+        let corner = PadCorner::Chamfer(w.min(h) * (1.0 - 0.5f64.sqrt()));
+        PadShape::Corners((w, h), [corner; 4])
+      }
+      "custom" => PadShape::Custom(path(
+        pad,
+        |p| transform.local(p),
+        |a| transform.arc(a),
+      )?),
+      _ => PadShape::Rect((w, h)),
+    };
+    let drill = pad.child("hole").map(|hole| {
+      let diameter = hole.number("diameter")?;
+      let vertices = hole
+        .children("vertex")
+        .map(|x| x.point("position"))
+        .collect::<Result<Vec<_>, _>>()?;
+      let (first, last) = match (vertices.first(), vertices.last()) {
+        (Some(first), Some(last)) => (*first, *last),
+        _ => return Err("hole without vertices".to_owned()),
+      };
+      let length = (last.0 - first.0).hypot(last.1 - first.1);
+      let center = ((first.0 + last.0) / 2.0, (first.1 + last.1) / 2.0);
+      Ok(((length + diameter, diameter), transform.local(center)))
+    });
+    let drill = drill.transpose()?;
+    let angle =
+      transform.rotation + if transform.flip { -rotation } else { rotation };
+    let package_pad = package
+      .children("pad")
+      .find(|x| x.value() == pad.text("package_pad"));
+    let number = package_pad.map(|x| x.text("name"));
+    let mut pad_obj = Pad::with_shape(
+      layers,
+      transform.map(pos),
+      angle,
+      shape,
+      drill.map(|x| x.0),
+      None,
+      number == Some("1"),
+    );
+    if let Some((_, offset)) = drill {
+      pad_obj.set_drill_offset(offset);
+    }
+    // Net and pin name are assigned through the device's pad-signal map.
+    let signal = lib_device
+      .children("pad")
+      .find(|x| Some(x.value()) == package_pad.map(|x| x.value()))
+      .map(|x| x.text("signal"));
+    let pin_name = signal.and_then(|signal| {
+      let signal = lib_component?
+        .children("signal")
+        .find(|x| x.value() == signal)?;
+      Some(signal.text("name"))
+    });
+    pad_obj.set_pin(number, pin_name);
+    let net = signal.and_then(|signal| {
+      let signal =
+        component.children("signal").find(|x| x.value() == signal)?;
+      board.nets.get(signal.text("net")).cloned()
+    });
+    pad_obj.set_net_id(net);
+    pads.push(pad_obj);
+    board.pads.insert(
+      (device.value().to_owned(), pad.value().to_owned()),
+      transform.map(pos),
+    );
+
+    // Bounding box of the pad, rotated in the footprint.
+    let (sin, cos) = rotation.to_radians().sin_cos();
+    for (dx, dy) in [(-w, -h), (w, -h), (w, h), (-w, h)] {
+      let (dx, dy) = (dx / 2.0, dy / 2.0);
+      bounds.push((pos.0 + dx * cos - dy * sin, pos.1 + dx * sin + dy * cos));
+    }
+  }
+
+  let mut drawings = Vec::new();
+  for polygon in footprint.children("polygon") {
+    let layer = polygon.text("layer");
+    if layer.ends_with("_documentation") || layer.ends_with("_legend") {
+      for vertex in polygon.children("vertex") {
+        bounds.push(vertex.point("position")?);
+      }
+    }
+    let Some(layer) = footprint_layer(&transform, layer) else {
+      continue;
+    };
+    let svgpath = path(polygon, |p| transform.map(p), |a| transform.arc(a))?;
+    drawings.push(Drawing::polygon(
+      layer,
+      &svgpath,
+      polygon.number("width")?,
+      polygon.flag("fill"),
+    ));
+  }
+  for circle_node in footprint.children("circle") {
+    let Some(layer) = footprint_layer(&transform, circle_node.text("layer"))
+    else {
+      continue;
+    };
+    let center = transform.map(circle_node.point("position")?);
+    drawings.push(Drawing::polygon(
+      layer,
+      &circle(center, circle_node.number("diameter")?),
+      circle_node.number("width")?,
+      circle_node.flag("fill"),
+    ));
+  }
+  for text in device.children("stroke_text") {
+    let (kind, content) = match text.text("value") {
+      "{{NAME}}" => (DrawingKind::ReferenceText, name.to_owned()),
+      "{{VALUE}}" => (DrawingKind::ValueText, value.clone()),
+      _ => continue,
+    };
+    let Some(layer) = drawing_layer(text.text("layer")) else {
+      continue;
+    };
+    drawings.push(stroke_text(text, kind, layer, &content)?);
+  }
+
+  let local = bounds.iter().map(|p| transform.local(*p));
+  let (bottom_left, top_right) = local.fold(
+    (
+      (f64::INFINITY, f64::INFINITY),
+      (f64::NEG_INFINITY, f64::NEG_INFINITY),
+    ),
+    |(a, b), p| ((a.0.min(p.0), a.1.min(p.1)), (b.0.max(p.0), b.1.max(p.1))),
+  );
+  let (bottom_left, top_right) = match bounds.is_empty() {
+    true => ((0.0, 0.0), (0.0, 0.0)),
+    false => (bottom_left, top_right),
+  };
+  let mut fpt = Footprint::new(
+    transform.layer("top").unwrap_or(Layer::Front),
+    transform.map((0.0, 0.0)),
+    transform.rotation,
+    bottom_left,
+    top_right,
+    [value, package.text("name").to_owned()],
+    pads,
+    true,
+  );
+  for drawing in drawings {
+    fpt.add_drawing(drawing);
+  }
+  Ok(RefMap::new(name, bom.add_footprint(fpt)))
+}
+
+/// Map a footprint layer name to a drawing layer, considering the placement
+fn footprint_layer(transform: &Transform, layer: &str) -> Option<DrawingLayer> {
+  let side = transform.layer(layer)?;
+  let front = side == Layer::Front;
+  match layer.split_once('_')?.1 {
+    "legend" if front => Some(DrawingLayer::SilkscreenFront),
+    "legend" => Some(DrawingLayer::SilkscreenBack),
+    "documentation" if front => Some(DrawingLayer::FabricationFront),
+    "documentation" => Some(DrawingLayer::FabricationBack),
+    _ => None,
+  }
+}
+
+/// Build a text drawing from a stroke text with absolute coordinates
+fn stroke_text(
+  text: &Node,
+  kind: DrawingKind,
+  layer: DrawingLayer,
+  content: &str,
+) -> Result<Drawing, String> {
+  let height = text.number("height")?;
+  let (x, y) = text.point("position")?;
+  let rotation = text.number("rotation")?;
+  let align = text.child("align").map(|x| x.values.as_slice());
+  let lines = content.lines().count().max(1) as f64;
+  let width = content
+    .lines()
+    .map(|x| x.chars().count())
+    .max()
+    .unwrap_or(0);
+  let width = width as f64 * height * CHAR_WIDTH;
+
+  // The viewer centers texts, thus move the anchor to the center.
+  let mut dx = match align.and_then(|x| x.first()).map(|x| x.as_str()) {
+    Some("left") => width / 2.0,
+    Some("right") => -width / 2.0,
+    _ => 0.0,
+  };
+  let dy = match align.and_then(|x| x.get(1)).map(|x| x.as_str()) {
+    Some("bottom") => height * lines / 2.0,
+    Some("top") => -height * lines / 2.0,
+    _ => 0.0,
+  };
+  if text.flag("mirror") {
+    dx = -dx;
+  }
+  let (sin, cos) = rotation.to_radians().sin_cos();
+  let center = (x + dx * cos - dy * sin, -(y + dx * sin + dy * cos));
+  Ok(Drawing::text(
+    kind,
+    layer,
+    content,
+    center,
+    (height, height),
+    rotation,
+    text.number("stroke_width")?,
+  ))
+}
+
+/// Add the board objects which do not belong to a device
+fn add_board_objects(
+  bom: &mut InteractiveHtmlBom,
+  board: &Board,
+  node: &Node,
+) -> Result<(), String> {
+  let map = |p: (f64, f64)| (p.0, -p.1 + 0.0);
+  let arc = |a: f64| -a;
+  let mut outlines = Vec::new();
+  let mut cutouts = Vec::new();
+  for polygon in node.children("polygon") {
+    let svgpath = path(polygon, map, arc)?;
+    let width = polygon.number("width")?;
+    match polygon.text("layer") {
+      "brd_outlines" => outlines.push((svgpath, width)),
+      "brd_cutouts" | "brd_plated_cutouts" => cutouts.push(svgpath),
+      layer => {
+        if let Some(layer) = drawing_layer(layer) {
+          let filled = polygon.flag("fill");
+          bom
+            .drawings
+            .push(Drawing::polygon(layer, &svgpath, width, filled));
+        }
+      }
+    }
+  }
+  let cutouts = cutouts.iter().map(|x| x.as_str()).collect::<Vec<_>>();
+  for (i, (outline, width)) in outlines.iter().enumerate() {
+    let cutouts = if i == 0 { cutouts.as_slice() } else { &[] };
+    bom
+      .outlines
+      .push(BoardOutline::new(outline, cutouts, *width));
+  }
+
+  for hole in node.children("hole") {
+    let diameter = hole.number("diameter")?;
+    let vertices = hole
+      .children("vertex")
+      .map(|x| x.point("position").map(map))
+      .collect::<Result<Vec<_>, _>>()?;
+    match vertices.as_slice() {
+      [] => return Err("hole without vertices".into()),
+      [pos] => bom.holes.push(Hole::new(*pos, diameter)),
+      [start, .., end] => {
+        let (dx, dy) = (end.0 - start.0, end.1 - start.1);
+        let center = (start.0 + dx / 2.0, start.1 + dy / 2.0);
+        let size = (dx.hypot(dy) + diameter, diameter);
+        let angle = -dy.atan2(dx).to_degrees() + 0.0;
+        bom.holes.push(Hole::slot(center, size, angle, false));
+      }
+    }
+  }
+
+  for segment in node.children("netsegment") {
+    let net = board.nets.get(segment.text("net"));
+    let mut anchors = BTreeMap::new();
+    for junction in segment.children("junction") {
+      anchors.insert(junction.value(), map(junction.point("position")?));
+    }
+    for via in segment.children("via") {
+      let pos = map(via.point("position")?);
+      anchors.insert(via.value(), pos);
+      // Vias without layer span are through-hole vias.
+      let from =
+        copper_layer(via.child("from").map_or("top_cu", |x| x.value()));
+      let to = copper_layer(via.child("to").map_or("bot_cu", |x| x.value()));
+      let layers = [from, to].into_iter().flatten().collect::<Vec<_>>();
+      if !layers.is_empty() {
+        let via_obj = Via::with_net_id(
+          layers,
+          pos,
+          via.number("size")?,
+          via.number("drill")?,
+          net.cloned(),
+        );
+        bom.vias.push(via_obj);
+      }
+    }
+    for trace in segment.children("trace") {
+      let Some(layer) = copper_layer(trace.text("layer")) else {
+        continue;
+      };
+      let anchor = |name: &str| {
+        let end = trace.child(name)?;
+        match end.child("device") {
+          Some(device) => board
+            .pads
+            .get(&(device.value().to_owned(), end.text("pad").to_owned())),
+          None => end.children.first().and_then(|x| anchors.get(x.value())),
+        }
+        .copied()
+      };
+      let (Some(start), Some(end)) = (anchor("from"), anchor("to")) else {
+        return Err("trace with unknown endpoint".into());
+      };
+      let width = trace.number("width")?;
+      let track = Track::with_net_id(layer, start, end, width, net.cloned());
+      bom.tracks.push(track);
+    }
+  }
+  Ok(())
+}
+
+/// Read a LibrePCB project
+///
+/// # Arguments
+///
+/// * `path` - Project directory, or the *.lpp file within it.
+/// * `board_name` - Name of the board to read, or [None] for the first one.
+///
+/// # Returns
+///
+/// Returns the populated object, or an error if reading the files failed or
+/// they are invalid.
+pub(crate) fn import(
+  path: &Path,
+  board_name: Option<&str>,
+) -> Result<InteractiveHtmlBom, GenerateError> {
+  let dir = match path.is_file() {
+    true => path.parent().unwrap_or(Path::new(".")),
+    false => path,
+  };
+  let mut project = Project {
+    dir,
+    files: BTreeMap::new(),
+  };
+  let mut bom = InteractiveHtmlBom::new("", "", "", "", (0.0, 0.0), (0.0, 0.0));
+  bom.fields = vec!["Value".into(), "Package".into()];
+
+  if dir.join("project/metadata.lp").exists() {
+    project.load("project/metadata.lp")?;
+    let metadata = project.file("project/metadata.lp");
+    bom.set_title(metadata.text("name"));
+    bom.set_company(metadata.text("author"));
+    bom.set_revision(metadata.text("version"));
+    let date = metadata.text("created");
+    bom.set_date(date.split('T').next().unwrap_or(date));
+  }
+
+  // Find the board.
+  project.load("boards/boards.lp")?;
+  let paths = project
+    .file("boards/boards.lp")
+    .children("board")
+    .map(|x| x.value().to_owned())
+    .collect::<Vec<_>>();
+  let mut board_path = None;
+  for path in paths {
+    project.load(&path)?;
+    let name = project.file(&path).text("name");
+    if board_name.is_none_or(|x| x == name) {
+      board_path = Some(path);
+      break;
+    }
+  }
+  let board_path = board_path.ok_or_else(|| {
+    let reason = match board_name {
+      Some(name) => format!("board \"{}\" not found", name),
+      None => "project contains no boards".into(),
+    };
+    GenerateError::InvalidInput {
+      format: InputFormat::LibrePcbProject,
+      reason,
+    }
+  })?;
+
+  // Load the circuit and all library elements used on the board.
+  project.load("circuit/circuit.lp")?;
+  let devices = project
+    .file(&board_path)
+    .children("device")
+    .map(|x| x.text("lib_device").to_owned())
+    .collect::<Vec<_>>();
+  for uuid in devices {
+    let path = format!("library/dev/{}/device.lp", uuid);
+    project.load(&path)?;
+    let package = project.file(&path).text("package").to_owned();
+    project.load(&format!("library/pac/{}/package.lp", package))?;
+  }
+  let components = project
+    .file("circuit/circuit.lp")
+    .children("component")
+    .map(|x| format!("library/cmp/{}/component.lp", x.text("lib_component")))
+    .collect::<Vec<_>>();
+  for path in components {
+    // Only used for pin names, thus optional.
+    if dir.join(&path).exists() {
+      project.load(&path)?;
+    }
+  }
+
+  let circuit = project.file("circuit/circuit.lp");
+  let node = project.file(&board_path);
+  let mut board = Board {
+    pads: BTreeMap::new(),
+    nets: BTreeMap::new(),
+  };
+  for net in circuit.children("net") {
+    let id = bom.add_net(net.text("name"));
+    board.nets.insert(net.value().to_owned(), id);
+  }
+  let mut refs = Vec::new();
+  for device in node.children("device") {
+    let refmap = add_device(&mut bom, &project, &mut board, circuit, device)
+      .map_err(|reason| invalid(&board_path, reason))?;
+    refs.push(refmap);
+  }
+  add_board_objects(&mut bom, &board, node)
+    .map_err(|reason| invalid(&board_path, reason))?;
+  bom.group_bom(&refs, &["Value", "Package"])?;
+  Ok(bom)
+}
@@ -0,0 +1,196 @@
+//! Conservative minifier for the bundled CSS and JavaScript
+//!
+//! Removes comments, indentation, trailing whitespace and empty lines. Line
+//! breaks are kept to not break JavaScript's automatic semicolon insertion.
+//! License notices of the bundled libraries are kept, see [is_notice].
+
+/// Characters after which a slash starts a regular expression literal
+const REGEX_PRECEDERS: &str = "(,=:[!&|?{};+-*%<>~^";
+
+/// Keywords after which a slash starts a regular expression literal
+const REGEX_KEYWORDS: [&str; 6] =
+  ["return", "typeof", "case", "in", "of", "void"];
+
+/// Words marking a comment as license notice (lowercase)
+const NOTICE_KEYWORDS: [&str; 2] = ["license", "copyright"];
+
+/// Whether a comment must be kept, i.e. is a `/*!` banner or mentions a
+/// license or copyright
+fn is_notice(comment: &[char]) -> bool {
+  let text = comment.iter().collect::<String>().to_lowercase();
+  text.starts_with("/*!") || NOTICE_KEYWORDS.iter().any(|x| text.contains(x))
+}
+
+/// Minify JavaScript code
+pub(crate) fn js(code: &str) -> String {
+  minify(code, true)
+}
+
+/// Minify CSS code
+pub(crate) fn css(code: &str) -> String {
+  minify(code, false)
+}
+
+/// Copy a string literal (or regex) starting at `chars[i]` until the closing
+/// delimiter and return the index after it
+fn copy_literal(chars: &[char], mut i: usize, out: &mut String) -> usize {
+  let delimiter = chars[i];
+  let mut in_class = false;
+  out.push(delimiter);
+  i += 1;
+  while let Some(&c) = chars.get(i) {
+    out.push(c);
+    i += 1;
+    match c {
+      '\\' => {
+        if let Some(&next) = chars.get(i) {
+          out.push(next);
+          i += 1;
+        }
+      }
+      '[' if delimiter == '/' => in_class = true,
+      ']' if delimiter == '/' => in_class = false,
+      '\n' if delimiter != '`' => break,
+      _ if c == delimiter && !in_class => break,
+      _ => {}
+    }
+  }
+  i
+}
+
+/// Whether a slash at the current output position starts a regex literal
+fn regex_allowed(out: &str) -> bool {
+  let code = out.trim_end();
+  match code.chars().last() {
+    None => true,
+    // Postfix increment/decrement, i.e. followed by a division.
+    Some('+' | '-') if code.ends_with("++") || code.ends_with("--") => false,
+    Some(c) if REGEX_PRECEDERS.contains(c) => true,
+    Some(c) if c.is_alphanumeric() || c == '_' || c == '$' => {
+      let word = code
+        .rsplit(|c: char| !(c.is_alphanumeric() || c == '_' || c == '$'))
+        .next()
+        .unwrap_or_default();
+      REGEX_KEYWORDS.contains(&word)
+    }
+    Some(_) => false,
+  }
+}
+
+/// End the current output line, dropping trailing whitespace and empty lines
+fn end_line(out: &mut String) {
+  out.truncate(out.trim_end_matches([' ', '\t', '\r']).len());
+  if !out.is_empty() && !out.ends_with('\n') {
+    out.push('\n');
+  }
+}
+
+fn minify(code: &str, js: bool) -> String {
+  let chars: Vec<char> = code.chars().collect();
+  let mut out = String::with_capacity(code.len());
+  let mut i = 0;
+  while let Some(&c) = chars.get(i) {
+    let next = chars.get(i + 1).copied();
+    match c {
+      '"' | '\'' => i = copy_literal(&chars, i, &mut out),
+      '`' if js => i = copy_literal(&chars, i, &mut out),
+      '/' if js && next == Some('/') => {
+        // Consecutive comment lines form a single (notice) comment.
+        let start = i;
+        let own_line = out.is_empty() || out.ends_with('\n');
+        let mut end = i;
+        loop {
+          while chars.get(end).is_some_and(|&c| c != '\n') {
+            end += 1;
+          }
+          let mut next_line = end + 1;
+          while chars.get(next_line).is_some_and(|&c| c == ' ' || c == '\t') {
+            next_line += 1;
+          }
+          let continued = own_line
+            && chars.get(next_line) == Some(&'/')
+            && chars.get(next_line + 1) == Some(&'/');
+          if !continued {
+            break;
+          }
+          end = next_line;
+        }
+        if is_notice(&chars[start..end]) {
+          let comment = chars[start..end].iter().collect::<String>();
+          let lines = comment.lines().map(str::trim_start).collect::<Vec<_>>();
+          out.push_str(&lines.join("\n"));
+        }
+        i = end;
+      }
+      '/' if next == Some('*') => {
+        let start = i;
+        i += 2;
+        while i < chars.len()
+          && (chars[i], chars.get(i + 1)) != ('*', Some(&'/'))
+        {
+          i += 1;
+        }
+        i += 2;
+        let comment = &chars[start..i.min(chars.len())];
+        if is_notice(comment) {
+          out.extend(comment);
+        } else if comment.contains(&'\n') {
+          // Keep tokens separated.
+          end_line(&mut out);
+        } else if !out.ends_with([' ', '\n']) && !out.is_empty() {
+          out.push(' ');
+        }
+      }
+      '/' if js && regex_allowed(&out) => i = copy_literal(&chars, i, &mut out),
+      '\n' => {
+        end_line(&mut out);
+        i += 1;
+      }
+      ' ' | '\t' | '\r' if out.is_empty() || out.ends_with('\n') => i += 1,
+      _ => {
+        out.push(c);
+        i += 1;
+      }
+    }
+  }
+  end_line(&mut out);
+  out
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_division_and_regex() {
+    let code = "a = b / c; // comment\nd = /x\\/[/]/g.test(e);\n";
+    assert_eq!(js(code), "a = b / c;\nd = /x\\/[/]/g.test(e);\n");
+    let code = "i++ / 2; // a\ni-- / 2; // b\nreturn /x/;\n";
+    assert_eq!(js(code), "i++ / 2;\ni-- / 2;\nreturn /x/;\n");
+    assert_eq!(js("a = (b) / c / d; // e\n"), "a = (b) / c / d;\n");
+  }
+
+  #[test]
+  fn test_literals() {
+    let code = "a = \"http://x\" + 'y // z'; // comment\n";
+    assert_eq!(js(code), "a = \"http://x\" + 'y // z';\n");
+    let code = "a = `b // c\n  ${d / 2} /* e */`; /* f */\n";
+    assert_eq!(js(code), "a = `b // c\n  ${d / 2} /* e */`;\n");
+  }
+
+  #[test]
+  fn test_notices() {
+    let code = "/*!\n * A v1\n */\na(); /* b */\n/* Copyright C */\n";
+    assert_eq!(js(code), "/*!\n * A v1\n */\na();\n/* Copyright C */\n");
+    let code = "// MIT License\n  // of D\n\n// e\nf(); // License of f\n";
+    assert_eq!(js(code), "// MIT License\n// of D\nf(); // License of f\n");
+    let code = "/* LICENSE: x */\na { }\n";
+    assert_eq!(css(code), "/* LICENSE: x */\na { }\n");
+  }
+
+  #[test]
+  fn test_css() {
+    let code = "a {\n  /* b */\n  color: red;\n}\n\n";
+    assert_eq!(css(code), "a {\ncolor: red;\n}\n");
+  }
+}
@@ -0,0 +1,324 @@
+//! Minimal PDF writer rendering text tables with the Courier standard font
+//! and line drawings of the board
+
+use crate::svgpath::Polyline;
+
+/// Page size (A4 portrait) [pt]
+const PAGE_SIZE: (f64, f64) = (595.28, 841.89);
+
+/// Page margin [pt]
+const MARGIN: f64 = 40.0;
+
+/// Width of Courier glyphs relative to the font size
+const CHAR_WIDTH: f64 = 0.6;
+
+/// Maximum width of a table column [characters]
+const MAX_COLUMN_WIDTH: usize = 40;
+
+/// Spacing between table columns [characters]
+const COLUMN_GAP: usize = 2;
+
+/// Font size range of the table [pt]
+const FONT_SIZE: (f64, f64) = (4.0, 9.0);
+
+/// Font size of the title [pt]
+const TITLE_FONT_SIZE: f64 = 14.0;
+
+/// Font size of board labels [pt]
+const LABEL_FONT_SIZE: f64 = 5.0;
+
+/// Minimum line width of board drawings [pt]
+const MIN_LINE_WIDTH: f64 = 0.25;
+
+/// Board side drawn on its own page
+pub(crate) struct Board {
+  /// Title printed on top of the page.
+  pub title: String,
+  /// Bounding box (bottom left, top right) of the board \[mm\].
+  pub bbox: ((f64, f64), (f64, f64)),
+  /// Whether to mirror the drawing horizontally, i.e. view from the back.
+  pub mirrored: bool,
+  /// Polylines and their line width \[mm\].
+  pub lines: Vec<(Polyline, f64)>,
+  /// Texts centered at a position \[mm\].
+  pub labels: Vec<((f64, f64), String)>,
+}
+
+/// Encode text as PDF string literal, replacing unsupported characters
+fn string(text: &str) -> Vec<u8> {
+  let mut out = vec![b'('];
+  for c in text.chars() {
+    match c {
+      '\\' | '(' | ')' => out.extend([b'\\', c as u8]),
+      // WinAnsiEncoding matches Latin-1 in these ranges.
+      ' '..='~' | '\u{a0}'..='\u{ff}' => out.push(c as u32 as u8),
+      _ => out.push(b'?'),
+    }
+  }
+  out.push(b')');
+  out
+}
+
+/// Wrap text into lines of at most `width` characters, preferably at spaces
+fn wrap(text: &str, width: usize) -> Vec<String> {
+  let mut lines = Vec::new();
+  let mut line = String::new();
+  for word in text.split(' ') {
+    let mut word: Vec<char> = word.chars().collect();
+    let len = line.chars().count();
+    if len > 0 && len + 1 + word.len() > width {
+      lines.push(std::mem::take(&mut line));
+    }
+    while word.len() > width {
+      lines.push(word.drain(..width).collect());
+    }
+    if !line.is_empty() {
+      line.push(' ');
+    }
+    line.extend(word);
+  }
+  lines.push(line);
+  lines
+}
+
+/// Content stream of a page
+struct Page {
+  content: Vec<u8>,
+  y: f64,
+}
+
+impl Page {
+  fn new() -> Page {
+    Page {
+      content: Vec::new(),
+      y: PAGE_SIZE.1 - MARGIN,
+    }
+  }
+
+  fn text(&mut self, font: &str, size: f64, x: f64, text: &str) {
+    self.text_at(font, size, (x, self.y), text);
+  }
+
+  fn text_at(&mut self, font: &str, size: f64, pos: (f64, f64), text: &str) {
+    self.content.extend(
+      format!("BT /{} {} Tf {:.2} {:.2} Td ", font, size, pos.0, pos.1)
+        .as_bytes(),
+    );
+    self.content.extend(string(text));
+    self.content.extend(b" Tj ET\n");
+  }
+
+  fn polyline(&mut self, points: &[(f64, f64)], closed: bool, width: f64) {
+    if points.is_empty() {
+      return;
+    }
+    self.content.extend(format!("{:.2} w", width).as_bytes());
+    for (i, (x, y)) in points.iter().enumerate() {
+      let op = if i == 0 { "m" } else { "l" };
+      self
+        .content
+        .extend(format!(" {:.2} {:.2} {}", x, y, op).as_bytes());
+    }
+    if closed {
+      self.content.extend(b" h");
+    }
+    self.content.extend(b" S\n");
+  }
+
+  fn rule(&mut self) {
+    self.content.extend(
+      format!(
+        "0.5 w {:.2} {:.2} m {:.2} {:.2} l S\n",
+        MARGIN,
+        self.y,
+        PAGE_SIZE.0 - MARGIN,
+        self.y
+      )
+      .as_bytes(),
+    );
+  }
+}
+
+/// Draw a board side on a new page, scaled to fit the page
+fn board_page(board: &Board) -> Page {
+  let mut page = Page::new();
+  page.y -= TITLE_FONT_SIZE;
+  page.text("F2", TITLE_FONT_SIZE, MARGIN, &board.title);
+  page.y -= TITLE_FONT_SIZE;
+
+  let ((minx, miny), (maxx, maxy)) = board.bbox;
+  let size = ((maxx - minx).max(1e-3), (maxy - miny).max(1e-3));
+  let area = (PAGE_SIZE.0 - 2.0 * MARGIN, page.y - MARGIN);
+  let scale = (area.0 / size.0).min(area.1 / size.1);
+  let left = MARGIN + (area.0 - size.0 * scale) / 2.0;
+  let top = page.y - (area.1 - size.1 * scale) / 2.0;
+  // The board's Y axis points downwards, the page's upwards.
+  let map = |(x, y): (f64, f64)| {
+    let x = if board.mirrored { maxx - x } else { x - minx };
+    (left + x * scale, top - (y - miny) * scale)
+  };
+
+  page.content.extend(b"1 J 1 j\n");
+  for (polyline, width) in &board.lines {
+    let points: Vec<_> = polyline.points.iter().copied().map(map).collect();
+    let width = (width * scale).max(MIN_LINE_WIDTH);
+    page.polyline(&points, polyline.closed, width);
+  }
+  for (pos, text) in &board.labels {
+    let (x, y) = map(*pos);
+    let width = text.chars().count() as f64 * LABEL_FONT_SIZE * CHAR_WIDTH;
+    let pos = (x - width / 2.0, y - LABEL_FONT_SIZE / 3.0);
+    page.text_at("F1", LABEL_FONT_SIZE, pos, text);
+  }
+  page
+}
+
+/// Render a table and board drawings to a PDF document
+///
+/// # Arguments
+///
+/// * `title` - Title printed on top of the first page.
+/// * `subtitle` - Lines printed below the title.
+/// * `header` - Column titles, repeated on every page.
+/// * `rows` - Table cells, with the same length as `header` each.
+/// * `boards` - Board sides, each drawn on a page after the table.
+///
+/// # Returns
+///
+/// Returns the PDF document.
+pub(crate) fn document(
+  title: &str,
+  subtitle: &[String],
+  header: &[String],
+  rows: &[Vec<String>],
+  boards: &[Board],
+) -> Vec<u8> {
+  // Determine column widths and the font size to fit the page width.
+  let widths: Vec<usize> = (0..header.len())
+    .map(|i| {
+      std::iter::once(&header[i])
+        .chain(rows.iter().map(|row| &row[i]))
+        .map(|x| x.chars().count())
+        .max()
+        .unwrap_or(0)
+        .clamp(1, MAX_COLUMN_WIDTH)
+    })
+    .collect();
+  let chars = widths.iter().map(|x| x + COLUMN_GAP).sum::<usize>();
+  let size = ((PAGE_SIZE.0 - 2.0 * MARGIN) / (chars as f64 * CHAR_WIDTH))
+    .clamp(FONT_SIZE.0, FONT_SIZE.1);
+  let line_height = size * 1.25;
+
+  let mut pages = vec![Page::new()];
+  let page = pages.last_mut().unwrap();
+  page.y -= TITLE_FONT_SIZE;
+  page.text("F2", TITLE_FONT_SIZE, MARGIN, title);
+  page.y -= TITLE_FONT_SIZE * 0.5;
+  for line in subtitle {
+    page.y -= line_height;
+    page.text("F1", size, MARGIN, line);
+  }
+  page.y -= line_height;
+
+  let print_row = |page: &mut Page, font: &str, row: &[String]| {
+    let cells: Vec<Vec<String>> = row
+      .iter()
+      .zip(&widths)
+      .map(|(cell, width)| wrap(cell, *width))
+      .collect();
+    let lines = cells.iter().map(|x| x.len()).max().unwrap_or(1);
+    for i in 0..lines {
+      page.y -= line_height;
+      let mut x = MARGIN;
+      for (cell, width) in cells.iter().zip(&widths) {
+        if let Some(text) = cell.get(i) {
+          page.text(font, size, x, text);
+        }
+        x += (width + COLUMN_GAP) as f64 * size * CHAR_WIDTH;
+      }
+    }
+  };
+  let print_header = |page: &mut Page| {
+    print_row(page, "F2", header);
+    page.y -= size * 0.4;
+    page.rule();
+  };
+
+  print_header(pages.last_mut().unwrap());
+  for row in rows {
+    let lines = row
+      .iter()
+      .zip(&widths)
+      .map(|(cell, width)| wrap(cell, *width).len())
+      .max()
+      .unwrap_or(1);
+    if pages.last().unwrap().y - lines as f64 * line_height < MARGIN {
+      pages.push(Page::new());
+      print_header(pages.last_mut().unwrap());
+    }
+    print_row(pages.last_mut().unwrap(), "F1", row);
+  }
+  pages.extend(boards.iter().map(board_page));
+
+  // Objects: catalog, page tree, fonts, then page and content per page.
+  let mut objects: Vec<Vec<u8>> = vec![
+    b"<< /Type /Catalog /Pages 2 0 R >>".to_vec(),
+    format!(
+      "<< /Type /Pages /Kids [{}] /Count {} >>",
+      (0..pages.len())
+        .map(|i| format!("{} 0 R", 5 + 2 * i))
+        .collect::<Vec<_>>()
+        .join(" "),
+      pages.len()
+    )
+    .into_bytes(),
+    b"<< /Type /Font /Subtype /Type1 /BaseFont /Courier \
+      /Encoding /WinAnsiEncoding >>"
+      .to_vec(),
+    b"<< /Type /Font /Subtype /Type1 /BaseFont /Courier-Bold \
+      /Encoding /WinAnsiEncoding >>"
+      .to_vec(),
+  ];
+  for (i, page) in pages.iter().enumerate() {
+    objects.push(
+      format!(
+        "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {} {}] \
+         /Resources << /Font << /F1 3 0 R /F2 4 0 R >> >> \
+         /Contents {} 0 R >>",
+        PAGE_SIZE.0,
+        PAGE_SIZE.1,
+        6 + 2 * i
+      )
+      .into_bytes(),
+    );
+    let mut stream =
+      format!("<< /Length {} >>\nstream\n", page.content.len()).into_bytes();
+    stream.extend(&page.content);
+    stream.extend(b"endstream");
+    objects.push(stream);
+  }
+
+  let mut pdf = b"%PDF-1.4\n".to_vec();
+  let mut offsets = Vec::new();
+  for (i, object) in objects.iter().enumerate() {
+    offsets.push(pdf.len());
+    pdf.extend(format!("{} 0 obj\n", i + 1).as_bytes());
+    pdf.extend(object);
+    pdf.extend(b"\nendobj\n");
+  }
+  let xref = pdf.len();
+  pdf.extend(format!("xref\n0 {}\n", objects.len() + 1).as_bytes());
+  pdf.extend(b"0000000000 65535 f \n");
+  for offset in offsets {
+    pdf.extend(format!("{:010} 00000 n \n", offset).as_bytes());
+  }
+  pdf.extend(
+    format!(
+      "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{}\n%%EOF\n",
+      objects.len() + 1,
+      xref
+    )
+    .as_bytes(),
+  );
+  pdf
+}
@@ -0,0 +1,211 @@
+//! Reader for pick and place (centroid) CSV files
+//!
+//! The columns are identified by the header line, which may be preceded by
+//! other lines (e.g. Altium writes some information about the design before
+//! it). Supports the column names used by common EDA tools and assembly
+//! houses, see [COLUMNS]. The Y axis is mirrored to point downwards.
+
+/// Recognized column names, normalized by [normalize]
+const COLUMNS: [(Column, &[&str]); 7] = [
+  (
+    Column::Ref,
+    &["ref", "reference", "designator", "refdes", "part"],
+  ),
+  (
+    Column::X,
+    &["x", "posx", "midx", "centerx", "locationx", "refx"],
+  ),
+  (
+    Column::Y,
+    &["y", "posy", "midy", "centery", "locationy", "refy"],
+  ),
+  (
+    Column::Rotation,
+    &["rot", "rotation", "angle", "orientation"],
+  ),
+  (Column::Side, &["side", "layer", "tb"]),
+  (Column::Value, &["val", "value", "comment"]),
+  (Column::Package, &["package", "footprint", "pattern"]),
+];
+
+/// Body sizes of chip packages by their imperial size code \[mm\]
+const CHIP_SIZES: [(&str, (f64, f64)); 10] = [
+  ("01005", (0.4, 0.2)),
+  ("0201", (0.6, 0.3)),
+  ("0402", (1.0, 0.5)),
+  ("0603", (1.6, 0.8)),
+  ("0805", (2.0, 1.25)),
+  ("1206", (3.2, 1.6)),
+  ("1210", (3.2, 2.5)),
+  ("1812", (4.5, 3.2)),
+  ("2010", (5.0, 2.5)),
+  ("2512", (6.3, 3.2)),
+];
+
+/// Placed component
+#[derive(Debug)]
+pub(crate) struct Placement {
+  pub reference: String,
+  /// Position (x, y) \[mm\]
+  pub pos: (f64, f64),
+  /// Rotation angle [°]
+  pub angle: f64,
+  pub back: bool,
+  pub value: String,
+  pub package: String,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Column {
+  Ref,
+  X,
+  Y,
+  Rotation,
+  Side,
+  Value,
+  Package,
+}
+
+/// Lowercase a column name and strip units like "(mm)" and non-alphanumerics
+fn normalize(name: &str) -> String {
+  name
+    .split(['(', '['])
+    .next()
+    .unwrap_or_default()
+    .chars()
+    .filter(char::is_ascii_alphanumeric)
+    .map(|c| c.to_ascii_lowercase())
+    .collect()
+}
+
+/// Guess the body size of a package from its name, if it is a chip package
+///
+/// Matches the imperial size codes used in package names like "0603",
+/// "R0603" or "R_0603_1608Metric", but only as a whole token which may be
+/// prefixed by letters, so names like "QFN-2010X" are not mistaken for chips.
+pub(crate) fn package_size(package: &str) -> Option<(f64, f64)> {
+  package
+    .split(|c: char| !c.is_ascii_alphanumeric())
+    .map(|x| x.trim_start_matches(|c: char| c.is_ascii_alphabetic()))
+    .find_map(|token| CHIP_SIZES.iter().find(|(code, _)| *code == token))
+    .map(|(_, size)| *size)
+}
+
+/// Split a line into its fields, respecting quotes
+fn split(line: &str, delimiter: char) -> Vec<String> {
+  let mut fields = vec![String::new()];
+  let mut quoted = false;
+  let mut chars = line.chars().peekable();
+  while let Some(c) = chars.next() {
+    let field = fields.last_mut().unwrap();
+    match c {
+      '"' if quoted && chars.peek() == Some(&'"') => {
+        field.push('"');
+        chars.next();
+      }
+      '"' => quoted = !quoted,
+      c if c == delimiter && !quoted => fields.push(String::new()),
+      c => field.push(c),
+    }
+  }
+  fields.iter().map(|x| x.trim().to_owned()).collect()
+}
+
+/// Parse a length, converting units to millimeters
+fn length(value: &str) -> Option<f64> {
+  let (number, scale) = if let Some(x) = value.strip_suffix("mm") {
+    (x, 1.0)
+  } else if let Some(x) = value.strip_suffix("mil") {
+    (x, 0.0254)
+  } else if let Some(x) = value.strip_suffix("in") {
+    (x, 25.4)
+  } else {
+    (value, 1.0)
+  };
+  number.trim().parse::<f64>().ok().map(|x| x * scale)
+}
+
+/// Read the components of a pick and place file
+///
+/// # Arguments
+///
+/// * `csv` - Content of the CSV file.
+///
+/// # Returns
+///
+/// Returns the components, or a description of the problem if the file is
+/// invalid.
+pub(crate) fn parse(csv: &str) -> Result<Vec<Placement>, String> {
+  let mut columns: Option<(char, Vec<Option<Column>>)> = None;
+  let mut placements = Vec::new();
+  for (index, line) in csv.lines().enumerate() {
+    let error = |message: String| format!("{} at line {}", message, index + 1);
+    if line.trim().is_empty() || line.starts_with('#') {
+      continue;
+    }
+    let Some((delimiter, columns)) = &columns else {
+      let delimiter = [',', ';', '\t']
+        .into_iter()
+        .max_by_key(|d| line.matches(*d).count())
+        .unwrap_or(',');
+      let header = split(line, delimiter)
+        .iter()
+        .map(|name| {
+          let name = normalize(name);
+          COLUMNS
+            .iter()
+            .find(|(_, names)| names.contains(&name.as_str()))
+            .map(|(column, _)| *column)
+        })
+        .collect::<Vec<_>>();
+      let required = [Column::Ref, Column::X, Column::Y];
+      if required.iter().all(|x| header.contains(&Some(*x))) {
+        columns = Some((delimiter, header));
+      }
+      continue;
+    };
+    let fields = split(line, *delimiter);
+    let get = |column: Column| {
+      columns
+        .iter()
+        .position(|x| *x == Some(column))
+        .and_then(|i| fields.get(i))
+        .map(String::as_str)
+    };
+    let reference = get(Column::Ref)
+      .filter(|x| !x.is_empty())
+      .ok_or_else(|| error("missing reference".into()))?;
+    let number =
+      |column: Column, name: &str, parse: fn(&str) -> Option<f64>| {
+        let value = get(column).unwrap_or_default();
+        parse(value)
+          .ok_or_else(|| error(format!("invalid {} \"{}\"", name, value)))
+      };
+    let x = number(Column::X, "X", length)?;
+    let y = number(Column::Y, "Y", length)?;
+    let angle = match get(Column::Rotation) {
+      Some(x) if !x.is_empty() => {
+        number(Column::Rotation, "rotation", |x| x.parse().ok())?
+      }
+      _ => 0.0,
+    };
+    let side = get(Column::Side).unwrap_or_default().to_ascii_lowercase();
+    let back = match side.as_str() {
+      "" | "top" | "t" | "front" | "f" | "f.cu" | "toplayer" => false,
+      "bottom" | "b" | "back" | "bot" | "b.cu" | "bottomlayer" => true,
+      _ => return Err(error(format!("invalid side \"{}\"", side))),
+    };
+    placements.push(Placement {
+      reference: reference.to_owned(),
+      pos: (x, -y + 0.0),
+      angle,
+      back,
+      value: get(Column::Value).unwrap_or_default().to_owned(),
+      package: get(Column::Package).unwrap_or_default().to_owned(),
+    });
+  }
+  match columns {
+    Some(_) => Ok(placements),
+    None => Err("missing header with reference and X/Y columns".into()),
+  }
+}
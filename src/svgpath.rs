@@ -0,0 +1,386 @@
+//! Minimal SVG path parser used for geometric computations
+
+/// Number of line segments used to approximate curves
+const CURVE_SEGMENTS: usize = 16;
+
+/// Flattened sub-path
+pub(crate) struct Polyline {
+  pub points: Vec<(f64, f64)>,
+  pub closed: bool,
+}
+
+/// Tokenizer for SVG path data
+struct Tokens<'a> {
+  data: &'a [u8],
+  pos: usize,
+}
+
+impl Tokens<'_> {
+  fn skip_separators(&mut self) {
+    while self.pos < self.data.len()
+      && (self.data[self.pos].is_ascii_whitespace()
+        || self.data[self.pos] == b',')
+    {
+      self.pos += 1;
+    }
+  }
+
+  fn command(&mut self) -> Option<u8> {
+    self.skip_separators();
+    let c = *self.data.get(self.pos)?;
+    if c.is_ascii_alphabetic() && c != b'e' && c != b'E' {
+      self.pos += 1;
+      Some(c)
+    } else {
+      None
+    }
+  }
+
+  fn has_number(&mut self) -> bool {
+    self.skip_separators();
+    self
+      .data
+      .get(self.pos)
+      .is_some_and(|c| c.is_ascii_digit() || b"+-.".contains(c))
+  }
+
+  fn number(&mut self) -> Option<f64> {
+    self.skip_separators();
+    let start = self.pos;
+    let mut seen_dot = false;
+    let mut seen_exp = false;
+    while let Some(&c) = self.data.get(self.pos) {
+      let is_sign = (c == b'+' || c == b'-')
+        && (self.pos == start || b"eE".contains(&self.data[self.pos - 1]));
+      if c.is_ascii_digit() || is_sign {
+        self.pos += 1;
+      } else if c == b'.' && !seen_dot && !seen_exp {
+        seen_dot = true;
+        self.pos += 1;
+      } else if (c == b'e' || c == b'E') && !seen_exp && self.pos > start {
+        seen_exp = true;
+        self.pos += 1;
+      } else {
+        break;
+      }
+    }
+    std::str::from_utf8(&self.data[start..self.pos])
+      .ok()?
+      .parse()
+      .ok()
+  }
+
+  fn flag(&mut self) -> Option<bool> {
+    self.skip_separators();
+    let c = *self.data.get(self.pos)?;
+    self.pos += 1;
+    match c {
+      b'0' => Some(false),
+      b'1' => Some(true),
+      _ => None,
+    }
+  }
+
+  fn point(&mut self) -> Option<(f64, f64)> {
+    Some((self.number()?, self.number()?))
+  }
+}
+
+/// Flatten an SVG path into polylines
+///
+/// Curves and arcs are approximated by line segments. Returns [None] if the
+/// path is malformed.
+pub(crate) fn flatten(path: &str) -> Option<Vec<Polyline>> {
+  let mut tokens = Tokens {
+    data: path.as_bytes(),
+    pos: 0,
+  };
+  let mut polylines: Vec<Polyline> = Vec::new();
+  let mut pos = (0.0, 0.0);
+  let mut start = (0.0, 0.0);
+  let mut last_ctrl: Option<(u8, (f64, f64))> = None;
+  let mut cmd = None;
+  loop {
+    let c = match tokens.command() {
+      Some(c) => c,
+      None if tokens.has_number() => match cmd? {
+        b'M' => b'L',
+        b'm' => b'l',
+        c => c,
+      },
+      None if tokens.pos < tokens.data.len() => return None,
+      None => break,
+    };
+    cmd = Some(c);
+    let rel = c.is_ascii_lowercase();
+    let abs = |p: (f64, f64), pos: (f64, f64)| {
+      if rel {
+        (pos.0 + p.0, pos.1 + p.1)
+      } else {
+        p
+      }
+    };
+    let mut ctrl = None;
+    let mut segment: Vec<(f64, f64)> = Vec::new();
+    match c.to_ascii_uppercase() {
+      b'M' => {
+        pos = abs(tokens.point()?, pos);
+        start = pos;
+        polylines.push(Polyline {
+          points: vec![pos],
+          closed: false,
+        });
+      }
+      b'L' => {
+        pos = abs(tokens.point()?, pos);
+        segment.push(pos);
+      }
+      b'H' => {
+        let x = tokens.number()?;
+        pos.0 = if rel { pos.0 + x } else { x };
+        segment.push(pos);
+      }
+      b'V' => {
+        let y = tokens.number()?;
+        pos.1 = if rel { pos.1 + y } else { y };
+        segment.push(pos);
+      }
+      b'C' | b'S' => {
+        let c1 = if c.eq_ignore_ascii_case(&b'C') {
+          abs(tokens.point()?, pos)
+        } else {
+          reflect(last_ctrl, b'C', pos)
+        };
+        let c2 = abs(tokens.point()?, pos);
+        let end = abs(tokens.point()?, pos);
+        segment.extend(sample(|t| {
+          let u = 1.0 - t;
+          (
+            u * u * u * pos.0
+              + 3.0 * u * u * t * c1.0
+              + 3.0 * u * t * t * c2.0
+              + t * t * t * end.0,
+            u * u * u * pos.1
+              + 3.0 * u * u * t * c1.1
+              + 3.0 * u * t * t * c2.1
+              + t * t * t * end.1,
+          )
+        }));
+        ctrl = Some((b'C', c2));
+        pos = end;
+      }
+      b'Q' | b'T' => {
+        let c1 = if c.eq_ignore_ascii_case(&b'Q') {
+          abs(tokens.point()?, pos)
+        } else {
+          reflect(last_ctrl, b'Q', pos)
+        };
+        let end = abs(tokens.point()?, pos);
+        segment.extend(sample(|t| {
+          let u = 1.0 - t;
+          (
+            u * u * pos.0 + 2.0 * u * t * c1.0 + t * t * end.0,
+            u * u * pos.1 + 2.0 * u * t * c1.1 + t * t * end.1,
+          )
+        }));
+        ctrl = Some((b'Q', c1));
+        pos = end;
+      }
+      b'A' => {
+        let radii = (tokens.number()?, tokens.number()?);
+        let rotation = tokens.number()?;
+        let large_arc = tokens.flag()?;
+        let sweep = tokens.flag()?;
+        let end = abs(tokens.point()?, pos);
+        segment.extend(arc(pos, radii, rotation, large_arc, sweep, end));
+        pos = end;
+      }
+      b'Z' => {
+        if let Some(polyline) = polylines.last_mut() {
+          polyline.closed = true;
+        }
+        pos = start;
+      }
+      _ => return None,
+    }
+    if !segment.is_empty() {
+      if polylines.last().is_none_or(|p| p.closed) {
+        // Implicit sub-path start after closing or without any move command.
+        polylines.push(Polyline {
+          points: vec![start],
+          closed: false,
+        });
+      }
+      if let Some(polyline) = polylines.last_mut() {
+        polyline.points.extend(segment);
+      }
+    }
+    last_ctrl = ctrl;
+  }
+  Some(polylines)
+}
+
+/// Split an SVG path into its sub-paths
+///
+/// Each sub-path starts with a move command. Relative move commands are made
+/// absolute, so the sub-paths can be used independently of each other.
+/// Returns [None] if the path is malformed.
+#[cfg(feature = "generic")]
+pub(crate) fn subpaths(path: &str) -> Option<Vec<String>> {
+  flatten(path)?;
+  let moves = path.match_indices(['M', 'm']).map(|(i, _)| i);
+  let moves = moves.filter(|i| !path[..*i].trim().is_empty());
+  let mut starts = std::iter::once(0).chain(moves).peekable();
+  let mut subpaths = Vec::new();
+  while let Some(start) = starts.next() {
+    let end = starts.peek().copied().unwrap_or(path.len());
+    let subpath = path[start..end].trim();
+    if start == 0 || subpath.starts_with('M') {
+      subpaths.push(subpath.to_owned());
+      continue;
+    }
+    // The relative move refers to the current point of the previous part.
+    let previous = flatten(&path[..start])?.pop()?;
+    let pos = match previous.closed {
+      true => *previous.points.first()?,
+      false => *previous.points.last()?,
+    };
+    let mut tokens = Tokens {
+      data: subpath.as_bytes(),
+      pos: 1,
+    };
+    let (dx, dy) = tokens.point()?;
+    // Further coordinate pairs of a relative move are relative lines.
+    let command = if tokens.has_number() { "l " } else { "" };
+    let rest = subpath[tokens.pos..].trim();
+    let (x, y) = (pos.0 + dx, pos.1 + dy);
+    let subpath = format!("M {} {} {}{}", x, y, command, rest);
+    subpaths.push(subpath.trim_end().to_owned());
+  }
+  Some(subpaths.into_iter().filter(|x| !x.is_empty()).collect())
+}
+
+/// Apply a function to all numbers of an SVG path
+///
+/// Everything else is kept as-is, as well as numbers not modified by the
+/// function (e.g. compact arc flags like "01").
+pub(crate) fn map_numbers(path: &str, f: impl Fn(f64) -> f64) -> String {
+  let mut tokens = Tokens {
+    data: path.as_bytes(),
+    pos: 0,
+  };
+  let mut result = String::with_capacity(path.len());
+  let mut copied = 0;
+  while tokens.pos < tokens.data.len() {
+    if !tokens.has_number() {
+      tokens.pos += 1;
+      continue;
+    }
+    let start = tokens.pos;
+    if let Some(value) = tokens.number() {
+      let mapped = f(value);
+      if mapped != value {
+        result += &path[copied..start];
+        result += &mapped.to_string();
+        copied = tokens.pos;
+      }
+    }
+    // Skip invalid numbers like a single sign.
+    tokens.pos = tokens.pos.max(start + 1);
+  }
+  result += &path[copied..];
+  result
+}
+
+/// Check whether an SVG path contains NaN or infinite numbers
+///
+/// These are formatted as "NaN", "inf" or "-inf", which are no valid
+/// numbers in SVG paths, but would silently break the rendering.
+pub(crate) fn has_non_finite(path: &str) -> bool {
+  path.contains("NaN") || path.contains("inf")
+}
+
+/// Get bounding box of an SVG path
+///
+/// Returns [None] for empty or malformed paths.
+pub(crate) fn bounds(path: &str) -> Option<((f64, f64), (f64, f64))> {
+  flatten(path)?
+    .iter()
+    .flat_map(|p| p.points.iter())
+    .fold(None, |bbox, p| {
+      let ((x0, y0), (x1, y1)) = bbox.unwrap_or((*p, *p));
+      Some(((x0.min(p.0), y0.min(p.1)), (x1.max(p.0), y1.max(p.1))))
+    })
+}
+
+fn reflect(
+  last_ctrl: Option<(u8, (f64, f64))>,
+  kind: u8,
+  pos: (f64, f64),
+) -> (f64, f64) {
+  match last_ctrl {
+    Some((k, c)) if k == kind => (2.0 * pos.0 - c.0, 2.0 * pos.1 - c.1),
+    _ => pos,
+  }
+}
+
+fn sample(f: impl Fn(f64) -> (f64, f64)) -> Vec<(f64, f64)> {
+  (1..=CURVE_SEGMENTS)
+    .map(|i| f(i as f64 / CURVE_SEGMENTS as f64))
+    .collect()
+}
+
+/// Sample an elliptical arc, see SVG specification appendix F.6.5
+fn arc(
+  start: (f64, f64),
+  radii: (f64, f64),
+  rotation: f64,
+  large_arc: bool,
+  sweep: bool,
+  end: (f64, f64),
+) -> Vec<(f64, f64)> {
+  let (mut rx, mut ry) = (radii.0.abs(), radii.1.abs());
+  if rx == 0.0 || ry == 0.0 || start == end {
+    return vec![end];
+  }
+  let (sin, cos) = rotation.to_radians().sin_cos();
+  let dx = (start.0 - end.0) / 2.0;
+  let dy = (start.1 - end.1) / 2.0;
+  let x1 = cos * dx + sin * dy;
+  let y1 = -sin * dx + cos * dy;
+  let lambda = (x1 * x1) / (rx * rx) + (y1 * y1) / (ry * ry);
+  if lambda > 1.0 {
+    rx *= lambda.sqrt();
+    ry *= lambda.sqrt();
+  }
+  let num = rx * rx * ry * ry - rx * rx * y1 * y1 - ry * ry * x1 * x1;
+  let den = rx * rx * y1 * y1 + ry * ry * x1 * x1;
+  let mut coef = (num / den).max(0.0).sqrt();
+  if large_arc == sweep {
+    coef = -coef;
+  }
+  let cx1 = coef * rx * y1 / ry;
+  let cy1 = -coef * ry * x1 / rx;
+  let cx = cos * cx1 - sin * cy1 + (start.0 + end.0) / 2.0;
+  let cy = sin * cx1 + cos * cy1 + (start.1 + end.1) / 2.0;
+  let angle = |ux: f64, uy: f64| uy.atan2(ux);
+  let theta1 = angle((x1 - cx1) / rx, (y1 - cy1) / ry);
+  let mut delta = angle((-x1 - cx1) / rx, (-y1 - cy1) / ry) - theta1;
+  if sweep && delta < 0.0 {
+    delta += std::f64::consts::TAU;
+  } else if !sweep && delta > 0.0 {
+    delta -= std::f64::consts::TAU;
+  }
+  let mut points = sample(|t| {
+    let (s, c) = (theta1 + delta * t).sin_cos();
+    (
+      cx + cos * rx * c - sin * ry * s,
+      cy + sin * rx * c + cos * ry * s,
+    )
+  });
+  // Avoid rounding errors at the end point.
+  if let Some(last) = points.last_mut() {
+    *last = end;
+  }
+  points
+}
@@ -0,0 +1,207 @@
+//! Minimal XML parser
+//!
+//! Only the subset of XML used by EDA tools is supported, i.e. no DTDs or
+//! custom entities.
+
+/// XML element
+#[derive(Debug, Default)]
+pub(crate) struct Element {
+  pub name: String,
+  pub attributes: Vec<(String, String)>,
+  pub children: Vec<Element>,
+  pub text: String,
+}
+
+impl Element {
+  pub(crate) fn attribute(&self, name: &str) -> Option<&str> {
+    self
+      .attributes
+      .iter()
+      .find(|(n, _)| n == name)
+      .map(|(_, v)| v.as_str())
+  }
+
+  pub(crate) fn children<'a>(
+    &'a self,
+    name: &'a str,
+  ) -> impl Iterator<Item = &'a Element> + 'a {
+    self.children.iter().filter(move |x| x.name == name)
+  }
+
+  pub(crate) fn child(&self, name: &str) -> Option<&Element> {
+    self.children.iter().find(|x| x.name == name)
+  }
+}
+
+/// Recursive descent XML parser
+struct Parser<'a> {
+  xml: &'a str,
+  pos: usize,
+}
+
+impl<'a> Parser<'a> {
+  fn rest(&self) -> &'a str {
+    &self.xml[self.pos..]
+  }
+
+  fn error(&self, message: &str) -> String {
+    let line = self.xml[..self.pos].matches('\n').count() + 1;
+    format!("{} at line {}", message, line)
+  }
+
+  /// Skip to after the next occurrence of `end`
+  fn skip_past(&mut self, end: &str) -> Result<(), String> {
+    let pos = self
+      .rest()
+      .find(end)
+      .ok_or_else(|| self.error("unexpected end of document"))?;
+    self.pos += pos + end.len();
+    Ok(())
+  }
+
+  /// Skip whitespace, comments, processing instructions and doctypes
+  fn skip_misc(&mut self) -> Result<(), String> {
+    loop {
+      self.pos = self.xml.len() - self.rest().trim_start().len();
+      if self.rest().starts_with("<!--") {
+        self.skip_past("-->")?;
+      } else if self.rest().starts_with("<?") {
+        self.skip_past("?>")?;
+      } else if self.rest().starts_with("<!") {
+        self.skip_past(">")?;
+      } else {
+        return Ok(());
+      }
+    }
+  }
+
+  fn name(&mut self) -> Result<String, String> {
+    let len = self
+      .rest()
+      .find(|c: char| c.is_whitespace() || "/>=".contains(c))
+      .unwrap_or(self.rest().len());
+    if len == 0 {
+      return Err(self.error("missing name"));
+    }
+    self.pos += len;
+    Ok(self.xml[self.pos - len..self.pos].to_owned())
+  }
+
+  fn element(&mut self) -> Result<Element, String> {
+    if !self.rest().starts_with('<') {
+      return Err(self.error("expected element"));
+    }
+    self.pos += 1;
+    let mut element = Element {
+      name: self.name()?,
+      ..Default::default()
+    };
+
+    // Attributes
+    loop {
+      self.pos = self.xml.len() - self.rest().trim_start().len();
+      if self.rest().starts_with("/>") {
+        self.pos += 2;
+        return Ok(element);
+      } else if self.rest().starts_with('>') {
+        self.pos += 1;
+        break;
+      }
+      let name = self.name()?;
+      let rest = self.rest().trim_start();
+      let value = rest.strip_prefix('=').unwrap_or_default().trim_start();
+      let quote = match value.chars().next() {
+        Some('"') => "\"",
+        Some('\'') => "'",
+        _ => return Err(self.error("invalid attribute")),
+      };
+      self.pos = self.xml.len() - value.len() + 1;
+      let start = self.pos;
+      self.skip_past(quote)?;
+      let value = unescape(&self.xml[start..self.pos - 1])
+        .ok_or_else(|| self.error("invalid entity"))?;
+      element.attributes.push((name, value));
+    }
+
+    // Content
+    loop {
+      if self.rest().starts_with("</") {
+        self.pos += 2;
+        if self.name()? != element.name {
+          return Err(self.error("mismatched closing tag"));
+        }
+        self.skip_past(">")?;
+        element.text = element.text.trim().to_owned();
+        return Ok(element);
+      } else if self.rest().starts_with("<![CDATA[") {
+        let start = self.pos + 9;
+        self.skip_past("]]>")?;
+        element.text += &self.xml[start..self.pos - 3];
+      } else if self.rest().starts_with("<!--") {
+        self.skip_past("-->")?;
+      } else if self.rest().starts_with("<?") {
+        self.skip_past("?>")?;
+      } else if self.rest().starts_with('<') {
+        element.children.push(self.element()?);
+      } else if self.rest().is_empty() {
+        return Err(self.error("unexpected end of document"));
+      } else {
+        let len = self.rest().find('<').unwrap_or(self.rest().len());
+        let text = unescape(&self.rest()[..len])
+          .ok_or_else(|| self.error("invalid entity"))?;
+        element.text += &text;
+        self.pos += len;
+      }
+    }
+  }
+}
+
+/// Replace entity and character references, returning [None] if invalid
+fn unescape(text: &str) -> Option<String> {
+  let mut out = String::with_capacity(text.len());
+  let mut rest = text;
+  while let Some(pos) = rest.find('&') {
+    out += &rest[..pos];
+    rest = &rest[pos + 1..];
+    let end = rest.find(';')?;
+    let c = match &rest[..end] {
+      "amp" => '&',
+      "lt" => '<',
+      "gt" => '>',
+      "quot" => '"',
+      "apos" => '\'',
+      x => {
+        let code = match x.strip_prefix("#x") {
+          Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+          None => x.strip_prefix('#')?.parse().ok()?,
+        };
+        char::from_u32(code)?
+      }
+    };
+    out.push(c);
+    rest = &rest[end + 1..];
+  }
+  out += rest;
+  Some(out)
+}
+
+/// Parse a document
+///
+/// # Arguments
+///
+/// * `xml` - The XML document.
+///
+/// # Returns
+///
+/// Returns the root element, or a description of the problem if the
+/// document is invalid.
+pub(crate) fn parse(xml: &str) -> Result<Element, String> {
+  let mut parser = Parser { xml, pos: 0 };
+  parser.skip_misc()?;
+  let root = parser.element()?;
+  parser.skip_misc()?;
+  if !parser.rest().is_empty() {
+    return Err(parser.error("unexpected content"));
+  }
+  Ok(root)
+}
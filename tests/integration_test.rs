@@ -15,6 +15,32 @@ fn test_empty() {
   assert!(html.contains("<html"));
 }
 
+#[test]
+fn test_default() {
+  let mut bom = InteractiveHtmlBom::default();
+  assert_eq!(bom.title(), "");
+  assert_eq!(bom.settings, RenderSettings::default());
+  assert_eq!(bom.settings.view_mode, ViewMode::default());
+  assert!(bom.generate_html().is_ok());
+  assert_eq!(bom.warnings(), vec![Warning::EmptyBoundingBox]);
+
+  let mut other = bom.clone();
+  bom.add_net("B");
+  bom.add_net("A");
+  other.add_net("A");
+  other.add_net("B");
+  assert_eq!(bom.generate_html(), other.generate_html());
+
+  bom.strictness = Strictness::Strict;
+  let err = bom.generate_html().unwrap_err();
+  assert_eq!(
+    err,
+    GenerateError::Diagnostic {
+      warning: Warning::EmptyBoundingBox
+    }
+  );
+}
+
 #[test]
 fn test_everything() {
   let mut bom = InteractiveHtmlBom::new(
@@ -25,17 +51,54 @@ fn test_everything() {
     (0.0, 0.0),
     (100.0, 100.0),
   );
+  assert_eq!(bom.title(), "Test Title");
+  assert_eq!(bom.bottom_left(), Point::new(0.0, 0.0));
+  assert_eq!(Angle::from(-270.0), Angle::from(90.0));
+  bom.set_date("2025-01-01");
+  bom.set_top_right((100.0, 90.0));
 
-  bom.view_mode = ViewMode::TopBottom;
-  bom.highlight_pin1 = HighlightPin1Mode::All;
-  bom.dark_mode = true;
-  bom.board_rotation = 45.0;
-  bom.offset_back_rotation = true;
-  bom.show_silkscreen = false;
-  bom.show_fabrication = false;
-  bom.show_pads = false;
+  bom.settings.view_mode = ViewMode::TopBottom;
+  bom.settings.layer_view = Some(LayerView::Back);
+  bom.settings.sort_column = Some("Value".into());
+  bom.settings.sort_order = SortOrder::Descending;
+  bom.settings.filter = "Value".into();
+  bom.settings.ref_lookup = "R1".into();
+  bom.settings.highlight_pin1 = HighlightPin1Mode::All;
+  bom.settings.dark_mode = true;
+  bom.settings.theme.pad = Some("#c0c0c0".into());
+  bom.settings.theme.silkscreen = Some("white".into());
+  bom.settings.board_rotation = 45.0;
+  bom.settings.offset_back_rotation = true;
+  bom.settings.kicad_text_formatting = true;
+  bom.settings.redraw_on_drag = false;
+  bom.settings.show_silkscreen = false;
+  bom.settings.show_fabrication = false;
+  bom.settings.show_paste = true;
+  bom.settings.show_mask = true;
+  bom.aux_layers.push(AuxiliaryLayer::new(
+    "Keepout",
+    "rgba(255, 0, 0, 0.5)",
+    true,
+  ));
+  bom.settings.show_pads = false;
+  bom.drill_table = true;
+  bom.view_3d = true;
   bom.checkboxes = vec!["Foo".into(), "Bar".into()];
   bom.fields = vec!["Field 1".into(), "Field 2".into()];
+  bom.settings.hidden_columns = vec!["Field 2".into()];
+  bom.settings.extra_config =
+    vec![("highlight_rows_on_click".into(), "true".into())];
+  bom.page_title = Some("ACME-board Rev3 - assembly".into());
+  bom.favicon = Some(Image::new(ImageFormat::Png, &[0x89, 0x50, 0x4e, 0x47]));
+  bom.logo = Some(Image::new(ImageFormat::Svg, b"<svg></svg>"));
+  bom
+    .images
+    .push(("Photo".into(), Image::new(ImageFormat::Png, b"PNG")));
+  bom.schematic_sheets.push(SchematicSheet::new(
+    "Sheet 1",
+    Image::new(ImageFormat::Svg, b"<svg></svg>"),
+    &[("R2", (0.5, 0.25)), ("X1", (0.1, 0.1))],
+  ));
   bom.user_header = "<!-- header -->".into();
   bom.user_footer = "<!-- footer -->".into();
   bom.user_js = "<!-- js -->".into();
@@ -75,6 +138,66 @@ fn test_everything() {
     0.1,
     false,
   ));
+  assert_eq!(
+    Drawing::value_text(DrawingLayer::FabricationBack, "M 0 0", 0.1),
+    bom.drawings[4]
+  );
+  assert_eq!(
+    Drawing::reference_text(DrawingLayer::SilkscreenBack, "", 0.1),
+    bom.drawings[2]
+  );
+  assert_eq!(
+    Drawing::polygon(DrawingLayer::Edge, "", 0.1, false),
+    bom.drawings[0]
+  );
+  bom.drawings.push(Drawing::arc(
+    DrawingLayer::Edge,
+    (50.0, 50.0),
+    10.0,
+    0.0,
+    270.0,
+    0.1,
+  ));
+  bom.drawings.push(Drawing::arc(
+    DrawingLayer::SilkscreenFront,
+    (50.0, 50.0),
+    10.0,
+    90.0,
+    -270.0,
+    0.1,
+  ));
+  bom.drawings.push(Drawing::rect(
+    DrawingLayer::FabricationFront,
+    (50.0, 50.0),
+    (10.0, 5.0),
+    30.0,
+    0.1,
+    true,
+  ));
+  bom.drawings.push(Drawing::polygon_from_points(
+    DrawingLayer::FabricationBack,
+    [(0.0, 0.0), (10.0, 0.0), (5.0, 5.0)],
+    0.1,
+    false,
+  ));
+  bom.drawings.push(Drawing::text(
+    DrawingKind::ReferenceText,
+    DrawingLayer::SilkscreenBack,
+    "R1",
+    (50.0, 50.0),
+    (1.0, 1.0),
+    90.0,
+    0.15,
+  ));
+  bom.drawings.push(Drawing::text(
+    DrawingKind::Text,
+    DrawingLayer::FabricationFront,
+    "Hello\nWörld!",
+    (50.0, 50.0),
+    (1.0, 1.0),
+    0.0,
+    0.15,
+  ));
 
   bom.tracks.push(Track::new(
     Layer::Front,
@@ -90,22 +213,64 @@ fn test_everything() {
     1.0,
     Some("net 1"),
   ));
+  let range = bom.add_tracks(vec![
+    Track::new(Layer::Front, (20.0, 0.0), (20.0, 100.0), 1.0, None),
+    Track::new(Layer::Back, (30.0, 0.0), (30.0, 100.0), 1.0, None),
+  ]);
+  assert_eq!(range, 2..4);
+  let gnd = bom.add_net("GND");
+  assert_eq!(bom.add_net("GND"), gnd);
+  let mut track =
+    Track::new(Layer::Front, (10.0, 0.0), (10.0, 100.0), 1.0, None);
+  track.set_net_id(Some(gnd));
+  bom.tracks.push(track);
+  let range = bom.add_track_polyline(
+    Layer::Back,
+    [(40.0, 0.0), (40.0, 50.0), (45.0, 55.0)],
+    0.5,
+    Some("GND"),
+  );
+  assert_eq!(range, 5..7);
+  assert_eq!(bom.tracks[6].start(), Point::new(40.0, 50.0));
+  assert_eq!(bom.tracks[6].net(), Some("GND"));
 
   bom
     .vias
-    .push(Via::new(&[Layer::Front], (50.0, 50.0), 1.0, 0.5, None));
+    .push(Via::new([Layer::Front], (50.0, 50.0), 1.0, 0.5, None));
   bom.vias.push(Via::new(
-    &[Layer::Front, Layer::Back],
+    [Layer::Front, Layer::Back],
     (50.0, 50.0),
     1.0,
     0.5,
     Some("net 2"),
   ));
+  bom.vias.push(Via::with_layer_span(
+    (0, 1),
+    4,
+    (60.0, 50.0),
+    0.6,
+    0.3,
+    Some("net 2"),
+  ));
+  bom.vias.push(Via::with_layer_span(
+    (1, 2),
+    4,
+    (70.0, 50.0),
+    0.6,
+    0.3,
+    None,
+  ));
 
   bom.zones.push(Zone::new(Layer::Front, "M 0 0", None));
   bom
     .zones
     .push(Zone::new(Layer::Back, "M 0 0", Some("net 3")));
+  bom.zones.push(Zone::with_holes(
+    Layer::Front,
+    "M 0 0 H 10 V 10 H 0 Z",
+    &["M 2 2 H 4 V 4 H 2 Z", "M 6 6 H 8 V 8 H 6 Z"],
+    Some("net 3"),
+  ));
 
   bom.footprints.push(Footprint::new(
     Layer::Front,
@@ -113,20 +278,89 @@ fn test_everything() {
     45.0,
     (-5.0, -5.0),
     (5.0, 5.0),
-    &["Value 1".into(), "Value 2".into()],
-    &[],
+    ["Value 1".into(), "Value 2".into()],
+    [],
     false,
   ));
-  bom.footprints.push(Footprint::new(
+  bom.outlines.push(BoardOutline::new(
+    "M 0 0 H 100 V 100 H 0 Z",
+    &["M 40 40 h 20 v 20 h -20 z", "M 70 70 A 5 5 0 0 1 80 80"],
+    0.1,
+  ));
+  bom.holes.push(Hole::new((5.0, 5.0), 3.2));
+  bom
+    .holes
+    .push(Hole::slot((10.0, 5.0), (1.0, 3.0), 30.0, true));
+  bom
+    .holes
+    .push(Hole::slot((15.0, 5.0), (3.0, 1.0), 0.0, false));
+  let mut copper = Drawing::polygon_from_points(
+    DrawingLayer::CopperFront,
+    [
+      Point::new(20.0, 20.0),
+      Point::new(30.0, 20.0),
+      Point::new(25.0, 28.0),
+    ],
+    0.2,
+    true,
+  );
+  copper.set_net(Some("GND"));
+  bom.drawings.push(copper);
+  let mut copper =
+    Drawing::arc(DrawingLayer::CopperBack, (25.0, 25.0), 5.0, 0.0, 90.0, 0.3);
+  copper.set_net(Some("GND"));
+  bom.drawings.push(copper);
+  bom.dimensions.push(Dimension::new(
+    Layer::Front,
+    (0.0, 100.0),
+    (100.0, 100.0),
+    -5.0,
+    None,
+    1.5,
+    0.15,
+  ));
+  bom.dimensions.push(Dimension::new(
+    Layer::Back,
+    (0.0, 0.0),
+    (0.0, 100.0),
+    5.0,
+    Some("100 mm"),
+    1.5,
+    0.15,
+  ));
+
+  let mut offset_pad = Pad::with_shape(
+    [Layer::Front, Layer::Back],
+    (7.0, 0.0),
+    90.0,
+    PadShape::Oval((2.0, 1.0)),
+    Some((0.5, 0.5)),
+    None,
+    false,
+  );
+  offset_pad.set_drill_offset((0.5, 0.0));
+  offset_pad.set_pin(Some("3"), Some("VCC"));
+  assert_eq!(
+    PadBuilder::new(PadShape::Oval((2.0, 1.0)))
+      .tht((0.5, 0.5))
+      .at((7.0, 0.0))
+      .angle(90.0)
+      .drill_offset((0.5, 0.0))
+      .pin(Some("3"), Some("VCC"))
+      .build(),
+    offset_pad
+  );
+
+  let mut footprint = Footprint::new(
     Layer::Front,
     (50.0, 50.0),
     45.0,
     (-5.0, -5.0),
     (5.0, 5.0),
-    &["Value 1".into(), "Value 2".into()],
-    &[
+    ["Value 1".into(), "Value 2".into()],
+    [
       Pad::new(
-        &[Layer::Front],
+        [Layer::Front],
         (0.0, -5.0),
         45.0,
         "M 0 0",
@@ -135,7 +369,7 @@ fn test_everything() {
         false,
       ),
       Pad::new(
-        &[Layer::Front, Layer::Back],
+        [Layer::Front, Layer::Back],
         (0.0, 5.0),
         45.0,
         "M 0 0",
@@ -143,21 +377,358 @@ fn test_everything() {
         Some("net 4"),
         true,
       ),
+      Pad::with_shape(
+        [Layer::Front],
+        (1.0, 0.0),
+        0.0,
+        PadShape::Rect((1.0, 0.5)),
+        None,
+        None,
+        false,
+      ),
+      Pad::with_shape(
+        [Layer::Front, Layer::Back],
+        (2.0, 0.0),
+        0.0,
+        PadShape::Circle(1.0),
+        Some((0.5, 0.5)),
+        None,
+        false,
+      ),
+      Pad::with_shape(
+        [Layer::Back],
+        (3.0, 0.0),
+        90.0,
+        PadShape::Oval((1.0, 0.5)),
+        None,
+        None,
+        false,
+      ),
+      Pad::with_shape(
+        [Layer::Back],
+        (4.0, 0.0),
+        0.0,
+        PadShape::RoundRect((1.0, 0.5), 0.1),
+        None,
+        Some("net 4"),
+        false,
+      ),
+      Pad::with_shape(
+        [Layer::Front],
+        (5.0, 0.0),
+        0.0,
+        PadShape::Corners(
+          (1.0, 0.5),
+          [
+            PadCorner::Chamfer(0.1),
+            PadCorner::Round(0.1),
+            PadCorner::Round(0.1),
+            PadCorner::Chamfer(0.1),
+          ],
+        ),
+        None,
+        None,
+        false,
+      ),
+      Pad::with_shape(
+        [Layer::Front],
+        (6.0, 0.0),
+        0.0,
+        PadShape::Corners(
+          (1.0, 0.5),
+          [
+            PadCorner::Sharp,
+            PadCorner::Round(0.1),
+            PadCorner::Round(0.2),
+            PadCorner::Chamfer(0.1),
+          ],
+        ),
+        None,
+        None,
+        false,
+      ),
+      offset_pad,
     ],
     true,
+  );
+  footprint.add_drawing(Drawing::rect(
+    DrawingLayer::FabricationFront,
+    (50.0, 50.0),
+    (10.0, 10.0),
+    45.0,
+    0.1,
+    false,
+  ));
+  footprint.add_drawing(Drawing::text(
+    DrawingKind::ReferenceText,
+    DrawingLayer::SilkscreenBack,
+    "R2",
+    (50.0, 50.0),
+    (1.0, 1.0),
+    45.0,
+    0.15,
+  ));
+  footprint.add_drawing(Drawing::rect(
+    DrawingLayer::PasteFront,
+    (50.0, 50.0),
+    (1.0, 1.0),
+    0.0,
+    0.0,
+    true,
+  ));
+  footprint.add_drawing(Drawing::rect(
+    DrawingLayer::MaskBack,
+    (50.0, 50.0),
+    (1.2, 1.2),
+    0.0,
+    0.0,
+    true,
+  ));
+  footprint.add_drawing(Drawing::rect(
+    DrawingLayer::AuxiliaryFront(0),
+    (50.0, 50.0),
+    (3.0, 3.0),
+    0.0,
+    0.1,
+    false,
   ));
+  footprint.detect_pin1();
+  footprint.set_checked("Foo", true);
+  footprint.add_tooltip_field("Description", "Resistor 1%");
+  assert_eq!(footprint.layer(), &Layer::Front);
+  assert_eq!(footprint.pos(), Point::new(50.0, 50.0));
+  assert!(footprint.pads().iter().any(|p| p.pin1()));
+  bom.footprints.push(footprint);
 
-  bom
-    .bom_front
-    .push(vec![RefMap::new("R1", 0), RefMap::new("R2", 1)]);
-  bom
-    .bom_back
-    .push(vec![RefMap::new("R1", 0), RefMap::new("R2", 1)]);
-  bom.bom_both.push(vec![RefMap::new("R1", 0)]);
-  bom.bom_both.push(vec![RefMap::new("R2", 1)]);
+  bom.bom_front.push(vec![
+    RefMap::new("R1", FootprintId::from(0)),
+    RefMap::new("R2", FootprintId::from(1)),
+  ]);
+  bom.bom_back.push(vec![
+    RefMap::new("R1", FootprintId::from(0)),
+    RefMap::new("R2", FootprintId::from(1)),
+  ]);
+  bom.add_bom_row(LayerView::FrontAndBack, &[("R1", FootprintId::from(0))]);
+  bom.add_single(LayerView::FrontAndBack, "R2", FootprintId::from(1));
+
+  assert_eq!(bom.clone(), bom);
+  let warnings = vec![
+    Warning::PadLayerMismatch {
+      footprint: FootprintId::from(1),
+      pad: 4,
+    },
+    Warning::PadLayerMismatch {
+      footprint: FootprintId::from(1),
+      pad: 5,
+    },
+    Warning::EmptyDrawing { index: 0 },
+    Warning::EmptyDrawing { index: 2 },
+    Warning::OpenOutline { index: 5 },
+  ];
+  assert_eq!(
+    bom.warnings(),
+    [
+      vec![
+        Warning::MultipleBomRows {
+          footprint: FootprintId::from(0)
+        },
+        Warning::MultipleBomRows {
+          footprint: FootprintId::from(1)
+        },
+      ],
+      warnings.clone(),
+    ]
+    .concat()
+  );
+  let mut misplaced = bom.clone();
+  misplaced.drawings.push(Drawing::polygon(
+    DrawingLayer::SilkscreenFront,
+    "M 200 200 H 210",
+    0.1,
+    false,
+  ));
+  misplaced.tracks[0] =
+    Track::new(Layer::Front, (0.0, 0.0), (1.0, 0.0), 0.0, None);
+  misplaced.bom_front.clear();
+  misplaced.bom_back.clear();
+  misplaced.bom_both.clear();
+  misplaced
+    .outlines
+    .push(BoardOutline::new("M 0 0 X 1", &[], 0.1));
+  assert_eq!(
+    misplaced.warnings(),
+    [
+      vec![
+        Warning::DrawingOutsideBoard {
+          index: bom.drawings.len()
+        },
+        Warning::UnreferencedFootprint {
+          footprint: FootprintId::from(1)
+        },
+      ],
+      warnings,
+      vec![
+        Warning::EmptyOutline { index: 1 },
+        Warning::ZeroWidthTrack { index: 0 },
+      ],
+    ]
+    .concat()
+  );
 
   let html = bom.generate_html().unwrap();
   assert!(html.contains("<html"));
+  assert!(html.contains("data:image/svg+xml;base64,PHN2Zz48L3N2Zz4="));
+  assert_eq!(bom.clone().generate_html().unwrap(), html);
+  let mut light = bom.settings.clone();
+  light.dark_mode = false;
+  let variants = bom
+    .generate_html_variants(&[bom.settings.clone(), light])
+    .unwrap();
+  assert_eq!(variants[0], html);
+  assert!(variants[1].contains("\"dark_mode\":false"));
+  assert!(html.contains(
+    "\"images\":[{\"title\":\"Photo\",\"src\":\"data:image/png;base64,UE5H\"}]"
+  ));
+
+  let json = bom.generate_pcbdata_json().unwrap();
+  assert!(json.starts_with("{\"ibom_version\":"));
+  assert!(json.contains("\"nets\":[\"GND\","));
+  let json = bom.generate_config_json().unwrap();
+  assert!(json.contains("\"dark_mode\":true"));
+  assert!(json.contains("\"title\":\"Sheet 1\""));
+  assert!(json.contains("\"locations\":[[1,0.5,0.25]]"));
+  assert!(json.contains(
+    "\"view_3d\":{\"thickness\":1.6,\"outlines\":[[[0,0],[100,0],[100,100],"
+  ));
+
+  let mut split = bom.clone();
+  split.pcbdata_url = Some("pcbdata.js".into());
+  let split_html = split.generate_html().unwrap();
+  assert!(split_html.contains("src=\"pcbdata.js\""));
+  assert!(!split_html.contains("var pcbdata"));
+  let js = split.generate_pcbdata_js().unwrap();
+  assert!(js.starts_with("var pcbdata = "));
+  split.data_encoding = DataEncoding::Json;
+  let js = split.generate_pcbdata_js().unwrap();
+  assert!(js.starts_with("var pcbdata = {\"ibom_version\":"));
+  split.data_encoding = DataEncoding::Deflate;
+  let js = split.generate_pcbdata_js().unwrap();
+  assert!(js.contains("var pcbdata = JSON.parse(IbomInflate.inflateBase64(\""));
+  split.data_encoding = DataEncoding::Cbor;
+  let js = split.generate_pcbdata_js().unwrap();
+  assert!(js.contains("var pcbdata = IbomCbor.decode("));
+
+  let mut coarse = bom.clone();
+  coarse.decimals = 1;
+  assert!(coarse.generate_html().unwrap().len() < html.len());
+
+  let mut external = bom.clone();
+  external.assets_url = Some("https://example.com/ibom/".into());
+  let external_html = external.generate_html().unwrap();
+  assert!(external_html.contains("src=\"https://example.com/ibom/ibom.js\""));
+  assert!(external_html.contains("href=\"https://example.com/ibom/ibom.css\""));
+  assert!(external_html.len() < html.len() / 2);
+  let assets = external.generate_assets();
+  assert!(assets.iter().any(|(name, _)| *name == "ibom-ext.js"));
+
+  let mut minified = bom.clone();
+  minified.minify = true;
+  let minified_html = minified.generate_html().unwrap();
+  assert!(minified_html.len() < html.len());
+  assert!(!minified_html.contains("/* PCB rendering code */"));
+  for notice in ["Split.js", "MIT License", "PEP v", "Copyright (c) 2013"] {
+    assert!(minified_html.contains(notice), "{notice}");
+  }
+
+  bom.verify_html(&html).unwrap();
+  bom.verify_html(&variants[1]).unwrap();
+  split.verify_html(&split_html).unwrap();
+  minified.verify_html(&minified_html).unwrap();
+  for encoding in [
+    DataEncoding::Json,
+    DataEncoding::Deflate,
+    DataEncoding::Cbor,
+  ] {
+    let mut encoded = bom.clone();
+    encoded.data_encoding = encoding;
+    encoded
+      .verify_html(&encoded.generate_html().unwrap())
+      .unwrap();
+  }
+  let start = html.find("var config = ").unwrap();
+  let end = start + html[start..].find('\n').unwrap();
+  let unreplaced = format!("{}///CONFIG///{}", &html[..start], &html[end..]);
+  let err = bom.verify_html(&unreplaced).unwrap_err();
+  assert_eq!(
+    err.to_string(),
+    "Invalid generated HTML: placeholder ///CONFIG/// not replaced."
+  );
+  let err = bom.verify_html(&html.replacen("<", "<!-- -->", 1));
+  assert_eq!(
+    err.unwrap_err().to_string(),
+    "Invalid generated HTML: document does not match the template."
+  );
+  assert!(coarse.verify_html(&html).is_err());
+
+  // Placeholder-like user content and data are not mistaken as placeholders,
+  // and user-defined JavaScript does not shadow the config.
+  let mut user = bom.clone();
+  user.data_encoding = DataEncoding::Json;
+  user.fields[0] = "///NC///".into();
+  let mut fpt = FootprintBuilder::new();
+  for _ in &user.fields {
+    fpt.field("///NC///");
+  }
+  let id = user.add_footprint(fpt.build());
+  user.add_single(LayerView::FrontAndBack, "///NC///", id);
+  user.user_header = "<!-- ///TODO/// -->".into();
+  user.user_js = "var config = 42;\nvar pcbdata = 42;\n".into();
+  user.verify_html(&user.generate_html().unwrap()).unwrap();
+
+  let mut buf = Vec::new();
+  bom.generate_html_to(&mut buf).unwrap();
+  assert_eq!(String::from_utf8(buf).unwrap(), html);
+
+  let dir = std::env::temp_dir()
+    .join(format!("interactive-html-bom-test-{}", std::process::id()));
+  std::fs::create_dir_all(&dir).unwrap();
+  let path = dir.join("bom.html");
+  std::thread::scope(|s| {
+    for _ in 0..4 {
+      s.spawn(|| bom.write_html_file(&path).unwrap());
+    }
+  });
+  assert_eq!(std::fs::read_to_string(&path).unwrap(), html);
+  assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 1);
+  std::fs::remove_dir_all(&dir).unwrap();
+
+  let mut patched = bom.clone();
+  let fpt = patched.footprints[1].clone();
+  assert_eq!(
+    patched.replace_footprint(FootprintId::from(1), fpt.clone()),
+    Some(fpt.clone())
+  );
+  assert_eq!(patched.replace_footprint(FootprintId::from(2), fpt), None);
+  let removed = patched.remove_footprint(FootprintId::from(0)).unwrap();
+  assert_eq!(removed.footprint, bom.footprints[0]);
+  assert_eq!(
+    removed.references,
+    vec![RefMap::new("R1", FootprintId::from(0))]
+  );
+  assert_eq!(removed.remap(FootprintId::from(0)), None);
+  assert_eq!(
+    removed.remap(FootprintId::from(1)),
+    Some(FootprintId::from(0))
+  );
+  assert_eq!(patched.remove_footprint(FootprintId::from(1)), None);
+  assert_eq!(
+    patched.bom_front,
+    vec![vec![RefMap::new("R2", FootprintId::from(0))]]
+  );
+  assert_eq!(
+    patched.bom_both,
+    vec![vec![RefMap::new("R2", FootprintId::from(0))]]
+  );
 }
 
 #[test]
@@ -171,14 +742,24 @@ fn test_invalid_footprint_id() {
     (100.0, 100.0),
   );
 
-  bom.bom_both.push(vec![RefMap::new("R1", 0)]);
+  bom
+    .bom_both
+    .push(vec![RefMap::new("R1", FootprintId::from(0))]);
 
   let err = bom.generate_html().unwrap_err();
-  assert_eq!(err, "Invalid footprint ID.");
+  assert_eq!(err, GenerateError::InvalidFootprintId { row: 0, index: 0 });
+
+  bom.strictness = Strictness::Lenient;
+  assert!(bom.generate_html().is_ok());
+  assert!(bom.generate_generic_json().is_ok());
+  assert_eq!(
+    bom.warnings(),
+    vec![Warning::DroppedReference { row: 0, index: 0 }]
+  );
 }
 
 #[test]
-fn test_inconsistent_fields() {
+fn test_invalid_copper_path() {
   let mut bom = InteractiveHtmlBom::new(
     "Test Title",
     "Test Company",
@@ -188,19 +769,1833 @@ fn test_inconsistent_fields() {
     (100.0, 100.0),
   );
 
-  bom.fields = vec!["Field 1".into()];
+  bom.drawings.push(Drawing::polygon(
+    DrawingLayer::CopperFront,
+    "M 0 0 L 10 0 Q",
+    0.1,
+    false,
+  ));
+  let err = bom.generate_html().unwrap_err();
+  assert_eq!(err, GenerateError::InvalidCopperPath);
 
-  bom.footprints.push(Footprint::new(
+  bom.strictness = Strictness::Lenient;
+  let json = bom.generate_pcbdata_json().unwrap();
+  assert!(json.contains("\"tracks\":{\"F\":[],\"B\":[]}"));
+  assert_eq!(
+    bom.warnings(),
+    vec![
+      Warning::DroppedDrawing {
+        footprint: None,
+        index: 0
+      },
+      Warning::EmptyDrawing { index: 0 },
+    ]
+  );
+}
+
+#[test]
+fn test_non_finite_number() {
+  let mut bom = InteractiveHtmlBom::new(
+    "Test Title",
+    "Test Company",
+    "Test Revision",
+    "Test Date",
+    (0.0, 0.0),
+    (100.0, 100.0),
+  );
+
+  bom.tracks.push(Track::new(
     Layer::Front,
-    (50.0, 50.0),
-    45.0,
-    (-5.0, -5.0),
-    (5.0, 5.0),
-    &["Value 1".into(), "Value 2".into()],
-    &[],
+    (0.0, 0.0),
+    (10.0, 0.0),
+    f64::NAN,
+    None,
+  ));
+
+  let err = bom.generate_html().unwrap_err();
+  assert_eq!(
+    err,
+    GenerateError::NonFiniteNumber {
+      path: "tracks.F[0].width".into()
+    }
+  );
+
+  bom.tracks.clear();
+  let svgpath = SvgPathBuilder::new()
+    .move_to((0.0, 0.0))
+    .line_to((f64::NAN, 1.0))
+    .build();
+  bom.drawings.push(Drawing::polygon(
+    DrawingLayer::SilkscreenFront,
+    &svgpath,
+    0.1,
     false,
   ));
+  let err = bom.generate_html().unwrap_err();
+  assert_eq!(
+    err,
+    GenerateError::NonFiniteNumber {
+      path: "drawings.silkscreen.F[0].svgpath".into()
+    }
+  );
+
+  bom.drawings.clear();
+  bom.settings.board_rotation = f64::NAN;
+  let err = bom.generate_html().unwrap_err();
+  assert_eq!(
+    err,
+    GenerateError::NonFiniteNumber {
+      path: "config.board_rotation".into()
+    }
+  );
+}
+
+#[test]
+fn test_html_escaping() {
+  let mut bom = InteractiveHtmlBom::new(
+    "Test Title",
+    "Test Company",
+    "Test Revision",
+    "Test Date",
+    (0.0, 0.0),
+    (100.0, 100.0),
+  );
+
+  bom.fields = vec!["<b>Value</b>".into()];
+  bom.settings.filter = "</script>".into();
+
+  let html = bom.generate_html().unwrap();
+  assert!(html.contains("\"fields\":[\"&lt;b&gt;Value&lt;/b&gt;\"]"));
+  assert!(html.contains("\"filter\":\"\\u003c/script>\""));
+
+  let mut pad = PadBuilder::new(PadShape::Rect((1.0, 1.0))).build();
+  pad.set_pin(Some("<1>"), Some("A&B"));
+  let mut fpt = FootprintBuilder::new().field("").pad(pad).build();
+  fpt.add_tooltip_field("<i>Note</i>", "\"x\"");
+  bom.add_footprint(fpt);
+  bom.data_encoding = DataEncoding::Json;
+  let js = bom.generate_pcbdata_js().unwrap();
+  assert!(js.contains("\"pinnumber\":\"&lt;1&gt;\",\"pinname\":\"A&amp;B\""));
+  assert!(js.contains("[\"&lt;i&gt;Note&lt;/i&gt;\",\"&quot;x&quot;\"]"));
+
+  // Data exported for other tools is not escaped.
+  let json = bom.generate_pcbdata_json().unwrap();
+  assert!(json.contains("\"pinnumber\":\"<1>\",\"pinname\":\"A&B\""));
+  assert!(json.contains("[\"<i>Note</i>\",\"\\\"x\\\"\"]"));
+  assert!(json.contains("\"fields\":{\"0\":[\"\"]}"));
+
+  bom.sanitize_user_content = true;
+  bom.user_footer = "<script>alert(1)</script>".into();
+  let html = bom.generate_html().unwrap();
+  assert!(html.contains("&lt;script&gt;alert(1)&lt;/script&gt;"));
+  bom.user_js = "alert(1)".into();
+  let err = bom.generate_html().unwrap_err();
+  assert_eq!(err, GenerateError::UntrustedUserJs);
+}
+
+#[test]
+fn test_theme_colors() {
+  let mut bom = InteractiveHtmlBom::new(
+    "Test Title",
+    "Test Company",
+    "Test Revision",
+    "Test Date",
+    (0.0, 0.0),
+    (100.0, 100.0),
+  );
+
+  bom.settings.theme.pad = Some("#d04040".into());
+  bom.settings.theme.copper = Some("rgb(208 64 64 / 50%)".into());
+  bom.settings.theme.edge = Some("rebeccapurple".into());
+  let html = bom.generate_html().unwrap();
+  assert!(html.contains("  --pad-color: #d04040;\n"));
+  assert!(html.contains("  --track-color: rgb(208 64 64 / 50%);\n"));
+  assert!(html.contains("  --pcb-edge-color: rebeccapurple;\n"));
+
+  for color in [
+    "",
+    "red;}</style><script>alert(1)</script>",
+    "red; background: url(x)",
+    "rgb(1, 2, 3)}",
+    "rgb(1 /* 2 */ 3)",
+    "url(\"x\")",
+  ] {
+    bom.settings.theme.silkscreen = Some(color.into());
+    let err = bom.generate_html().unwrap_err();
+    assert_eq!(
+      err,
+      GenerateError::InvalidColor {
+        color: color.into()
+      }
+    );
+  }
+}
+
+#[test]
+fn test_custom_template() {
+  let mut bom = InteractiveHtmlBom::default();
+  bom.template =
+    Some("<p>///NOTICE///</p><script>///CONFIG///</script>".into());
+  bom.placeholders = vec![("///NOTICE///".into(), "<b>Draft</b>".into())];
+  let html = bom.generate_html().unwrap();
+  assert!(html.starts_with("<p><b>Draft</b></p><script>var config = {"));
 
+  bom.sanitize_user_content = true;
+  let html = bom.generate_html().unwrap();
+  assert!(html.starts_with("<p>&lt;b&gt;Draft&lt;/b&gt;</p>"));
+
+  bom.placeholders = vec![(String::new(), String::new())];
   let err = bom.generate_html().unwrap_err();
-  assert_eq!(err, "Inconsistent number of fields.");
+  assert_eq!(err, GenerateError::EmptyPlaceholder);
+}
+
+#[test]
+fn test_csp_nonce() {
+  let mut bom = InteractiveHtmlBom::default();
+  bom.csp_nonce = Some("r4nd0m".into());
+  let html = bom.generate_html().unwrap();
+  assert!(html.contains("<script nonce=\"r4nd0m\" type=\"text/javascript\" >"));
+  assert!(html.contains("<style nonce=\"r4nd0m\" type=\"text/css\">"));
+  assert!(!html.contains(" onclick=\""));
+  assert!(html.contains(".onclick = function (event) { topToggle() };"));
+}
+
+#[test]
+fn test_golden_output() {
+  let mut bom = InteractiveHtmlBom::new(
+    "Test Title",
+    "Test Company",
+    "Test Revision",
+    "Test Date",
+    (0.0, 0.0),
+    (10.0, 10.0),
+  );
+  bom.fields = vec!["Value".into()];
+  bom
+    .outlines
+    .push(BoardOutline::new("M 0 0 H 10 V 10 H 0 Z", &[], 0.1));
+  bom.tracks.push(Track::new(
+    Layer::Front,
+    (1.0, 1.0),
+    (9.0, 1.0),
+    0.25,
+    Some("GND"),
+  ));
+  let pad = PadBuilder::new(PadShape::Oval((2.0, 1.0)))
+    .tht((0.5, 0.5))
+    .angle(30.0)
+    .drill_offset((0.5, 0.0))
+    .net("GND")
+    .pin1()
+    .build();
+  let id = bom.add_footprint(
+    FootprintBuilder::new()
+      .at((5.0, 5.0))
+      .angle(30.0)
+      .bbox((-1.0, -1.0), (1.0, 1.0))
+      .field("1k")
+      .pad(pad)
+      .build(),
+  );
+  bom.add_single(LayerView::FrontAndBack, "R1", id);
+
+  // Everything except the viewer version, which changes with updates.
+  let json = bom.generate_pcbdata_json().unwrap();
+  let (_, json) = json.split_once("\",").unwrap();
+  assert_eq!(
+    json,
+    concat!(
+      r#""metadata":{"title":"Test Title","company":"Test Company","#,
+      r#""revision":"Test Revision","date":"Test Date"},"#,
+      r#""edges_bbox":{"minx":0,"maxx":10,"miny":0,"maxy":10},"#,
+      r#""edges":[{"svgpath":"M 0 0 H 10 V 10 H 0 Z","filled":false,"#,
+      r#""type":"polygon","width":0.1}],"drawings":{"silkscreen":{"F":[],"#,
+      r#""B":[]},"fabrication":{"F":[],"B":[]}},"tracks":{"F":[{"start":[1,"#,
+      r#"1],"end":[9,1],"width":0.25,"net":"GND"}],"B":[]},"zones":{"F":[],"#,
+      r#""B":[]},"font_data":{},"nets":["GND"],"#,
+      r#""footprints":[{"bbox":{"pos":[5,5],"angle":30,"relpos":[-1,-1],"#,
+      r#""size":[2,2]},"drawings":[],"layer":"F","pads":[{"layers":["F","#,
+      r#""B"],"pos":[0.433013,-0.25],"angle":30,"offset":[-0.5,0],"#,
+      r#""shape":"oval","size":[2,1],"type":"th","drillsize":[0.5,0.5],"#,
+      r#""drillshape":"circle","net":"GND","pin1":1}]}],"bom":{"F":[],"#,
+      r#""B":[],"both":[[["R1",0]]],"skipped":[],"fields":{"0":["1k"]}},"#,
+      r#""tooltips":{}}"#,
+    )
+  );
+}
+
+#[test]
+fn test_cbor_data() {
+  let mut bom = InteractiveHtmlBom::new(
+    "Ω – 10µF",
+    "Test Company",
+    "Test Revision",
+    "Test Date",
+    (-100000.0, -0.5),
+    (70000.25, 300.0),
+  );
+  bom.fields = vec!["Value".into()];
+  bom.tracks.push(Track::new(
+    Layer::Back,
+    (-1.5, 1e-6),
+    (65536.0, 4294967296.0),
+    0.25,
+    Some("Ü&<>"),
+  ));
+  let pad = PadBuilder::new(PadShape::Rect((1.0, 1.0))).pin1().build();
+  let id = bom.add_footprint(
+    FootprintBuilder::new()
+      .field("x".repeat(300))
+      .pad(pad)
+      .build(),
+  );
+  bom.add_single(LayerView::Back, "R1", id);
+  bom.data_encoding = DataEncoding::Json;
+  let json = bom.generate_pcbdata_js().unwrap();
+  let json = json.strip_prefix("var pcbdata = ").unwrap();
+
+  // Decoded by the verification.
+  bom.data_encoding = DataEncoding::Cbor;
+  let html = bom.generate_html().unwrap();
+  bom.verify_html(&html).unwrap();
+
+  // Decoded by the viewer, if Node.js is available.
+  let js = bom.generate_pcbdata_js().unwrap();
+  let dir = std::env::temp_dir()
+    .join(format!("interactive-html-bom-cbor-{}", std::process::id()));
+  std::fs::create_dir_all(&dir).unwrap();
+  let path = dir.join("pcbdata.js");
+  std::fs::write(&path, js + "\nconsole.log(JSON.stringify(pcbdata));\n")
+    .unwrap();
+  let output = std::process::Command::new("node").arg(&path).output();
+  std::fs::remove_dir_all(&dir).unwrap();
+  match output {
+    Ok(output) => {
+      assert!(output.status.success(), "{:?}", output);
+      let decoded: serde_json::Value =
+        serde_json::from_slice(&output.stdout).unwrap();
+      assert_eq!(
+        decoded,
+        serde_json::from_str::<serde_json::Value>(json).unwrap()
+      );
+    }
+    Err(err) => eprintln!("Skipping decoding in Node.js: {}", err),
+  }
+}
+
+#[test]
+fn test_inconsistent_fields() {
+  let mut bom = InteractiveHtmlBom::new(
+    "Test Title",
+    "Test Company",
+    "Test Revision",
+    "Test Date",
+    (0.0, 0.0),
+    (100.0, 100.0),
+  );
+
+  bom.fields = vec!["Field 1".into()];
+
+  bom.footprints.push(Footprint::new(
+    Layer::Front,
+    (50.0, 50.0),
+    45.0,
+    (-5.0, -5.0),
+    (5.0, 5.0),
+    ["Value 1".into(), "Value 2".into()],
+    [],
+    false,
+  ));
+
+  let err = bom.generate_html().unwrap_err();
+  assert_eq!(
+    err,
+    GenerateError::FieldCountMismatch {
+      footprint: FootprintId::from(0)
+    }
+  );
+
+  bom.strictness = Strictness::Lenient;
+  assert!(bom.generate_html().is_ok());
+  assert!(bom.generate_generic_json().is_ok());
+  assert_eq!(
+    bom.warnings(),
+    vec![Warning::ResizedFields {
+      footprint: FootprintId::from(0)
+    }]
+  );
+  bom.strictness = Strictness::Normal;
+
+  let fpt = bom.footprints.pop().unwrap();
+  let err = bom.try_add_footprint(fpt.clone()).unwrap_err();
+  assert_eq!(err.expected, 1);
+  assert_eq!(*err.footprint, fpt);
+  assert_eq!(
+    err.to_string(),
+    "Footprint has 2 fields [\"Value 1\", \"Value 2\"] instead of 1."
+  );
+  assert!(bom.footprints.is_empty());
+}
+
+#[test]
+fn test_group_bom() {
+  let mut bom = InteractiveHtmlBom::new(
+    "Test Title",
+    "Test Company",
+    "Test Revision",
+    "Test Date",
+    (0.0, 0.0),
+    (100.0, 100.0),
+  );
+  bom.fields = vec!["Value".into(), "MPN".into()];
+  let footprint = |layer: Layer, value: &str, mpn: &str, mount: bool| {
+    FootprintBuilder::new()
+      .layer(layer)
+      .bbox((-1.0, -1.0), (1.0, 1.0))
+      .field(value)
+      .field(mpn)
+      .mount(mount)
+      .build()
+  };
+  let refs = [
+    RefMap::new(
+      "R1",
+      bom.add_footprint(footprint(Layer::Front, "1k", "A", true)),
+    ),
+    RefMap::new(
+      "R2",
+      bom.add_footprint(footprint(Layer::Back, "1k", "B", true)),
+    ),
+    RefMap::new(
+      "R3",
+      bom.add_footprint(footprint(Layer::Front, "2k", "A", true)),
+    ),
+    RefMap::new(
+      "R4",
+      bom.add_footprint(footprint(Layer::Front, "1k", "A", false)),
+    ),
+  ];
+  bom.group_bom(&refs, &["Value"]).unwrap();
+  assert_eq!(bom.bom_both.len(), 3);
+  assert_eq!(bom.bom_both[0].len(), 2);
+  assert_eq!(bom.bom_both[2][0].reference(), "R4");
+  assert_eq!(bom.bom_front.len(), 3);
+  assert_eq!(bom.bom_back.len(), 1);
+  assert_eq!(bom.group_fields, ["Value"]);
+  let config = bom.generate_config_json().unwrap();
+  assert!(config.contains("\"group_fields\":[\"Value\"]"));
+
+  let json = bom.generate_generic_json().unwrap();
+  assert!(json.starts_with("{\"spec_version\":1,\"pcbdata\":{"));
+  assert!(json.contains("{\"ref\":\"R1\",\"val\":\"1k\",\"footprint\":\"\""));
+  assert!(
+    json.contains("\"extra_fields\":{\"MPN\":\"A\"},\"attr\":\"Virtual\"}")
+  );
+
+  #[cfg(feature = "pdf")]
+  {
+    let pdf = bom.generate_pdf(&[]).unwrap();
+    assert!(pdf.starts_with(b"%PDF-"));
+    assert!(pdf.windows(8).any(|x| x == b"(R1, R2)"));
+  }
+
+  let err = bom.group_bom(&refs, &["Foo"]).unwrap_err();
+  assert_eq!(err, GenerateError::UnknownField { name: "Foo".into() });
+}
+
+#[test]
+fn test_svg_path_builder() {
+  let path = SvgPathBuilder::new()
+    .move_to((0.0, -0.0))
+    .line_to((1.5, 0.0))
+    .arc_to((1.5, 2.0), -180.0)
+    .cubic_to((1.0, 2.0), (0.5, 1.0), (0.0, 1.0))
+    .arc_to((0.0, 0.5), 0.0)
+    .close()
+    .build();
+  assert_eq!(
+    path,
+    "M 0 0 L 1.5 0 A 1 1 0 0 0 1.5 2 C 1 2 0.5 1 0 1 L 0 0.5 Z"
+  );
+}
+
+#[test]
+fn test_board_outline_contours() {
+  let mut bom = InteractiveHtmlBom::new(
+    "Title",
+    "Company",
+    "Rev",
+    "Date",
+    (0.0, 0.0),
+    (10.0, 10.0),
+  );
+  bom.outlines.push(BoardOutline::new(
+    "M 0 0 H 10 V 10 H 0 Z",
+    &["m 2 2 h 2 v 2 h -2 z", "m 6 6 l 2 0 l 0 2"],
+    0.1,
+  ));
+  let json = bom.generate_pcbdata_json().unwrap();
+  for path in [
+    r#""svgpath":"M 0 0 H 10 V 10 H 0 Z""#,
+    r#""svgpath":"m 2 2 h 2 v 2 h -2 z""#,
+    r#""svgpath":"m 6 6 l 2 0 l 0 2""#,
+  ] {
+    assert!(json.contains(path), "{path} missing in {json}");
+  }
+}
+
+#[test]
+#[should_panic(expected = "Invalid arc angle")]
+#[cfg(debug_assertions)]
+fn test_svg_path_builder_full_circle_arc() {
+  SvgPathBuilder::new()
+    .move_to((0.0, 0.0))
+    .arc_to((0.0, 0.0), 360.0);
+}
+
+fn parse_pcbdata(bom: &InteractiveHtmlBom) -> serde_json::Value {
+  serde_json::from_str(&bom.generate_pcbdata_json().unwrap()).unwrap()
+}
+
+fn parse_config(bom: &InteractiveHtmlBom) -> serde_json::Value {
+  serde_json::from_str(&bom.generate_config_json().unwrap()).unwrap()
+}
+
+#[test]
+fn test_drawing_shapes() {
+  let mut bom = InteractiveHtmlBom::default();
+  bom.drawings.push(Drawing::arc(
+    DrawingLayer::SilkscreenFront,
+    (5.0, 5.0),
+    2.0,
+    0.0,
+    90.0,
+    0.1,
+  ));
+  bom.drawings.push(Drawing::arc(
+    DrawingLayer::SilkscreenFront,
+    (5.0, 5.0),
+    2.0,
+    90.0,
+    -270.0,
+    0.1,
+  ));
+  bom.drawings.push(Drawing::rect(
+    DrawingLayer::SilkscreenBack,
+    (5.0, 5.0),
+    (4.0, 2.0),
+    90.0,
+    0.1,
+    true,
+  ));
+  bom.drawings.push(Drawing::polygon_from_points(
+    DrawingLayer::FabricationFront,
+    [(0.0, 0.0), (10.0, 0.0), (5.0, 5.0)],
+    0.2,
+    false,
+  ));
+
+  let data = parse_pcbdata(&bom);
+  let polygon = |svgpath: &str, width: f64, filled: bool| {
+    serde_json::json!({
+      "type": "polygon",
+      "svgpath": svgpath,
+      "width": width,
+      "filled": filled,
+    })
+  };
+  assert_eq!(
+    data["drawings"]["silkscreen"]["F"],
+    serde_json::json!([
+      polygon("M 7 5 A 2 2 0 0 1 5 7", 0.1, false),
+      // Full circles are split into two halves.
+      polygon("M 5 7 A 2 2 0 0 0 5 3 A 2 2 0 0 0 5 7", 0.1, false),
+    ])
+  );
+  assert_eq!(
+    data["drawings"]["silkscreen"]["B"],
+    serde_json::json!([polygon("M 4 7 L 4 3 L 6 3 L 6 7 Z", 0.1, true)])
+  );
+  assert_eq!(
+    data["drawings"]["fabrication"]["F"],
+    serde_json::json!([polygon("M 0 0 L 10 0 L 5 5 Z", 0.2, false)])
+  );
+}
+
+#[test]
+fn test_drawing_texts() {
+  let mut bom = InteractiveHtmlBom::default();
+  bom.drawings.push(Drawing::text(
+    DrawingKind::Text,
+    DrawingLayer::FabricationFront,
+    "Ab\nC",
+    (5.0, 5.0),
+    (1.0, 1.5),
+    90.0,
+    0.15,
+  ));
+  bom.drawings.push(Drawing::text(
+    DrawingKind::ReferenceText,
+    DrawingLayer::SilkscreenBack,
+    "R1",
+    (2.0, 3.0),
+    (1.0, 1.0),
+    0.0,
+    0.1,
+  ));
+  bom.drawings.push(Drawing::text(
+    DrawingKind::ValueText,
+    DrawingLayer::SilkscreenFront,
+    "1k",
+    (2.0, 4.0),
+    (1.0, 1.0),
+    0.0,
+    0.1,
+  ));
+
+  let data = parse_pcbdata(&bom);
+  assert_eq!(
+    data["drawings"]["fabrication"]["F"][0],
+    serde_json::json!({
+      "pos": [5, 5],
+      "text": "Ab\nC",
+      "height": 1.5,
+      "width": 1,
+      "justify": [0, 0],
+      "thickness": 0.15,
+      "attr": [],
+      "angle": 90,
+    })
+  );
+  // Back side texts are mirrored, reference and value texts are marked.
+  let reference = &data["drawings"]["silkscreen"]["B"][0];
+  assert_eq!(reference["attr"], serde_json::json!(["mirrored"]));
+  assert_eq!(reference["ref"], 1);
+  let value = &data["drawings"]["silkscreen"]["F"][0];
+  assert_eq!(value["attr"], serde_json::json!([]));
+  assert_eq!(value["val"], 1);
+
+  // Glyphs are embedded for all used characters, and only for those.
+  let font_data = data["font_data"].as_object().unwrap();
+  let glyphs: Vec<_> = font_data.keys().map(String::as_str).collect();
+  assert_eq!(glyphs, ["1", "A", "C", "R", "b", "k"]);
+  assert_eq!(
+    data["font_data"]["1"],
+    serde_json::json!({
+      "w": 0.583333,
+      "l": [
+        [[0.125, -0.833333], [0.291667, -1], [0.291667, 0]],
+        [[0.125, 0], [0.458333, 0]],
+      ],
+    })
+  );
+}
+
+#[test]
+fn test_pad_shapes() {
+  let corners = [
+    PadCorner::Chamfer(0.2),
+    PadCorner::Round(0.1),
+    PadCorner::Sharp,
+    PadCorner::Sharp,
+  ];
+  let fpt = FootprintBuilder::new()
+    .at((5.0, 5.0))
+    .bbox((-1.0, -1.0), (1.0, 1.0))
+    .field("1k")
+    .pad(
+      PadBuilder::new(PadShape::Rect((1.0, 0.5)))
+        .at((1.0, 0.0))
+        .build(),
+    )
+    .pad(
+      PadBuilder::new(PadShape::Circle(1.0))
+        .tht((0.5, 0.5))
+        .at((2.0, 0.0))
+        .build(),
+    )
+    .pad(
+      PadBuilder::new(PadShape::Oval((2.0, 1.0)))
+        .tht((0.6, 1.2))
+        .at((3.0, 0.0))
+        .drill_offset((0.5, 0.0))
+        .build(),
+    )
+    .pad(
+      PadBuilder::new(PadShape::RoundRect((1.0, 0.5), 0.1))
+        .at((4.0, 0.0))
+        .build(),
+    )
+    .pad(
+      PadBuilder::new(PadShape::Corners((1.0, 0.5), corners))
+        .at((5.0, 0.0))
+        .build(),
+    )
+    .pad(
+      PadBuilder::new(PadShape::Custom("M 0 0 L 1 0 L 1 1 Z".into()))
+        .at((6.0, 0.0))
+        .build(),
+    )
+    .build();
+  let mut bom = InteractiveHtmlBom::default();
+  bom.fields = vec!["Value".into()];
+  bom.add_footprint(fpt);
+
+  let data = parse_pcbdata(&bom);
+  let pads = &data["footprints"][0]["pads"];
+  assert_eq!(
+    pads[0],
+    serde_json::json!({
+      "layers": ["F"],
+      "pos": [1, 0],
+      "angle": 0,
+      "shape": "rect",
+      "size": [1, 0.5],
+      "type": "smd",
+    })
+  );
+  assert_eq!(
+    pads[1],
+    serde_json::json!({
+      "layers": ["F", "B"],
+      "pos": [2, 0],
+      "angle": 0,
+      "shape": "circle",
+      "size": [1, 1],
+      "type": "th",
+      "drillsize": [0.5, 0.5],
+      "drillshape": "circle",
+    })
+  );
+  // The drill offset moves the pad, the drill stays in place.
+  assert_eq!(pads[2]["pos"], serde_json::json!([3.5, 0]));
+  assert_eq!(pads[2]["offset"], serde_json::json!([-0.5, 0]));
+  assert_eq!(pads[2]["shape"], "oval");
+  assert_eq!(pads[2]["drillshape"], "oblong");
+  assert_eq!(pads[3]["shape"], "roundrect");
+  assert_eq!(pads[3]["radius"], 0.1);
+  // Mixed corners are converted to a custom shape.
+  assert_eq!(pads[4]["shape"], "custom");
+  assert_eq!(
+    pads[4]["svgpath"],
+    "M -0.5 -0.05 L -0.3 -0.25 L 0.4 -0.25 A 0.1 0.1 0 0 1 0.5 -0.15 \
+     L 0.5 0.25 L -0.5 0.25 Z"
+  );
+  assert_eq!(pads[5]["shape"], "custom");
+  assert_eq!(pads[5]["svgpath"], "M 0 0 L 1 0 L 1 1 Z");
+}
+
+#[test]
+fn test_holes() {
+  let mut bom = InteractiveHtmlBom::default();
+  bom.holes.push(Hole::new((1.0, 1.0), 3.2));
+  bom
+    .holes
+    .push(Hole::slot((3.0, 1.0), (1.0, 3.0), 0.0, true));
+
+  // Holes are drawn as board edges, plated holes also as copper.
+  let data = parse_pcbdata(&bom);
+  let edges: Vec<_> = data["edges"]
+    .as_array()
+    .unwrap()
+    .iter()
+    .map(|x| (x["svgpath"].as_str().unwrap(), x["width"].as_f64().unwrap()))
+    .collect();
+  assert_eq!(
+    edges,
+    [
+      (
+        "M 1 -0.6 L 1 -0.6 A 1.6 1.6 0 0 1 1 2.6 L 1 2.6 A 1.6 1.6 0 0 1 \
+         1 -0.6 Z",
+        0.0
+      ),
+      (
+        "M 2.5 2 L 2.5 0 A 0.5 0.5 0 0 1 3.5 0 L 3.5 2 A 0.5 0.5 0 0 1 2.5 \
+         2 Z",
+        0.0
+      ),
+    ]
+  );
+  let copper =
+    serde_json::json!([{"start": [3, 2], "end": [3, 0], "width": 1}]);
+  assert_eq!(data["tracks"]["F"], copper);
+  assert_eq!(data["tracks"]["B"], copper);
+}
+
+#[test]
+fn test_zones() {
+  let mut bom = InteractiveHtmlBom::default();
+  bom.zones.push(Zone::with_holes(
+    Layer::Front,
+    "M 0 0 H 10 V 10 H 0 Z",
+    &["M 2 2 H 4 V 4 H 2 Z"],
+    Some("GND"),
+  ));
+  bom
+    .zones
+    .push(Zone::new(Layer::Back, "M 0 0 H 5 V 5 Z", None));
+
+  let data = parse_pcbdata(&bom);
+  assert_eq!(
+    data["zones"],
+    serde_json::json!({
+      "F": [{
+        "svgpath": "M 0 0 H 10 V 10 H 0 Z M 2 2 H 4 V 4 H 2 Z",
+        "fillrule": "evenodd",
+        "net": "GND",
+      }],
+      "B": [{"svgpath": "M 0 0 H 5 V 5 Z"}],
+    })
+  );
+  assert_eq!(data["nets"], serde_json::json!(["GND"]));
+}
+
+#[test]
+fn test_via_layer_spans() {
+  let mut bom = InteractiveHtmlBom::default();
+  for (span, x) in [((0, 3), 1.0), ((0, 1), 2.0), ((2, 3), 3.0), ((1, 2), 4.0)]
+  {
+    let via = Via::with_layer_span(span, 4, (x, 0.0), 0.6, 0.3, None);
+    bom.vias.push(via);
+  }
+  assert_eq!(bom.vias[0].layers(), [Layer::Front, Layer::Back]);
+  assert_eq!(bom.vias[1].layers(), [Layer::Front]);
+  assert_eq!(bom.vias[2].layers(), [Layer::Back]);
+  assert_eq!(bom.vias[3].layers(), []);
+
+  // Vias are only drawn on the outer layers they reach.
+  let data = parse_pcbdata(&bom);
+  let positions = |layer: &str| {
+    data["tracks"][layer]
+      .as_array()
+      .unwrap()
+      .iter()
+      .map(|x| x["start"][0].as_f64().unwrap())
+      .collect::<Vec<_>>()
+  };
+  assert_eq!(positions("F"), [1.0, 2.0]);
+  assert_eq!(positions("B"), [1.0, 3.0]);
+  assert_eq!(
+    data["tracks"]["F"][0],
+    serde_json::json!({
+      "start": [1, 0],
+      "end": [1, 0],
+      "width": 0.6,
+      "drillsize": 0.3,
+    })
+  );
+}
+
+#[test]
+fn test_aux_layers() {
+  let mut bom = InteractiveHtmlBom::default();
+  bom
+    .aux_layers
+    .push(AuxiliaryLayer::new("Keepout", "red", false));
+  bom
+    .aux_layers
+    .push(AuxiliaryLayer::new("Unused", "blue", true));
+  bom.drawings.push(Drawing::rect(
+    DrawingLayer::AuxiliaryBack(0),
+    (5.0, 5.0),
+    (1.0, 1.0),
+    0.0,
+    0.1,
+    false,
+  ));
+
+  let data = parse_pcbdata(&bom);
+  assert_eq!(data["drawings"]["aux0"]["F"], serde_json::json!([]));
+  assert_eq!(
+    data["drawings"]["aux0"]["B"][0]["svgpath"],
+    "M 4.5 4.5 L 5.5 4.5 L 5.5 5.5 L 4.5 5.5 Z"
+  );
+  // Only layers with drawings are listed in the settings.
+  assert_eq!(
+    parse_config(&bom)["extra_layers"],
+    serde_json::json!([{
+      "name": "aux0",
+      "title": "Keepout",
+      "color": "red",
+      "visible": false,
+    }])
+  );
+
+  bom.drawings[0] = Drawing::rect(
+    DrawingLayer::AuxiliaryFront(2),
+    (5.0, 5.0),
+    (1.0, 1.0),
+    0.0,
+    0.1,
+    false,
+  );
+  assert!(bom.generate_pcbdata_json().is_err());
+}
+
+#[test]
+fn test_dimensions() {
+  let mut bom = InteractiveHtmlBom::default();
+  bom.dimensions.push(Dimension::new(
+    Layer::Front,
+    (0.0, 10.0),
+    (10.0, 10.0),
+    -2.0,
+    None,
+    1.0,
+    0.1,
+  ));
+  bom.dimensions.push(Dimension::new(
+    Layer::Back,
+    (0.0, 0.0),
+    (0.0, 10.0),
+    2.0,
+    Some("10 mm"),
+    1.0,
+    0.1,
+  ));
+
+  // Drawn on the fabrication layer as extension lines, dimension line and
+  // arrows, plus the measured length or the custom text.
+  let data = parse_pcbdata(&bom);
+  let front = &data["drawings"]["fabrication"]["F"];
+  assert_eq!(
+    front[0]["svgpath"],
+    "M 0 10 L 0 12.5 M 10 10 L 10 12.5 M 0 12 L 10 12 \
+     M 1 11.666667 L 0 12 L 1 12.333333 M 9 11.666667 L 10 12 L 9 12.333333"
+  );
+  assert_eq!(
+    front[1],
+    serde_json::json!({
+      "pos": [5, 12.85],
+      "text": "10.00 mm",
+      "height": 1,
+      "width": 1,
+      "justify": [0, 0],
+      "thickness": 0.1,
+      "attr": [],
+      "angle": 0,
+    })
+  );
+  let back = &data["drawings"]["fabrication"]["B"];
+  assert_eq!(back[1]["text"], "10 mm");
+  assert_eq!(back[1]["pos"], serde_json::json!([2.85, 5]));
+  assert_eq!(back[1]["angle"], 90);
+  assert_eq!(back[1]["attr"], serde_json::json!(["mirrored"]));
+}
+
+#[test]
+fn test_drill_table() {
+  let mut bom = InteractiveHtmlBom::default();
+  bom
+    .vias
+    .push(Via::new([Layer::Front], (1.0, 0.0), 0.6, 0.3, None));
+  bom
+    .vias
+    .push(Via::new([Layer::Back], (2.0, 0.0), 0.6, 0.3, None));
+  bom.holes.push(Hole::new((3.0, 0.0), 3.2));
+  bom
+    .holes
+    .push(Hole::slot((4.0, 0.0), (3.0, 1.0), 90.0, true));
+  bom.fields = vec!["Value".into()];
+  bom.add_footprint(
+    FootprintBuilder::new()
+      .field("")
+      .pad(
+        PadBuilder::new(PadShape::Circle(1.0))
+          .tht((0.5, 0.5))
+          .build(),
+      )
+      .build(),
+  );
+  assert!(!bom.generate_html().unwrap().contains("Drill Table"));
+
+  // Drills are grouped by size and plating, smaller dimension first.
+  bom.drill_table = true;
+  let html = bom.generate_html().unwrap();
+  assert!(html.contains(
+    "<tbody>\n\
+     <tr><td>0.3</td><td>Yes</td><td>2</td></tr>\n\
+     <tr><td>0.5</td><td>Yes</td><td>1</td></tr>\n\
+     <tr><td>1 x 3</td><td>Yes</td><td>1</td></tr>\n\
+     <tr><td>3.2</td><td>No</td><td>1</td></tr>\n\
+     </tbody>"
+  ));
+}
+
+#[test]
+fn test_checkbox_defaults() {
+  let mut bom = InteractiveHtmlBom::default();
+  bom.fields = vec!["Value".into()];
+  bom.checkboxes = vec!["Sourced".into(), "Placed".into(), "<b>".into()];
+  for checked in [true, false, true] {
+    let mut fpt = FootprintBuilder::new().field("").build();
+    fpt.set_checked("Placed", checked);
+    fpt.set_checked("<b>", true);
+    fpt.set_checked("<b>", checked);
+    bom.add_footprint(fpt);
+  }
+
+  // Only checkboxes with checked footprints are listed.
+  let config = parse_config(&bom);
+  assert_eq!(config["checkboxes"], "Sourced,Placed,&lt;b&gt;");
+  assert_eq!(
+    config["checkbox_defaults"],
+    serde_json::json!({"Placed": "0,2", "&lt;b&gt;": "0,2"})
+  );
+}
+
+#[test]
+fn test_bom_columns() {
+  let mut bom = InteractiveHtmlBom::default();
+  bom.fields = vec!["Value".into(), "Footprint".into()];
+  let config = parse_config(&bom);
+  assert_eq!(config["sort_column"], serde_json::Value::Null);
+  assert_eq!(config["sort_order"], "asc");
+  assert_eq!(config["filter"], "");
+  assert_eq!(config["hidden_columns"], serde_json::json!([]));
+
+  bom.settings.sort_column = Some("Value".into());
+  bom.settings.sort_order = SortOrder::Descending;
+  bom.settings.filter = "10k".into();
+  bom.settings.hidden_columns = vec!["Footprint".into()];
+  let config = parse_config(&bom);
+  assert_eq!(config["sort_column"], "Value");
+  assert_eq!(config["sort_order"], "desc");
+  assert_eq!(config["filter"], "10k");
+  assert_eq!(config["hidden_columns"], serde_json::json!(["Footprint"]));
+  assert_eq!(config["fields"], serde_json::json!(["Value", "Footprint"]));
+}
+
+#[test]
+fn test_tooltips() {
+  let mut bom = InteractiveHtmlBom::default();
+  bom.fields = vec!["Value".into()];
+  bom.add_footprint(FootprintBuilder::new().field("").build());
+  let mut fpt = FootprintBuilder::new().field("").build();
+  fpt.add_tooltip_field("Description", "Resistor 1%");
+  fpt.add_tooltip_field("MPN", "RC0603");
+  bom.add_footprint(fpt);
+
+  // Listed by footprint ID, footprints without tooltips are omitted.
+  assert_eq!(
+    parse_pcbdata(&bom)["tooltips"],
+    serde_json::json!({
+      "1": [["Description", "Resistor 1%"], ["MPN", "RC0603"]],
+    })
+  );
+}
+
+#[cfg(feature = "kicad")]
+#[test]
+fn test_kicad_netlist() {
+  let mut bom = InteractiveHtmlBom::default();
+  bom.fields = vec!["Value".into(), "MPN".into(), "Datasheet".into()];
+  let footprint = FootprintBuilder::new()
+    .field("?")
+    .field("?")
+    .field("?")
+    .build();
+  let refs = [
+    RefMap::new("R1", bom.add_footprint(footprint.clone())),
+    RefMap::new("C1", bom.add_footprint(footprint)),
+  ];
+  let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<export version="E">
+  <design>
+    <source>/tmp/test.kicad_sch</source>
+  </design>
+  <components>
+    <comp ref="R1">
+      <value>10k &amp; 1%</value>
+      <footprint>Resistor_SMD:R_0603_1608Metric</footprint>
+      <datasheet>~</datasheet>
+      <fields>
+        <field name="MPN">RC0603FR-0710KL</field>
+      </fields>
+      <libsource lib="Device" part="R" description="Resistor"/>
+      <property name="Sheetname" value="Root"/>
+    </comp>
+    <comp ref="C1">
+      <value>100n</value>
+      <property name="MPN" value='GRM188R71H104KA93D'/>
+    </comp>
+    <comp ref="U1">
+      <value>MCU</value>
+    </comp>
+  </components>
+</export>
+"#;
+  let unknown = bom.merge_kicad_netlist(xml, &refs).unwrap();
+  assert_eq!(unknown, vec!["U1".to_string()]);
+  assert_eq!(
+    bom.footprints[0].fields(),
+    ["10k & 1%", "RC0603FR-0710KL", ""]
+  );
+  assert_eq!(
+    bom.footprints[1].fields(),
+    ["100n", "GRM188R71H104KA93D", "?"]
+  );
+
+  let err = bom
+    .merge_kicad_netlist("<export>\n<components>\n</export>", &refs)
+    .unwrap_err();
+  assert_eq!(
+    err,
+    GenerateError::InvalidInput {
+      format: InputFormat::KicadNetlist,
+      reason: "mismatched closing tag at line 3".into(),
+    }
+  );
+  assert_eq!(
+    err.to_string(),
+    "Invalid KiCad netlist: mismatched closing tag at line 3."
+  );
+
+  // Invalid references are detected before any field is merged.
+  let invalid = [
+    RefMap::new("R1", FootprintId::from(1)),
+    RefMap::new("R2", FootprintId::from(9)),
+  ];
+  let err = bom.merge_kicad_netlist(xml, &invalid).unwrap_err();
+  assert_eq!(err, GenerateError::InvalidFootprintId { row: 1, index: 9 });
+  assert_eq!(
+    bom.footprints[1].fields(),
+    ["100n", "GRM188R71H104KA93D", "?"]
+  );
+}
+
+#[cfg(feature = "gerber")]
+#[test]
+fn test_gerber_excellon() {
+  let mut bom = InteractiveHtmlBom::default();
+  let edges = "%FSLAX46Y46*%
+%MOMM*%
+%ADD10C,0.100000*%
+D10*
+X0Y0D02*
+X10000000Y0D01*
+X10000000Y-5000000D01*
+G75*
+G03*
+X0Y-5000000I-5000000J0D01*
+G01*
+X0Y0D01*
+M02*
+";
+  bom.import_gerber(DrawingLayer::Edge, edges).unwrap();
+  assert_eq!(bom.outlines.len(), 1);
+
+  let copper = "%FSLAX46Y46*%
+%MOMM*%
+%AMRoundRect*
+0 Rectangle with rounded corners*
+21,1,$1,$2-$3-$3,0,0,0*
+21,1,$1-$3-$3,$2,0,0,0*
+1,1,$3+$3,$1/2-$3,$2/2-$3*
+1,1,$3+$3,-$1/2+$3,$2/2-$3*
+1,1,$3+$3,-$1/2+$3,-$2/2+$3*
+1,1,$3+$3,$1/2-$3,-$2/2+$3*%
+%ADD10RoundRect,1.0X0.8X0.2*%
+%ADD11O,1.2X2.0*%
+%LPD*%
+D10*
+X2000000Y-1000000D03*
+D11*
+X4000000Y-1000000D03*
+G36*
+X1000000Y-3000000D02*
+X3000000Y-3000000D01*
+X3000000Y-4000000D01*
+X1000000Y-3000000D01*
+G37*
+M02*
+";
+  bom
+    .import_gerber(DrawingLayer::CopperFront, copper)
+    .unwrap();
+  assert_eq!(bom.drawings.len(), 3);
+
+  let drill = "M48
+; DRILL file
+FMAT,2
+METRIC,TZ
+T1C0.800
+T2C1.000
+%
+G90
+G05
+T1
+X2.0Y-1.0
+X4.0Y-1.0
+T2
+X6.0Y-2.0G85X6.0Y-3.0
+M30
+";
+  bom.import_excellon(drill, true).unwrap();
+  assert_eq!(bom.holes.len(), 3);
+  bom.generate_html().unwrap();
+
+  let err = bom
+    .import_gerber(DrawingLayer::CopperFront, "%FSLAX46Y46*%\nD99*\nX0Y0D03*\n")
+    .unwrap_err();
+  assert_eq!(
+    err.to_string(),
+    "Invalid Gerber file: undefined aperture D99."
+  );
+
+  let err = bom
+    .import_gerber(
+      DrawingLayer::CopperFront,
+      "%FSLAX46Y46*%\n%A\u{e9}0C,0.1*%\n",
+    )
+    .unwrap_err();
+  assert_eq!(
+    err.to_string(),
+    "Invalid Gerber file: invalid format \"A\u{e9}0C,0.1\"."
+  );
+}
+
+#[cfg(feature = "pnp")]
+#[test]
+fn test_pick_and_place() {
+  let mut bom = InteractiveHtmlBom::default();
+  bom.fields = vec!["Value".into(), "Footprint".into(), "MPN".into()];
+  let csv = "Altium Designer Pick and Place Locations
+Units used in this file: mm
+
+\"Designator\",\"Comment\",\"Layer\",\"Footprint\",\"Center-X(mm)\",\
+\"Center-Y(mm)\",\"Rotation\"
+\"R1\",\"10k\",\"TopLayer\",\"R_0603_1608Metric\",\"10.0mm\",\"20.0mm\",\"90\"
+\"R2\",\"10k\",\"BottomLayer\",\"R_0603_1608Metric\",\"30.0mm\",\"20.0mm\",\"0\"
+\"U1\",\"MCU, 32 bit\",\"TopLayer\",\"QFP-48\",\"20.0mm\",\"5.0mm\",\"45\"
+";
+  let refs = bom.import_pick_and_place(csv, (5.0, 5.0)).unwrap();
+  let refs: Vec<_> = refs.iter().map(|x| x.reference()).collect();
+  assert_eq!(refs, ["R1", "R2", "U1"]);
+  assert_eq!(bom.footprints[0].fields(), ["10k", "R_0603_1608Metric", ""]);
+  assert_eq!(bom.footprints[1].layer(), &Layer::Back);
+  assert_eq!(bom.footprints[2].pos(), Point::new(20.0, -5.0));
+  assert_eq!(bom.footprints[2].angle(), 45.0);
+  assert_eq!(bom.bom_both.len(), 2);
+  assert_eq!(bom.bom_front.len(), 2);
+  assert_eq!(bom.bom_back.len(), 1);
+  assert!(bom.top_right().x > 30.0);
+  assert!(bom.bottom_left().y < -20.0);
+  bom.generate_html().unwrap();
+
+  let err = bom
+    .import_pick_and_place(
+      "Ref,Val,Package,PosX,PosY,Rot,Side\nC1,,,1,a,0,top",
+      (1.0, 1.0),
+    )
+    .unwrap_err();
+  assert_eq!(
+    err.to_string(),
+    "Invalid pick and place file: invalid Y \"a\" at line 2."
+  );
+
+  let err = bom
+    .import_pick_and_place("Ref,PosX,PosY,Rot\nC1,1,1,90mm", (1.0, 1.0))
+    .unwrap_err();
+  assert_eq!(
+    err.to_string(),
+    "Invalid pick and place file: invalid rotation \"90mm\" at line 2."
+  );
+
+  // Size codes are only recognized as whole tokens.
+  for (package, x) in [("C0805", 11.18), ("QFN-2010X", 15.0)] {
+    let mut bom = InteractiveHtmlBom::default();
+    let csv = format!("Ref,Package,PosX,PosY\nU1,{},10,0", package);
+    bom.import_pick_and_place(&csv, (6.0, 8.0)).unwrap();
+    assert_eq!((bom.top_right().x * 100.0).round() / 100.0, x);
+  }
+}
+
+#[cfg(feature = "librepcb")]
+#[test]
+fn test_librepcb_project() {
+  let dir = std::env::temp_dir().join("interactive-html-bom-test-librepcb");
+  let files = [
+    (
+      "project/metadata.lp",
+      "(librepcb_project_metadata\n (name \"Blinky\")\n (author \"ACME\")\n \
+       (version \"v2\")\n (created 2024-05-01T12:00:00Z)\n)\n",
+    ),
+    (
+      "boards/boards.lp",
+      "(librepcb_boards\n (board \"boards/default/board.lp\")\n)\n",
+    ),
+    (
+      "circuit/circuit.lp",
+      r#"(librepcb_circuit
+ (net 8f1c (auto false) (name "GND") (netclass 1))
+ (component c1 (lib_component cmp1) (name "R1") (value "{{RESISTANCE}}")
+  (attribute "RESISTANCE" (type resistance) (unit kiloohm) (value "10"))
+  (signal s1 (net 8f1c))
+  (signal s2 (net none))
+ )
+)
+"#,
+    ),
+    (
+      "library/cmp/cmp1/component.lp",
+      "(librepcb_component cmp1\n (signal s1 (name \"1\"))\n \
+       (signal s2 (name \"2\"))\n)\n",
+    ),
+    (
+      "library/dev/dev1/device.lp",
+      "(librepcb_device dev1\n (component cmp1)\n (package pac1)\n \
+       (pad pp1 (optional false) (signal s1))\n \
+       (pad pp2 (optional false) (signal s2))\n)\n",
+    ),
+    (
+      "library/pac/pac1/package.lp",
+      r#"(librepcb_package pac1
+ (name "R0603")
+ (pad pp1 (name "1"))
+ (pad pp2 (name "2"))
+ (footprint fp1 (name "default")
+  (pad fpp1 (side top) (shape roundrect) (position -0.8 0.0) (rotation 0.0)
+   (size 0.8 0.9) (radius 0.5) (package_pad pp1)
+  )
+  (pad fpp2 (side top) (shape roundrect) (position 0.8 0.0) (rotation 0.0)
+   (size 0.8 0.9) (radius 0.0) (package_pad pp2)
+  )
+  (polygon l1 (layer top_documentation) (width 0.1) (fill false)
+   (vertex (position -0.8 0.4) (angle 0.0))
+   (vertex (position 0.8 0.4) (angle 0.0))
+  )
+  (circle c1 (layer top_legend) (width 0.1) (fill true) (diameter 0.2)
+   (position -1.5 0.0)
+  )
+ )
+)
+"#,
+    ),
+    (
+      "boards/default/board.lp",
+      r#"(librepcb_board b1
+ (name "default")
+ (device c1 (lib_device dev1) (lib_footprint fp1) (lib_3d_model none)
+  (position 10.0 -5.0) (rotation 90.0) (flip true) (lock false) (glue true)
+  (stroke_text t1 (layer bot_names) (height 1.0) (stroke_width 0.2)
+   (letter_spacing auto) (line_spacing auto) (align center bottom)
+   (position 10.0 -3.0) (rotation 0.0) (auto_rotate true) (mirror true)
+   (lock false) (value "{{NAME}}")
+  )
+ )
+ (netsegment n1 (net 8f1c)
+  (via v1 (from top_cu) (to bot_cu) (position 15.0 -5.0) (size 0.7)
+   (drill 0.3) (exposure off)
+  )
+  (trace t1 (layer bot_cu) (width 0.25)
+   (from (device c1) (pad fpp1)) (to (via v1))
+  )
+ )
+ (polygon o1 (layer brd_outlines) (width 0.0) (fill false) (grab_area false)
+  (vertex (position 0.0 0.0) (angle 0.0))
+  (vertex (position 20.0 0.0) (angle 0.0))
+  (vertex (position 20.0 -10.0) (angle 0.0))
+  (vertex (position 0.0 -10.0) (angle 0.0))
+  (vertex (position 0.0 0.0) (angle 0.0))
+ )
+ (hole h1 (diameter 3.0) (stop_mask auto) (lock false)
+  (vertex (position 2.0 -2.0) (angle 0.0))
+ )
+)
+"#,
+    ),
+  ];
+  for (path, content) in files {
+    let path = dir.join(path);
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(path, content).unwrap();
+  }
+
+  let bom = InteractiveHtmlBom::from_librepcb_project(&dir, None).unwrap();
+  assert_eq!(bom.title(), "Blinky");
+  assert_eq!(bom.date(), "2024-05-01");
+  assert_eq!(bom.fields, ["Value", "Package"]);
+  assert_eq!(bom.footprints.len(), 1);
+  let fpt = &bom.footprints[0];
+  assert_eq!(fpt.layer(), &Layer::Back);
+  assert_eq!(fpt.pos(), Point::new(10.0, 5.0));
+  assert_eq!(fpt.fields(), ["10kΩ", "R0603"]);
+  assert_eq!(fpt.pads().len(), 2);
+  assert_eq!(fpt.pads()[0].layers(), [Layer::Back]);
+  assert_eq!(fpt.pads()[0].shape(), &PadShape::RoundRect((0.8, 0.9), 0.2));
+  assert_eq!(fpt.pads()[0].net(), Some("GND"));
+  assert_eq!(fpt.pads()[0].pin_name(), Some("1"));
+  assert!(fpt.pads()[0].pin1());
+  assert_eq!(fpt.pads()[1].net(), None);
+  assert_eq!(fpt.drawings().len(), 3);
+  assert_eq!(bom.tracks.len(), 1);
+  assert_eq!(bom.tracks[0].start(), fpt.pads()[0].pos());
+  assert_eq!(bom.vias.len(), 1);
+  assert_eq!(bom.outlines.len(), 1);
+  assert_eq!(bom.holes.len(), 1);
+  assert_eq!(bom.bom_back.len(), 1);
+  bom.generate_html().unwrap();
+
+  let err =
+    InteractiveHtmlBom::from_librepcb_project(&dir, Some("panel")).unwrap_err();
+  assert_eq!(
+    err.to_string(),
+    "Invalid LibrePCB project: board \"panel\" not found."
+  );
+  std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[cfg(feature = "eagle")]
+#[test]
+fn test_eagle_board() {
+  let xml = r#"<?xml version="1.0" encoding="utf-8"?>
+<!DOCTYPE eagle SYSTEM "eagle.dtd">
+<eagle version="9.6.2">
+<drawing>
+<settings><setting alwaysvectorfont="no"/></settings>
+<layers><layer number="1" name="Top" color="4" fill="1" visible="yes"/></layers>
+<board>
+<plain>
+<wire x1="0" y1="0" x2="20" y2="0" width="0" layer="20"/>
+<wire x1="20" y1="0" x2="20" y2="10" width="0" layer="20"/>
+<wire x1="20" y1="10" x2="0" y2="10" width="0" layer="20" curve="-90"/>
+<wire x1="0" y1="10" x2="0" y2="0" width="0" layer="20"/>
+<text x="1" y="1" size="1" layer="21">Rev A</text>
+<hole x="2" y="2" drill="3.2"/>
+</plain>
+<libraries>
+<library name="rcl">
+<packages>
+<package name="R0603">
+<smd name="1" x="-0.8" y="0" dx="0.8" dy="0.9" layer="1" roundness="100"/>
+<smd name="2" x="0.8" y="0" dx="0.8" dy="0.9" layer="1"/>
+<wire x1="-0.8" y1="0.4" x2="0.8" y2="0.4" width="0.1" layer="51"/>
+<text x="0" y="1" size="1" layer="25" align="bottom-center">&gt;NAME</text>
+<text x="0" y="-1" size="1" layer="27" align="top-center">&gt;VALUE</text>
+</package>
+<package name="PINHD-1X1">
+<pad name="1" x="0" y="0" drill="1" diameter="1.7" shape="offset" rot="R90"/>
+</package>
+</packages>
+</library>
+</libraries>
+<elements>
+<element name="R1" library="rcl" package="R0603" value="10k"
+ x="5" y="5" rot="R90"/>
+<element name="R2" library="rcl" package="R0603" value="10k"
+ x="10" y="5" rot="MR0" populate="no">
+<attribute name="NAME" x="10" y="7" size="1" layer="26" rot="MR0"/>
+</element>
+<element name="J1" library="rcl" package="PINHD-1X1" value="" x="15" y="5"/>
+</elements>
+<signals>
+<signal name="GND">
+<contactref element="R1" pad="1"/>
+<contactref element="J1" pad="1"/>
+<wire x1="5" y1="4.2" x2="15" y2="4.2" width="0.25" layer="1" curve="90"/>
+<via x="12" y="2" extent="1-16" drill="0.3"/>
+</signal>
+</signals>
+</board>
+</drawing>
+</eagle>
+"#;
+  let bom = InteractiveHtmlBom::from_eagle_board(xml).unwrap();
+  assert_eq!(bom.fields, ["Value", "Package"]);
+  assert_eq!(bom.footprints.len(), 3);
+  let r1 = &bom.footprints[0];
+  assert_eq!(r1.pos(), Point::new(5.0, -5.0));
+  assert_eq!(r1.angle(), 90.0);
+  assert_eq!(r1.fields(), ["10k", "R0603"]);
+  assert_eq!(r1.pads()[0].shape(), &PadShape::Oval((0.8, 0.9)));
+  assert_eq!(r1.pads()[0].net(), Some("GND"));
+  assert_eq!(r1.pads()[1].net(), None);
+  assert_eq!(r1.drawings().len(), 3);
+  let r2 = &bom.footprints[1];
+  assert_eq!(r2.layer(), &Layer::Back);
+  assert!(!r2.mount());
+  assert_eq!(r2.pads()[0].layers(), [Layer::Back]);
+  assert_eq!(r2.pads()[0].pos(), Point::new(10.8, -5.0));
+  let j1 = &bom.footprints[2];
+  assert_eq!(j1.pads()[0].layers(), [Layer::Front, Layer::Back]);
+  assert_eq!(j1.pads()[0].shape(), &PadShape::Oval((3.4, 1.7)));
+  assert_eq!(bom.tracks.len(), 6);
+  assert_eq!(bom.vias.len(), 1);
+  assert_eq!(bom.outlines.len(), 1);
+  assert_eq!(bom.holes.len(), 1);
+  assert_eq!(bom.drawings.len(), 1);
+  assert_eq!(bom.drawings[0].kind(), &DrawingKind::Text);
+  assert_eq!(bom.drawings[0].layer(), &DrawingLayer::SilkscreenFront);
+  assert_eq!(bom.bom_both.len(), 3);
+  assert!(bom.generate_pcbdata_json().unwrap().contains("\"Rev A\""));
+  bom.generate_html().unwrap();
+
+  let err = InteractiveHtmlBom::from_eagle_board(
+    &xml.replace("package=\"PINHD-1X1\"", "package=\"X\""),
+  )
+  .unwrap_err();
+  assert_eq!(
+    err.to_string(),
+    "Invalid Eagle board: element \"J1\": package \"X\" not found."
+  );
+}
+
+#[cfg(feature = "generic")]
+#[test]
+fn test_generic_json_import() {
+  let mut bom = InteractiveHtmlBom::new(
+    "Title & Co",
+    "Company",
+    "Rev",
+    "Date",
+    (0.0, 0.0),
+    (20.0, 10.0),
+  );
+  bom.fields = vec!["Value".into(), "Footprint".into(), "MPN".into()];
+  bom.outlines.push(BoardOutline::new(
+    "M 0 0 L 20 0 L 20 10 L 0 10 Z",
+    &["M 5 5 L 6 5 L 6 6 Z"],
+    0.1,
+  ));
+  bom.drawings.push(Drawing::text(
+    DrawingKind::Text,
+    DrawingLayer::SilkscreenBack,
+    "Rev A",
+    (3.0, 4.0),
+    (1.0, 1.0),
+    90.0,
+    0.1,
+  ));
+  let gnd = bom.add_net("GND");
+  let corners = [
+    PadCorner::Chamfer(0.25),
+    PadCorner::Sharp,
+    PadCorner::Sharp,
+    PadCorner::Sharp,
+  ];
+  let mut fpt = FootprintBuilder::new()
+    .at((5.0, 5.0))
+    .angle(90.0)
+    .bbox((-2.0, -1.0), (2.0, 1.0))
+    .field("1k")
+    .field("R0603")
+    .field("<A>")
+    .pad(
+      PadBuilder::new(PadShape::Corners((1.0, 0.5), corners))
+        .at((4.0, 5.0))
+        .net_id(gnd.clone())
+        .pin1()
+        .build(),
+    )
+    .pad(
+      PadBuilder::new(PadShape::Oval((2.0, 1.0)))
+        .tht((0.6, 0.6))
+        .at((6.0, 5.0))
+        .angle(90.0)
+        .drill_offset((0.5, 0.0))
+        .pin(Some("2"), None)
+        .build(),
+    )
+    .build();
+  fpt.add_drawing(Drawing::rect(
+    DrawingLayer::SilkscreenFront,
+    (5.0, 5.0),
+    (3.0, 1.5),
+    0.0,
+    0.1,
+    false,
+  ));
+  let id = bom.add_footprint(fpt);
+  let refs = [RefMap::new("R1", id)];
+  bom.group_bom(&refs, &["Value", "Footprint"]).unwrap();
+  let mut track = Track::new(Layer::Front, (4.0, 5.0), (4.0, 8.0), 0.2, None);
+  track.set_net_id(Some(gnd.clone()));
+  bom.tracks.push(track);
+  bom.vias.push(Via::new(
+    [Layer::Front, Layer::Back],
+    (4.0, 8.0),
+    0.6,
+    0.3,
+    Some("GND"),
+  ));
+  bom.zones.push(Zone::with_holes(
+    Layer::Back,
+    "M 10 1 L 19 1 L 19 9 Z",
+    &["M 15 3 L 16 3 L 16 4 Z"],
+    Some("GND"),
+  ));
+
+  // Exporting the imported data results in the same file again.
+  let json = bom.generate_generic_json().unwrap();
+  assert!(json.contains("\"title\":\"Title & Co\""));
+  assert!(json.contains("\"extra_fields\":{\"MPN\":\"<A>\"}"));
+  let imported = InteractiveHtmlBom::from_generic_json(&json).unwrap();
+  assert_eq!(imported.title(), "Title & Co");
+  assert_eq!(imported.fields, ["Value", "Footprint", "MPN"]);
+  assert_eq!(imported.footprints[0].fields(), ["1k", "R0603", "<A>"]);
+  assert_eq!(imported.footprints[0].pads()[1].pos(), Point::new(6.0, 5.0));
+  assert_eq!(imported.vias.len(), 1);
+  assert_eq!(imported.vias[0].layers(), [Layer::Front, Layer::Back]);
+  assert_eq!(imported.bom_front.len(), 1);
+  assert_eq!(imported.generate_generic_json().unwrap(), json);
+
+  // Upstream-style shapes are converted to SVG paths.
+  let json = r#"{"spec_version": 1, "pcbdata": {
+    "edges_bbox": {"minx": 0, "miny": 0, "maxx": 10, "maxy": 10},
+    "edges": [
+      {"type": "segment", "start": [0, 0], "end": [10, 0], "width": 0.1},
+      {"type": "arc", "start": [5, 5], "radius": 5, "startangle": 270,
+        "endangle": 0, "width": 0.1}
+    ],
+    "drawings": {"silkscreen": {"F": [], "B": []},
+      "fabrication": {"F": [], "B": []}},
+    "footprints": [{"layer": "F", "drawings": [],
+      "bbox": {"pos": [5, 5], "relpos": [-1, -1], "size": [2, 2]},
+      "pads": [{"layers": ["F"], "pos": [5, 5], "shape": "custom",
+        "polygons": [[[0, 0], [1, 0], [1, 1]]], "type": "smd"}]}]
+    }, "components": [{"ref": "U1", "val": "X &amp; Y", "footprint": "Y",
+      "layer": "F", "extra_fields": {}}]}"#;
+  let imported = InteractiveHtmlBom::from_generic_json(json).unwrap();
+  assert_eq!(imported.footprints[0].fields(), ["X &amp; Y", "Y"]);
+  assert_eq!(imported.drawings[0].svgpath(), "M 0 0 L 10 0");
+  assert_eq!(imported.drawings[1].svgpath(), "M 5 0 A 5 5 0 0 1 10 5");
+  assert_eq!(
+    imported.footprints[0].pads()[0].shape(),
+    &PadShape::Custom("M 0 0 L 1 0 L 1 1 Z".into())
+  );
+
+  let err = InteractiveHtmlBom::from_generic_json(
+    &json.replace("\"spec_version\": 1", "\"spec_version\": 2"),
+  )
+  .unwrap_err();
+  assert_eq!(
+    err.to_string(),
+    "Invalid generic JSON: unsupported spec_version 2."
+  );
+
+  // Contours after the first are holes with even-odd filling.
+  let zones = [
+    ("", 0),
+    ("M 0 0 h 9 v 9 Z m 1 1 h 1 v 1 z M 5 5 H 6 V 6 Z", 2),
+    ("\u{e9}", 0),
+    (" m 0 0 l 5 0 l 0 5 z m 1 1 l 1 0 l 0 1 z", 1),
+  ];
+  for (svgpath, holes) in zones {
+    let zone = format!(
+      r#""zones": {{"F": [{{"svgpath": "{}", "fillrule": "evenodd"}}],
+        "B": []}}, "footprints""#,
+      svgpath
+    );
+    let json = json.replace("\"footprints\"", &zone);
+    let imported = InteractiveHtmlBom::from_generic_json(&json).unwrap();
+    assert_eq!(imported.zones[0].holes().len(), holes);
+  }
+  let json = json.replace(
+    "\"footprints\"",
+    r#""zones": {"F": [{"svgpath": "M 0 0 h 9 v 9 Z m 1 1 h 1 v 1 z m 1 0",
+      "fillrule": "evenodd"}], "B": []}, "footprints""#,
+  );
+  let imported = InteractiveHtmlBom::from_generic_json(&json).unwrap();
+  assert_eq!(imported.zones[0].svgpath(), "M 0 0 h 9 v 9 Z");
+  assert_eq!(imported.zones[0].holes(), ["M 1 1 h 1 v 1 z", "M 2 1"]);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_serde_round_trip() {
+  let mut bom = InteractiveHtmlBom::new(
+    "Test Title",
+    "Test Company",
+    "Test Revision",
+    "Test Date",
+    (0.0, 0.0),
+    (10.0, 10.0),
+  );
+  let gnd = bom.add_net("GND");
+  bom.tracks.push(Track::with_net_id(
+    Layer::Front,
+    (0.0, 0.0),
+    (1.0, 0.0),
+    0.2,
+    Some(gnd.clone()),
+  ));
+  bom.vias.push(Via::with_net_id(
+    [Layer::Front, Layer::Back],
+    (1.0, 0.0),
+    0.6,
+    0.3,
+    Some(gnd.clone()),
+  ));
+  bom.zones.push(Zone::with_net_id(
+    Layer::Back,
+    "M 0 0 H 1 V 1 Z",
+    Some(gnd.clone()),
+  ));
+  bom.drawings.push(Drawing::polygon_with_net_id(
+    DrawingLayer::CopperFront,
+    "M 0 0 L 1 1",
+    0.1,
+    false,
+    Some(gnd),
+  ));
+  assert_eq!(bom.vias[0].net(), Some("GND"));
+  assert_eq!(bom.drawings[0].net(), Some("GND"));
+
+  // All other kinds of data and settings are serialized too.
+  bom.fields = vec!["Value".into()];
+  bom.checkboxes = vec!["Placed".into()];
+  bom.drill_table = true;
+  bom.settings.theme.pad = Some("#c0c0c0".into());
+  bom.settings.hidden_columns = vec!["Value".into()];
+  bom.settings.sort_order = SortOrder::Descending;
+  bom
+    .aux_layers
+    .push(AuxiliaryLayer::new("Keepout", "red", true));
+  bom.outlines.push(BoardOutline::new(
+    "M 0 0 H 10 V 10 H 0 Z",
+    &["M 4 4 h 2 v 2 h -2 z"],
+    0.1,
+  ));
+  bom
+    .holes
+    .push(Hole::slot((2.0, 2.0), (1.0, 2.0), 30.0, true));
+  bom.dimensions.push(Dimension::new(
+    Layer::Back,
+    (0.0, 0.0),
+    (10.0, 0.0),
+    2.0,
+    Some("10 mm"),
+    1.0,
+    0.1,
+  ));
+  bom.drawings.push(Drawing::text(
+    DrawingKind::Text,
+    DrawingLayer::AuxiliaryFront(0),
+    "Rev A",
+    (5.0, 5.0),
+    (1.0, 1.0),
+    90.0,
+    0.1,
+  ));
+  let corners = [
+    PadCorner::Chamfer(0.2),
+    PadCorner::Round(0.1),
+    PadCorner::Sharp,
+    PadCorner::Sharp,
+  ];
+  let mut fpt = FootprintBuilder::new()
+    .at((5.0, 5.0))
+    .angle(45.0)
+    .bbox((-1.0, -1.0), (1.0, 1.0))
+    .field("1k")
+    .pad(
+      PadBuilder::new(PadShape::Corners((1.0, 0.5), corners))
+        .net("GND")
+        .pin(Some("1"), Some("A"))
+        .pin1()
+        .build(),
+    )
+    .pad(
+      PadBuilder::new(PadShape::Oval((2.0, 1.0)))
+        .tht((0.5, 1.0))
+        .drill_offset((0.25, 0.0))
+        .build(),
+    )
+    .build();
+  fpt.add_drawing(Drawing::arc(
+    DrawingLayer::SilkscreenBack,
+    (0.0, 0.0),
+    1.0,
+    0.0,
+    180.0,
+    0.1,
+  ));
+  fpt.set_checked("Placed", true);
+  fpt.add_tooltip_field("Description", "Resistor");
+  let id = bom.add_footprint(fpt);
+  bom.add_single(LayerView::Front, "R1", id);
+
+  let json = serde_json::to_string(&bom).unwrap();
+  assert!(!json.contains("net_index"));
+  let mut copy: InteractiveHtmlBom = serde_json::from_str(&json).unwrap();
+  assert_eq!(copy, bom);
+  assert_eq!(copy.generate_html().unwrap(), bom.generate_html().unwrap());
+
+  // The net lookup is rebuilt, so existing nets are not added again.
+  assert_eq!(copy.add_net("GND").name(), "GND");
+  copy.add_net("VCC");
+  let json = copy.generate_pcbdata_json().unwrap();
+  assert!(json.contains("\"nets\":[\"GND\",\"VCC\"]"));
+}
+
+#[cfg(feature = "pdf")]
+#[test]
+fn test_pdf() {
+  let mut bom = InteractiveHtmlBom::new(
+    "Test Title",
+    "Test Company",
+    "Test Revision",
+    "Test Date",
+    (0.0, 0.0),
+    (100.0, 50.0),
+  );
+  bom.fields = vec!["Value".into()];
+  bom
+    .outlines
+    .push(BoardOutline::new("M 0 0 H 100 V 50 H 0 Z", &[], 0.1));
+  let front = bom.add_footprint(
+    FootprintBuilder::new()
+      .at((10.0, 10.0))
+      .bbox((-1.0, -1.0), (1.0, 1.0))
+      .field("1k")
+      .build(),
+  );
+  let back = bom.add_footprint(
+    FootprintBuilder::new()
+      .layer(Layer::Back)
+      .at((90.0, 40.0))
+      .bbox((-1.0, -1.0), (1.0, 1.0))
+      .field("2k")
+      .build(),
+  );
+  bom.add_single(LayerView::FrontAndBack, "R1", front);
+  bom.add_single(LayerView::FrontAndBack, "R2", back);
+
+  let pdf = bom.generate_pdf(&[]).unwrap();
+  assert!(pdf.windows(9).any(|x| x == b"/Count 1 "));
+
+  let pdf = bom.generate_pdf(&[Layer::Front, Layer::Back]).unwrap();
+  let pdf = String::from_utf8_lossy(&pdf).to_string();
+  assert!(pdf.starts_with("%PDF-"));
+  assert!(pdf.contains("/Count 3 "));
+  let pages: Vec<&str> = pdf
+    .split("\nstream\n")
+    .skip(1)
+    .map(|x| x.split("endstream").next().unwrap())
+    .collect();
+  assert_eq!(pages.len(), 3);
+
+  // BOM table.
+  for text in ["Test Title", "Revision: Test Revision", "Qty", "Value"] {
+    assert!(pages[0].contains(&format!("({}) Tj", text)));
+  }
+  assert!(pages[0].contains("(R1) Tj"));
+  assert!(pages[0].contains("(1k) Tj"));
+  assert!(pages[0].contains("(R2) Tj"));
+  assert!(pages[0].contains("(2k) Tj"));
+
+  // Front side, with the outline starting at the left page margin.
+  assert!(pages[1].contains("(Test Title \\(Front\\)) Tj"));
+  assert!(pages[1].contains("(R1) Tj"));
+  assert!(!pages[1].contains("(R2) Tj"));
+  assert!(pages[1].contains(" 40.00 "));
+  assert_eq!(pages[1].matches(" h S\n").count(), 2);
+
+  // Back side, mirrored.
+  assert!(pages[2].contains("(Test Title \\(Back\\)) Tj"));
+  assert!(pages[2].contains("(R2) Tj"));
+  assert!(!pages[2].contains("(R1) Tj"));
+  assert!(pages[2].contains(" 555.28 "));
+  assert_eq!(pages[2].matches(" h S\n").count(), 2);
 }